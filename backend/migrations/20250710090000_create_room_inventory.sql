@@ -0,0 +1,36 @@
+-- Crear archivo: backend/migrations/20250710090000_create_room_inventory.sql
+
+-- Número de habitaciones que cada hotel pone a la venta por noche
+ALTER TABLE hotels ADD COLUMN total_rooms INTEGER NOT NULL DEFAULT 10 CHECK (total_rooms >= 0);
+
+-- Habitaciones ocupadas por hotel y por noche
+CREATE TABLE room_inventory (
+    hotel_id INTEGER NOT NULL REFERENCES hotels(id),
+    night DATE NOT NULL,
+    rooms_booked INTEGER NOT NULL DEFAULT 0 CHECK (rooms_booked >= 0),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (hotel_id, night)
+);
+
+-- Lista de espera para noches agotadas
+CREATE TABLE booking_waitlist (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    hotel_id INTEGER NOT NULL REFERENCES hotels(id),
+    check_in DATE NOT NULL,
+    check_out DATE NOT NULL,
+    guests INTEGER NOT NULL,
+    rooms INTEGER NOT NULL,
+    status VARCHAR(50) DEFAULT 'waiting',
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Reconstruir la ocupación a partir de las reservas existentes
+INSERT INTO room_inventory (hotel_id, night, rooms_booked)
+SELECT b.hotel_id, night::date, SUM(b.rooms)
+FROM bookings b, generate_series(b.check_in, b.check_out - 1, INTERVAL '1 day') AS night
+WHERE b.status IS DISTINCT FROM 'cancelled'
+GROUP BY b.hotel_id, night::date;
+
+-- Índices
+CREATE INDEX idx_booking_waitlist_hotel_dates ON booking_waitlist(hotel_id, check_in, check_out);
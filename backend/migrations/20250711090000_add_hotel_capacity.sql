@@ -0,0 +1,8 @@
+-- Crear archivo: backend/migrations/20250711090000_add_hotel_capacity.sql
+
+-- Capacidad máxima de huéspedes por habitación para búsquedas por tamaño de grupo
+ALTER TABLE hotels ADD COLUMN max_guests_per_room INTEGER NOT NULL DEFAULT 2 CHECK (max_guests_per_room > 0);
+
+-- Índices para la búsqueda de disponibilidad
+CREATE INDEX idx_hotels_status ON hotels(status);
+CREATE INDEX idx_room_inventory_night ON room_inventory(night);
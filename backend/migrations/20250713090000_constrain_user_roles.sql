@@ -0,0 +1,10 @@
+-- Crear archivo: backend/migrations/20250713090000_constrain_user_roles.sql
+
+-- Normalizar roles existentes antes de restringir la columna
+UPDATE users SET user_type = LOWER(user_type) WHERE user_type IS NOT NULL;
+UPDATE users SET user_type = 'customer'
+WHERE user_type IS NULL OR user_type NOT IN ('customer', 'owner', 'admin');
+
+ALTER TABLE users ALTER COLUMN user_type SET NOT NULL;
+ALTER TABLE users ADD CONSTRAINT users_user_type_check
+    CHECK (user_type IN ('customer', 'owner', 'admin'));
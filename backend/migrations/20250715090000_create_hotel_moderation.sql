@@ -0,0 +1,37 @@
+-- Crear archivo: backend/migrations/20250715090000_create_hotel_moderation.sql
+
+-- Normalizar estados existentes antes de restringir la columna
+UPDATE hotels SET status = LOWER(status) WHERE status IS NOT NULL;
+UPDATE hotels SET status = 'pending'
+WHERE status IS NULL OR status NOT IN ('pending', 'approved', 'rejected', 'suspended');
+
+ALTER TABLE hotels ALTER COLUMN status SET NOT NULL;
+ALTER TABLE hotels ADD CONSTRAINT hotels_status_check
+    CHECK (status IN ('pending', 'approved', 'rejected', 'suspended'));
+
+-- Bitácora de cambios de estado hechos por administradores
+CREATE TABLE hotel_status_audit (
+    id SERIAL PRIMARY KEY,
+    hotel_id INTEGER NOT NULL REFERENCES hotels(id),
+    from_status VARCHAR(50) NOT NULL,
+    to_status VARCHAR(50) NOT NULL,
+    reason TEXT,
+    changed_by INTEGER NOT NULL REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Notificaciones dentro de la plataforma
+CREATE TABLE notifications (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    message TEXT NOT NULL,
+    data JSONB,
+    read_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_hotel_status_audit_hotel_id ON hotel_status_audit(hotel_id);
+CREATE INDEX idx_notifications_user_id ON notifications(user_id);
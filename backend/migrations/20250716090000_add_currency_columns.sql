@@ -0,0 +1,5 @@
+-- Crear archivo: backend/migrations/20250716090000_add_currency_columns.sql
+
+-- Moneda de los precios de cada hotel y de los totales de cada reserva
+ALTER TABLE hotels ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'MXN';
+ALTER TABLE bookings ADD COLUMN currency VARCHAR(3) NOT NULL DEFAULT 'MXN';
@@ -0,0 +1,36 @@
+-- Crear archivo: backend/migrations/20250717090000_create_addon_services.sql
+
+-- Catálogo de servicios adicionales que ofrece cada hotel
+CREATE TABLE addon_services (
+    id SERIAL PRIMARY KEY,
+    hotel_id INTEGER NOT NULL REFERENCES hotels(id),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    icon VARCHAR(16),
+    price DECIMAL(10, 2) NOT NULL CHECK (price >= 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    -- per_stay: una vez por reserva, per_night: por noche, per_guest: por huésped
+    pricing_unit VARCHAR(20) NOT NULL DEFAULT 'per_stay'
+        CHECK (pricing_unit IN ('per_stay', 'per_night', 'per_guest')),
+    max_quantity INTEGER NOT NULL DEFAULT 10 CHECK (max_quantity > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_addon_services_hotel_id ON addon_services(hotel_id);
+
+-- Catálogo inicial para los hoteles de prueba
+INSERT INTO addon_services (hotel_id, name, description, icon, price, pricing_unit)
+SELECT h.id, a.name, a.description, a.icon, a.price, a.pricing_unit
+FROM hotels h
+CROSS JOIN (VALUES
+    ('Traslado al aeropuerto', 'Transporte privado ida y vuelta al aeropuerto más cercano', '🚐', 900.00, 'per_stay'),
+    ('Desayuno regional', 'Desayuno yucateco servido en el restaurante del hotel', '🍳', 250.00, 'per_guest'),
+    ('Tour a cenote', 'Visita guiada a un cenote cercano con equipo de snorkel', '🏊', 650.00, 'per_guest'),
+    ('Tour Romántico', 'Cena privada y recorrido al atardecer', '💖', 1500.00, 'per_stay'),
+    ('Paquete Luna de Miel', 'Decoración, champaña y masaje en pareja', '🥂', 3500.00, 'per_stay'),
+    ('Acceso a Spa', 'Acceso diario al circuito de spa', '💆', 800.00, 'per_stay')
+) AS a(name, description, icon, price, pricing_unit)
+WHERE h.status = 'approved';
@@ -0,0 +1,38 @@
+-- Crear archivo: backend/migrations/20250718090000_extend_pricing_rules.sql
+
+-- Tipos de regla: transport (tarifas de viaje existentes), seasonal y weekend
+-- (recargo % por noche) y length_of_stay (descuento % sobre el subtotal)
+ALTER TABLE pricing_rules
+    ADD COLUMN rule_type VARCHAR(20) NOT NULL DEFAULT 'transport'
+        CHECK (rule_type IN ('transport', 'seasonal', 'weekend', 'length_of_stay')),
+    -- NULL = regla global; con valor, la regla solo aplica a ese hotel y tiene prioridad
+    ADD COLUMN hotel_id INTEGER REFERENCES hotels(id),
+    ADD COLUMN start_date DATE,
+    ADD COLUMN end_date DATE,
+    ADD COLUMN min_nights INTEGER CHECK (min_nights > 0),
+    ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD CONSTRAINT pricing_rules_season_range
+        CHECK (rule_type <> 'seasonal' OR (start_date IS NOT NULL AND end_date IS NOT NULL AND start_date <= end_date)),
+    ADD CONSTRAINT pricing_rules_min_nights
+        CHECK (rule_type <> 'length_of_stay' OR min_nights IS NOT NULL);
+
+-- El nombre solo tiene que ser único dentro del mismo hotel
+ALTER TABLE pricing_rules DROP CONSTRAINT pricing_rules_rule_name_key;
+CREATE UNIQUE INDEX idx_pricing_rules_hotel_rule_name ON pricing_rules(COALESCE(hotel_id, 0), rule_name);
+
+-- Índices
+CREATE INDEX idx_pricing_rules_type ON pricing_rules(rule_type) WHERE is_active;
+
+-- Desglose de precios guardado con cada reserva
+ALTER TABLE bookings
+    ADD COLUMN commission_amount DECIMAL(10, 2),
+    ADD COLUMN price_breakdown JSONB;
+
+-- Reglas iniciales de hospedaje
+INSERT INTO pricing_rules (rule_name, rule_type, value, description, start_date, end_date, min_nights) VALUES
+    ('HIGH_SEASON_WINTER', 'seasonal', 25.00, 'Temporada alta de invierno', '2025-12-15', '2026-01-06', NULL),
+    ('HIGH_SEASON_EASTER', 'seasonal', 20.00, 'Temporada alta de Semana Santa', '2026-03-29', '2026-04-12', NULL),
+    ('HIGH_SEASON_SUMMER', 'seasonal', 15.00, 'Vacaciones de verano', '2025-07-15', '2025-08-20', NULL),
+    ('WEEKEND_SURCHARGE', 'weekend', 10.00, 'Recargo de fin de semana (viernes y sábado)', NULL, NULL, NULL),
+    ('LONG_STAY_7', 'length_of_stay', 10.00, 'Descuento por estancia de 7 noches o más', NULL, NULL, 7),
+    ('LONG_STAY_14', 'length_of_stay', 15.00, 'Descuento por estancia de 14 noches o más', NULL, NULL, 14);
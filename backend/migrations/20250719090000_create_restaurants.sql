@@ -0,0 +1,75 @@
+-- Crear archivo: backend/migrations/20250719090000_create_restaurants.sql
+
+-- Restaurantes publicados por socios. Igual que los hoteles, quedan pendientes
+-- hasta que un administrador los aprueba; 'suspended' los oculta del listado
+-- público sin borrar su menú.
+CREATE TABLE restaurants (
+    id SERIAL PRIMARY KEY,
+    owner_id INTEGER REFERENCES users(id),
+    name VARCHAR(255) NOT NULL,
+    location VARCHAR(255) NOT NULL,
+    address TEXT,
+    specialty VARCHAR(255),
+    description TEXT,
+    image_url TEXT,
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    status VARCHAR(50) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'suspended')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Platillos del menú de cada restaurante
+CREATE TABLE menu_items (
+    id SERIAL PRIMARY KEY,
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    price DECIMAL(10, 2) NOT NULL CHECK (price >= 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    category VARCHAR(100) NOT NULL DEFAULT 'Platos Fuertes',
+    -- Agotado temporalmente: se sigue mostrando pero no se puede pedir
+    is_available BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Eliminado por el socio: ya no aparece en el menú
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Bitácora de moderación, igual que hotel_status_audit
+CREATE TABLE restaurant_status_audit (
+    id SERIAL PRIMARY KEY,
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id),
+    from_status VARCHAR(50) NOT NULL,
+    to_status VARCHAR(50) NOT NULL,
+    reason TEXT,
+    changed_by INTEGER NOT NULL REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_restaurants_owner_id ON restaurants(owner_id);
+CREATE INDEX idx_restaurants_status ON restaurants(status);
+CREATE INDEX idx_menu_items_restaurant_id ON menu_items(restaurant_id);
+CREATE INDEX idx_restaurant_status_audit_restaurant_id ON restaurant_status_audit(restaurant_id);
+
+-- Insertar datos de prueba (los mismos que devolvía el endpoint simulado, ya aprobados)
+INSERT INTO restaurants (name, location, specialty, image_url, status) VALUES
+('Corazón de Jade', 'Campeche', 'Cocina de Autor', 'https://images.unsplash.com/photo-1552566626-52f8b828add9?q=80&w=2070&auto=format&fit=crop', 'approved'),
+('La Ceiba', 'Chetumal', 'Mariscos Frescos', 'https://images.unsplash.com/photo-1517248135467-4c7edcad34c4?q=80&w=2070&auto=format&fit=crop', 'approved'),
+('El Fogón del Jaguar', 'Valladolid', 'Carnes y Tradición', 'https://images.unsplash.com/photo-1414235077428-338989a2e8c0?q=80&w=2070&auto=format&fit=crop', 'approved');
+
+INSERT INTO menu_items (restaurant_id, name, description, price, category, sort_order)
+SELECT r.id, m.name, m.description, m.price, m.category, m.sort_order
+FROM restaurants r
+JOIN (VALUES
+    ('Corazón de Jade', 'Sopa de Lima', 'Caldo de pavo ligero con un toque de lima y tiras de tortilla frita.', 150.00, 'Entradas', 1),
+    ('Corazón de Jade', 'Cochinita Pibil', 'Carne de cerdo marinada en achiote, cocida lentamente.', 250.00, 'Platos Fuertes', 2),
+    ('Corazón de Jade', 'Relleno Negro', 'Pavo en recado negro con but de cerdo.', 230.00, 'Platos Fuertes', 3),
+    ('La Ceiba', 'Ceviche Mixto', 'Pescado fresco, camarón y pulpo marinado en cítricos.', 220.00, 'Entradas', 1),
+    ('La Ceiba', 'Pescado Tikin Xic', 'Filete de pescado en achiote asado en hoja de plátano.', 320.00, 'Platos Fuertes', 2),
+    ('El Fogón del Jaguar', 'Poc Chuc', 'Lomo de cerdo asado a la leña con cebolla morada y naranja agria.', 220.00, 'Platos Fuertes', 1),
+    ('El Fogón del Jaguar', 'Longaniza de Valladolid', 'Longaniza ahumada con frijol colado y tortillas hechas a mano.', 190.00, 'Platos Fuertes', 2)
+) AS m(restaurant_name, name, description, price, category, sort_order)
+    ON m.restaurant_name = r.name;
@@ -0,0 +1,70 @@
+-- Crear archivo: backend/migrations/20250720090000_create_restaurant_reservations.sql
+
+-- Referencias emitidas por la plataforma (reservas de hotel, mesas, ...).
+-- La PK garantiza que dos productos nunca compartan referencia.
+CREATE TABLE issued_references (
+    reference VARCHAR(20) PRIMARY KEY,
+    kind VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO issued_references (reference, kind)
+SELECT booking_reference, 'hotel_booking' FROM bookings WHERE booking_reference IS NOT NULL
+ON CONFLICT (reference) DO NOTHING;
+
+-- Configuración de reservaciones de mesa por restaurante
+ALTER TABLE restaurants
+    ADD COLUMN slot_minutes INTEGER NOT NULL DEFAULT 30 CHECK (slot_minutes BETWEEN 15 AND 240),
+    ADD COLUMN covers_per_slot INTEGER NOT NULL DEFAULT 20 CHECK (covers_per_slot > 0),
+    ADD COLUMN min_party_size INTEGER NOT NULL DEFAULT 1 CHECK (min_party_size > 0),
+    ADD COLUMN max_party_size INTEGER NOT NULL DEFAULT 8,
+    ADD CONSTRAINT restaurants_party_size_range CHECK (max_party_size >= min_party_size);
+
+-- Horario de atención; weekday usa ISO (1 = lunes ... 7 = domingo).
+-- Puede haber varios rangos por día (comida y cena).
+CREATE TABLE restaurant_opening_hours (
+    id SERIAL PRIMARY KEY,
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id) ON DELETE CASCADE,
+    weekday SMALLINT NOT NULL CHECK (weekday BETWEEN 1 AND 7),
+    opens_at TIME NOT NULL,
+    closes_at TIME NOT NULL,
+    CHECK (opens_at < closes_at)
+);
+
+-- Comensales apartados por horario, igual que room_inventory para hoteles
+CREATE TABLE restaurant_slot_inventory (
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id) ON DELETE CASCADE,
+    slot_date DATE NOT NULL,
+    slot_time TIME NOT NULL,
+    covers_booked INTEGER NOT NULL DEFAULT 0 CHECK (covers_booked >= 0),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (restaurant_id, slot_date, slot_time)
+);
+
+CREATE TABLE restaurant_reservations (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id),
+    reservation_date DATE NOT NULL,
+    slot_time TIME NOT NULL,
+    party_size INTEGER NOT NULL CHECK (party_size > 0),
+    special_requests TEXT,
+    status VARCHAR(50) NOT NULL DEFAULT 'confirmed'
+        CHECK (status IN ('confirmed', 'cancelled', 'completed', 'no_show')),
+    reservation_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancellation_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_restaurant_opening_hours_restaurant ON restaurant_opening_hours(restaurant_id, weekday);
+CREATE INDEX idx_restaurant_reservations_user_id ON restaurant_reservations(user_id);
+CREATE INDEX idx_restaurant_reservations_restaurant_date ON restaurant_reservations(restaurant_id, reservation_date);
+
+-- Horario inicial para los restaurantes existentes: todos los días de 13:00 a 22:00
+INSERT INTO restaurant_opening_hours (restaurant_id, weekday, opens_at, closes_at)
+SELECT r.id, d.weekday, '13:00', '22:00'
+FROM restaurants r
+CROSS JOIN generate_series(1, 7) AS d(weekday);
@@ -0,0 +1,47 @@
+-- Crear archivo: backend/migrations/20250721090000_create_restaurant_orders.sql
+
+-- Pedidos anticipados a restaurantes; el precio se calcula con el menú del servidor
+CREATE TABLE restaurant_orders (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    restaurant_id INTEGER NOT NULL REFERENCES restaurants(id),
+    -- Opcional: pedido para la mesa de una reservación
+    reservation_id INTEGER REFERENCES restaurant_reservations(id),
+    order_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    status VARCHAR(50) NOT NULL DEFAULT 'placed'
+        CHECK (status IN ('placed', 'accepted', 'preparing', 'ready', 'delivered', 'cancelled')),
+    total_price DECIMAL(10, 2) NOT NULL CHECK (total_price >= 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    notes TEXT,
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancellation_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Platillos del pedido con nombre y precio congelados al momento de pedir
+CREATE TABLE restaurant_order_items (
+    id SERIAL PRIMARY KEY,
+    order_id INTEGER NOT NULL REFERENCES restaurant_orders(id) ON DELETE CASCADE,
+    menu_item_id INTEGER NOT NULL REFERENCES menu_items(id),
+    name VARCHAR(255) NOT NULL,
+    unit_price DECIMAL(10, 2) NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    subtotal DECIMAL(10, 2) NOT NULL
+);
+
+-- Historial de cambios de estado del pedido
+CREATE TABLE restaurant_order_status_history (
+    id SERIAL PRIMARY KEY,
+    order_id INTEGER NOT NULL REFERENCES restaurant_orders(id) ON DELETE CASCADE,
+    from_status VARCHAR(50),
+    to_status VARCHAR(50) NOT NULL,
+    changed_by INTEGER NOT NULL REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_restaurant_orders_user_id ON restaurant_orders(user_id);
+CREATE INDEX idx_restaurant_orders_restaurant_status ON restaurant_orders(restaurant_id, status);
+CREATE INDEX idx_restaurant_order_items_order_id ON restaurant_order_items(order_id);
+CREATE INDEX idx_restaurant_order_status_history_order_id ON restaurant_order_status_history(order_id);
@@ -0,0 +1,110 @@
+-- Crear archivo: backend/migrations/20250722090000_create_experiences.sql
+
+-- Experiencias reservables (tours, paseos a caballo, cenotes)
+CREATE TABLE experiences (
+    id SERIAL PRIMARY KEY,
+    owner_id INTEGER REFERENCES users(id),
+    experience_type VARCHAR(50) NOT NULL CHECK (experience_type IN ('tour', 'caballos', 'cenote')),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    location VARCHAR(255) NOT NULL,
+    image_url TEXT,
+    duration_minutes INTEGER NOT NULL DEFAULT 120 CHECK (duration_minutes > 0),
+    -- Edad mínima para participar (NULL = sin restricción)
+    min_age INTEGER,
+    requirements TEXT,
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    status VARCHAR(50) NOT NULL DEFAULT 'approved' CHECK (status IN ('approved', 'suspended')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Tarifas por tipo de participante (adult, child, senior, ...)
+CREATE TABLE experience_fares (
+    id SERIAL PRIMARY KEY,
+    experience_id INTEGER NOT NULL REFERENCES experiences(id) ON DELETE CASCADE,
+    fare_type VARCHAR(50) NOT NULL,
+    label VARCHAR(255) NOT NULL,
+    price DECIMAL(10, 2) NOT NULL CHECK (price >= 0),
+    -- Las tarifas de menores no pueden reservarse sin al menos un adulto
+    requires_adult BOOLEAN NOT NULL DEFAULT FALSE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    UNIQUE (experience_id, fare_type)
+);
+
+-- Salidas programadas con cupo propio
+CREATE TABLE experience_departures (
+    id SERIAL PRIMARY KEY,
+    experience_id INTEGER NOT NULL REFERENCES experiences(id) ON DELETE CASCADE,
+    departure_date DATE NOT NULL,
+    departure_time TIME NOT NULL,
+    capacity INTEGER NOT NULL CHECK (capacity > 0),
+    seats_booked INTEGER NOT NULL DEFAULT 0 CHECK (seats_booked >= 0),
+    status VARCHAR(50) NOT NULL DEFAULT 'scheduled' CHECK (status IN ('scheduled', 'cancelled')),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (experience_id, departure_date, departure_time),
+    CHECK (seats_booked <= capacity)
+);
+
+CREATE TABLE experience_bookings (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    experience_id INTEGER NOT NULL REFERENCES experiences(id),
+    departure_id INTEGER NOT NULL REFERENCES experience_departures(id),
+    booking_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    participants INTEGER NOT NULL CHECK (participants > 0),
+    -- Snapshot de tarifas cobradas: [{fare_type, label, unit_price, quantity, subtotal}]
+    fares JSONB NOT NULL,
+    total_price DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    special_requests TEXT,
+    status VARCHAR(50) NOT NULL DEFAULT 'confirmed' CHECK (status IN ('confirmed', 'cancelled', 'completed')),
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancellation_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_experience_fares_experience_id ON experience_fares(experience_id);
+CREATE INDEX idx_experience_departures_lookup ON experience_departures(experience_id, departure_date);
+CREATE INDEX idx_experience_bookings_user_id ON experience_bookings(user_id);
+CREATE INDEX idx_experience_bookings_departure_id ON experience_bookings(departure_id);
+
+-- Insertar datos de prueba (los mismos que devolvía el endpoint simulado)
+INSERT INTO experiences (experience_type, name, description, location, image_url, duration_minutes, min_age, requirements) VALUES
+('tour', 'Tour a Chichén Itzá', 'Recorrido guiado por la zona arqueológica con transporte incluido.', 'Chichén Itzá, Yucatán',
+ 'https://images.unsplash.com/photo-1528181304800-259b08848526?q=80&w=2070&auto=format&fit=crop', 480, NULL, NULL),
+('caballos', 'Paseo a Caballo', 'Cabalgata por la selva hasta un cenote escondido.', 'Valladolid, Yucatán',
+ 'https://images.unsplash.com/photo-1599059813005-3603a5603703?q=80&w=1974&auto=format&fit=crop', 120, 12, 'Peso máximo: 100 kg'),
+('cenote', 'Nado en Cenote Sagrado', 'Acceso al cenote con chaleco salvavidas y regaderas.', 'Tulum, Quintana Roo',
+ 'https://images.unsplash.com/photo-1627907222543-4111d6946196?q=80&w=1965&auto=format&fit=crop', 180, NULL, NULL);
+
+INSERT INTO experience_fares (experience_id, fare_type, label, price, requires_adult)
+SELECT e.id, f.fare_type, f.label, f.price, f.requires_adult
+FROM experiences e
+JOIN (VALUES
+    ('tour', 'adult', 'Adulto', 1200.00, FALSE),
+    ('tour', 'child', 'Niño (3 a 11 años)', 720.00, TRUE),
+    ('tour', 'senior', 'Adulto mayor (60+)', 960.00, FALSE),
+    ('caballos', 'adult', 'Jinete', 850.00, FALSE),
+    ('cenote', 'adult', 'Acceso General', 450.00, FALSE),
+    ('cenote', 'adult_snorkel', 'Acceso con Snorkel', 650.00, FALSE),
+    ('cenote', 'child', 'Niño (3 a 11 años)', 300.00, TRUE),
+    ('cenote', 'senior', 'Adulto mayor (60+)', 350.00, FALSE)
+) AS f(experience_type, fare_type, label, price, requires_adult)
+    ON f.experience_type = e.experience_type;
+
+-- Salidas diarias para los próximos 60 días
+INSERT INTO experience_departures (experience_id, departure_date, departure_time, capacity)
+SELECT e.id, (CURRENT_DATE + d.day)::date, s.departure_time::time, s.capacity
+FROM experiences e
+JOIN (VALUES
+    ('tour', '07:30', 40),
+    ('caballos', '09:00', 8),
+    ('caballos', '16:00', 8),
+    ('cenote', '10:00', 25),
+    ('cenote', '13:00', 25)
+) AS s(experience_type, departure_time, capacity)
+    ON s.experience_type = e.experience_type
+CROSS JOIN generate_series(1, 60) AS d(day);
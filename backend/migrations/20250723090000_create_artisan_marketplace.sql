@@ -0,0 +1,84 @@
+-- Crear archivo: backend/migrations/20250723090000_create_artisan_marketplace.sql
+
+-- Nuevo rol para quienes venden artesanías en la plataforma
+ALTER TABLE users DROP CONSTRAINT users_user_type_check;
+ALTER TABLE users ADD CONSTRAINT users_user_type_check
+    CHECK (user_type IN ('customer', 'owner', 'admin', 'artisan'));
+
+-- Perfil público del artesano. user_id es NULL para los perfiles de prueba
+-- que todavía no tienen una cuenta asociada.
+CREATE TABLE artisans (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER UNIQUE REFERENCES users(id),
+    name VARCHAR(255) NOT NULL,
+    community VARCHAR(255),
+    bio TEXT,
+    image_url TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Piezas a la venta. stock se descuenta al comprar y nunca puede quedar negativo.
+CREATE TABLE products (
+    id SERIAL PRIMARY KEY,
+    artisan_id INTEGER NOT NULL REFERENCES artisans(id),
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    category VARCHAR(50) NOT NULL
+        CHECK (category IN ('textil', 'ceramica', 'joyeria')),
+    price DECIMAL(10, 2) NOT NULL CHECK (price > 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    stock INTEGER NOT NULL DEFAULT 0 CHECK (stock >= 0),
+    images TEXT[] NOT NULL DEFAULT '{}',
+    -- Retirado por el artesano: ya no aparece en el catálogo
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Compras del carrito de artesanías
+CREATE TABLE product_orders (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    order_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    status VARCHAR(50) NOT NULL DEFAULT 'confirmed'
+        CHECK (status IN ('confirmed', 'cancelled')),
+    total_price DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    shipping_address TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Nombre y precio se copian para que el historial no cambie si el artesano edita la pieza
+CREATE TABLE product_order_items (
+    id SERIAL PRIMARY KEY,
+    order_id INTEGER NOT NULL REFERENCES product_orders(id) ON DELETE CASCADE,
+    product_id INTEGER NOT NULL REFERENCES products(id),
+    name VARCHAR(255) NOT NULL,
+    unit_price DECIMAL(10, 2) NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    subtotal DECIMAL(10, 2) NOT NULL
+);
+
+-- Índices
+CREATE INDEX idx_products_artisan_id ON products(artisan_id);
+CREATE INDEX idx_products_category ON products(category) WHERE is_active;
+CREATE INDEX idx_product_orders_user_id ON product_orders(user_id);
+CREATE INDEX idx_product_order_items_order_id ON product_order_items(order_id);
+
+-- Insertar datos de prueba (los mismos que devolvían los endpoints simulados)
+INSERT INTO artisans (name, community) VALUES
+('Elena Poot', 'Valladolid, Yucatán'),
+('Mateo Cruz', 'Ticul, Yucatán'),
+('Isabel Chi', 'Mérida, Yucatán');
+
+INSERT INTO products (artisan_id, name, description, category, price, stock, images)
+SELECT a.id, p.name, p.description, p.category, p.price, p.stock, ARRAY[p.image]
+FROM artisans a
+JOIN (VALUES
+    ('Elena Poot', 'Huipil Ceremonial', 'Tejido a mano con técnicas ancestrales, este huipil representa la cosmovisión maya en cada uno de sus hilos.', 'textil', 1800.00, 5, 'https://images.unsplash.com/photo-1620921207299-b37993505b12?q=80&w=1964&auto=format&fit=crop'),
+    ('Mateo Cruz', 'Vasija de Sac-bé', 'Cerámica de alta temperatura pintada a mano con pigmentos naturales, ideal para decoración.', 'ceramica', 950.00, 12, 'https://images.unsplash.com/photo-1578899223131-a7isea110323?q=80&w=1887&auto=format&fit=crop'),
+    ('Isabel Chi', 'Aretes de Filigrana', 'Elegantes aretes de plata trabajados con la delicada técnica de filigrana.', 'joyeria', 1200.00, 8, 'https://images.unsplash.com/photo-1611652032935-a6ce59b4c03d?q=80&w=1887&auto=format&fit=crop')
+) AS p(artisan_name, name, description, category, price, stock, image)
+    ON p.artisan_name = a.name;
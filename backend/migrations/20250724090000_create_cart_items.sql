@@ -0,0 +1,16 @@
+-- Crear archivo: backend/migrations/20250724090000_create_cart_items.sql
+
+-- Carrito persistente: una fila por producto y usuario. El precio no se guarda;
+-- se toma del catálogo al mostrar el carrito y al pagar.
+CREATE TABLE cart_items (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    product_id INTEGER NOT NULL REFERENCES products(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (user_id, product_id)
+);
+
+-- Índices
+CREATE INDEX idx_cart_items_user_id ON cart_items(user_id);
@@ -0,0 +1,34 @@
+-- Crear archivo: backend/migrations/20250725090000_create_trips.sql
+
+-- Itinerario que agrupa reservas de distintos productos en un solo checkout.
+-- El total y el estado de pago viven en el viaje: se cobra una sola vez y el
+-- viaje queda pendiente hasta que se paga completo.
+CREATE TABLE trips (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    trip_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    name VARCHAR(255),
+    status VARCHAR(50) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'confirmed', 'cancelled')),
+    total_price DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    payment_status VARCHAR(50) NOT NULL DEFAULT 'pending'
+        CHECK (payment_status IN ('pending', 'paid', 'failed')),
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancellation_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Cada reserva puede pertenecer a un viaje; NULL si se hizo por separado
+ALTER TABLE bookings ADD COLUMN trip_id INTEGER REFERENCES trips(id);
+ALTER TABLE experience_bookings ADD COLUMN trip_id INTEGER REFERENCES trips(id);
+ALTER TABLE restaurant_reservations ADD COLUMN trip_id INTEGER REFERENCES trips(id);
+ALTER TABLE product_orders ADD COLUMN trip_id INTEGER REFERENCES trips(id);
+
+-- Índices
+CREATE INDEX idx_trips_user_id ON trips(user_id);
+CREATE INDEX idx_bookings_trip_id ON bookings(trip_id) WHERE trip_id IS NOT NULL;
+CREATE INDEX idx_experience_bookings_trip_id ON experience_bookings(trip_id) WHERE trip_id IS NOT NULL;
+CREATE INDEX idx_restaurant_reservations_trip_id ON restaurant_reservations(trip_id) WHERE trip_id IS NOT NULL;
+CREATE INDEX idx_product_orders_trip_id ON product_orders(trip_id) WHERE trip_id IS NOT NULL;
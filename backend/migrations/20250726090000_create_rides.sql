@@ -0,0 +1,75 @@
+-- Crear archivo: backend/migrations/20250726090000_create_rides.sql
+
+-- Conductores de transporte certificado. user_id es NULL para los conductores
+-- de prueba que todavía no tienen una cuenta asociada.
+CREATE TABLE drivers (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER UNIQUE REFERENCES users(id),
+    name VARCHAR(255) NOT NULL,
+    phone VARCHAR(20),
+    vehicle VARCHAR(255) NOT NULL,
+    plate VARCHAR(20) NOT NULL UNIQUE,
+    -- Última ubicación conocida; se usa para asignar al conductor más cercano
+    latitude DOUBLE PRECISION CHECK (latitude BETWEEN -90 AND 90),
+    longitude DOUBLE PRECISION CHECK (longitude BETWEEN -180 AND 180),
+    -- Libre para recibir un viaje (sin viaje en curso)
+    is_available BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Solicitudes de viaje. La tarifa se calcula en el servidor con las reglas
+-- 'transport' de pricing_rules y se congela al solicitar.
+CREATE TABLE rides (
+    id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    driver_id INTEGER REFERENCES drivers(id),
+    ride_reference VARCHAR(20) NOT NULL UNIQUE REFERENCES issued_references(reference),
+    origin_address VARCHAR(255) NOT NULL,
+    origin_lat DOUBLE PRECISION NOT NULL CHECK (origin_lat BETWEEN -90 AND 90),
+    origin_lng DOUBLE PRECISION NOT NULL CHECK (origin_lng BETWEEN -180 AND 180),
+    destination_address VARCHAR(255) NOT NULL,
+    destination_lat DOUBLE PRECISION NOT NULL CHECK (destination_lat BETWEEN -90 AND 90),
+    destination_lng DOUBLE PRECISION NOT NULL CHECK (destination_lng BETWEEN -180 AND 180),
+    distance_km DECIMAL(8, 2) NOT NULL CHECK (distance_km > 0),
+    duration_minutes INTEGER NOT NULL CHECK (duration_minutes > 0),
+    fare DECIMAL(10, 2) NOT NULL CHECK (fare >= 0),
+    currency VARCHAR(3) NOT NULL DEFAULT 'MXN',
+    fare_breakdown JSONB,
+    notes TEXT,
+    status VARCHAR(50) NOT NULL DEFAULT 'requested'
+        CHECK (status IN ('requested', 'assigned', 'en_route', 'completed', 'cancelled')),
+    assigned_at TIMESTAMP WITH TIME ZONE,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    cancellation_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    -- Un viaje asignado, en curso o completado siempre tiene conductor
+    CONSTRAINT rides_driver_required
+        CHECK (status IN ('requested', 'cancelled') OR driver_id IS NOT NULL)
+);
+
+-- Historial de cambios de estado del viaje.
+-- changed_by es NULL cuando el cambio lo hace el sistema (asignación automática).
+CREATE TABLE ride_status_history (
+    id SERIAL PRIMARY KEY,
+    ride_id INTEGER NOT NULL REFERENCES rides(id) ON DELETE CASCADE,
+    from_status VARCHAR(50),
+    to_status VARCHAR(50) NOT NULL,
+    changed_by INTEGER REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_drivers_available ON drivers(is_available) WHERE is_available;
+CREATE INDEX idx_rides_user_id ON rides(user_id);
+CREATE INDEX idx_rides_driver_status ON rides(driver_id, status);
+CREATE INDEX idx_ride_status_history_ride_id ON ride_status_history(ride_id);
+
+-- Insertar datos de prueba
+INSERT INTO drivers (name, phone, vehicle, plate, latitude, longitude) VALUES
+    ('Jorge Canul', '+52 998 123 4501', 'Nissan Urvan 2022 blanca', 'YZA-123-A', 21.0365, -86.8771),
+    ('Lucía May', '+52 998 123 4502', 'Toyota Hiace 2023 gris', 'YZA-456-B', 21.1619, -86.8515),
+    ('Raúl Tun', '+52 984 123 4503', 'Chevrolet Suburban 2021 negra', 'YZB-789-C', 20.6296, -87.0739);
@@ -0,0 +1,35 @@
+-- Crear archivo: backend/migrations/20250727090000_create_driver_onboarding.sql
+
+-- Nuevo rol para quienes manejan en el servicio de transporte
+ALTER TABLE users DROP CONSTRAINT users_user_type_check;
+ALTER TABLE users ADD CONSTRAINT users_user_type_check
+    CHECK (user_type IN ('customer', 'owner', 'admin', 'artisan', 'driver'));
+
+-- Datos de registro y certificación. Un conductor solo recibe ofertas de viaje
+-- cuando un administrador aprobó su certificación y él se puso en línea.
+ALTER TABLE drivers
+    ADD COLUMN license_number VARCHAR(50) UNIQUE,
+    ADD COLUMN license_expires_on DATE,
+    ADD COLUMN certification_status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (certification_status IN ('pending', 'approved', 'rejected', 'suspended')),
+    ADD COLUMN is_online BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Los conductores de prueba ya estaban certificados
+UPDATE drivers SET certification_status = 'approved' WHERE user_id IS NULL;
+
+-- Bitácora de cambios de certificación hechos por administradores
+CREATE TABLE driver_certification_audit (
+    id SERIAL PRIMARY KEY,
+    driver_id INTEGER NOT NULL REFERENCES drivers(id),
+    from_status VARCHAR(20) NOT NULL,
+    to_status VARCHAR(20) NOT NULL,
+    reason TEXT,
+    changed_by INTEGER NOT NULL REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_drivers_certification_status ON drivers(certification_status);
+CREATE INDEX idx_drivers_online ON drivers(is_online, is_available) WHERE is_online;
+CREATE INDEX idx_rides_requested ON rides(created_at) WHERE status = 'requested';
+CREATE INDEX idx_driver_certification_audit_driver_id ON driver_certification_audit(driver_id);
@@ -0,0 +1,48 @@
+-- Crear archivo: backend/migrations/20250728090000_create_payments.sql
+
+-- Estados de pago de una reserva; solo los webhooks del proveedor lo cambian
+UPDATE bookings SET payment_status = 'pending' WHERE payment_status IS NULL;
+ALTER TABLE bookings
+    ALTER COLUMN payment_status SET NOT NULL,
+    ADD CONSTRAINT bookings_payment_status_check
+        CHECK (payment_status IN ('pending', 'paid', 'failed'));
+
+-- Intento de cobro creado con un proveedor de pagos para una reserva suelta
+-- o para un viaje completo (solo uno de los dos)
+CREATE TABLE payment_intents (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER REFERENCES bookings(id),
+    trip_id INTEGER REFERENCES trips(id),
+    provider VARCHAR(30) NOT NULL,
+    provider_intent_id VARCHAR(100) NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    client_secret VARCHAR(255),
+    status VARCHAR(30) NOT NULL DEFAULT 'requires_payment'
+        CHECK (status IN ('requires_payment', 'succeeded', 'failed')),
+    failure_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, provider_intent_id),
+    CHECK (num_nonnulls(booking_id, trip_id) = 1)
+);
+
+-- Eventos de webhook recibidos. La llave única hace que una entrega repetida
+-- del mismo evento no se procese dos veces.
+CREATE TABLE payment_webhook_events (
+    id SERIAL PRIMARY KEY,
+    provider VARCHAR(30) NOT NULL,
+    event_id VARCHAR(100) NOT NULL,
+    event_type VARCHAR(60) NOT NULL,
+    payment_intent_id INTEGER REFERENCES payment_intents(id),
+    payload JSONB NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, event_id)
+);
+
+-- Índices
+CREATE INDEX idx_payment_intents_booking ON payment_intents(booking_id);
+CREATE INDEX idx_payment_intents_open ON payment_intents(booking_id) WHERE status = 'requires_payment';
+CREATE INDEX idx_payment_intents_trip ON payment_intents(trip_id) WHERE trip_id IS NOT NULL;
+CREATE INDEX idx_payment_intents_trip_open ON payment_intents(trip_id) WHERE status = 'requires_payment';
+CREATE INDEX idx_bookings_payment_status ON bookings(payment_status);
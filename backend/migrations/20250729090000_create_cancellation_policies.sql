@@ -0,0 +1,38 @@
+-- Crear archivo: backend/migrations/20250729090000_create_cancellation_policies.sql
+
+-- Política de cancelación por hotel (ver CancellationPolicyKind)
+ALTER TABLE hotels
+    ADD COLUMN cancellation_policy VARCHAR(20) NOT NULL DEFAULT 'flexible'
+        CHECK (cancellation_policy IN ('flexible', 'partial', 'non_refundable')),
+    ADD COLUMN free_cancellation_days INTEGER NOT NULL DEFAULT 1
+        CHECK (free_cancellation_days BETWEEN 0 AND 365),
+    ADD COLUMN cancellation_penalty_percent INTEGER NOT NULL DEFAULT 100
+        CHECK (cancellation_penalty_percent BETWEEN 0 AND 100);
+
+-- Estados de pago después de un reembolso
+ALTER TABLE bookings DROP CONSTRAINT bookings_payment_status_check;
+ALTER TABLE bookings ADD CONSTRAINT bookings_payment_status_check
+    CHECK (payment_status IN ('pending', 'paid', 'failed', 'refunded', 'partially_refunded'));
+
+-- Reembolsos emitidos al cancelar reservas pagadas, con la política aplicada
+CREATE TABLE refunds (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES bookings(id),
+    payment_intent_id INTEGER NOT NULL REFERENCES payment_intents(id),
+    provider VARCHAR(30) NOT NULL,
+    provider_refund_id VARCHAR(100) NOT NULL,
+    amount DECIMAL(10, 2) NOT NULL CHECK (amount > 0),
+    penalty_amount DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    currency VARCHAR(3) NOT NULL,
+    policy JSONB NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (provider, provider_refund_id)
+);
+
+-- Índices
+CREATE INDEX idx_refunds_booking ON refunds(booking_id);
+
+-- Insertar datos de prueba
+UPDATE hotels SET cancellation_policy = 'partial', free_cancellation_days = 7, cancellation_penalty_percent = 50
+WHERE name = 'Hacienda Uxmal';
@@ -0,0 +1,33 @@
+-- Crear archivo: backend/migrations/20250730090000_create_booking_lifecycle.sql
+
+-- Estados válidos de una reserva (ver BookingStatus)
+UPDATE bookings SET status = 'pending' WHERE status IS NULL;
+ALTER TABLE bookings
+    ALTER COLUMN status SET NOT NULL,
+    ADD CONSTRAINT bookings_status_check
+        CHECK (status IN ('pending', 'confirmed', 'checked_in', 'checked_out', 'cancelled', 'no_show', 'rejected')),
+    ADD COLUMN confirmed_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN checked_in_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN checked_out_at TIMESTAMP WITH TIME ZONE;
+
+-- Historial de cambios de estado de cada reserva
+CREATE TABLE booking_status_history (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    from_status VARCHAR(20),
+    to_status VARCHAR(20) NOT NULL,
+    reason TEXT,
+    changed_by INTEGER NOT NULL REFERENCES users(id),
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Índices
+CREATE INDEX idx_booking_status_history_booking_id ON booking_status_history(booking_id);
+
+-- Historial inicial de las reservas existentes
+INSERT INTO booking_status_history (booking_id, from_status, to_status, changed_by, changed_at)
+SELECT id, NULL, 'pending', user_id, created_at FROM bookings;
+
+INSERT INTO booking_status_history (booking_id, from_status, to_status, reason, changed_by, changed_at)
+SELECT id, 'pending', 'cancelled', cancellation_reason, user_id, cancelled_at
+FROM bookings WHERE status = 'cancelled';
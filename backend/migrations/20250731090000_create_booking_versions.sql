@@ -0,0 +1,26 @@
+-- Crear archivo: backend/migrations/20250731090000_create_booking_versions.sql
+
+-- Número de versión vigente de la reserva; aumenta con cada modificación
+ALTER TABLE bookings ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+-- Copia de cada versión anterior de una reserva modificada
+CREATE TABLE booking_versions (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    check_in DATE NOT NULL,
+    check_out DATE NOT NULL,
+    guests INTEGER NOT NULL,
+    rooms INTEGER NOT NULL,
+    total_price DECIMAL(10, 2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    addon_services JSONB,
+    special_requests TEXT,
+    price_breakdown JSONB,
+    replaced_by INTEGER NOT NULL REFERENCES users(id),
+    replaced_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (booking_id, version)
+);
+
+-- Índices
+CREATE INDEX idx_booking_versions_booking_id ON booking_versions(booking_id);
@@ -0,0 +1,54 @@
+-- Crear archivo: backend/migrations/20250801090000_create_guest_checkout.sql
+
+-- Huéspedes que reservan sin cuenta; al registrarse se enlazan con su usuario
+CREATE TABLE guests (
+    id SERIAL PRIMARY KEY,
+    first_name VARCHAR(100) NOT NULL,
+    last_name VARCHAR(100) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    phone VARCHAR(30) NOT NULL,
+    merged_into_user_id INTEGER REFERENCES users(id),
+    merged_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Un solo registro de huésped abierto por email
+CREATE UNIQUE INDEX idx_guests_open_email ON guests(LOWER(email)) WHERE merged_into_user_id IS NULL;
+
+-- Una reserva pertenece a un usuario, a un huésped o a ambos después de unirlos
+ALTER TABLE bookings
+    ALTER COLUMN user_id DROP NOT NULL,
+    ADD COLUMN guest_id INTEGER REFERENCES guests(id),
+    ADD CONSTRAINT bookings_holder_check CHECK (user_id IS NOT NULL OR guest_id IS NOT NULL);
+
+-- Los cambios hechos por un huésped desde su enlace no tienen usuario
+ALTER TABLE booking_status_history ALTER COLUMN changed_by DROP NOT NULL;
+
+-- Enlaces mágicos para administrar una reserva sin cuenta (solo se guarda el SHA-256)
+CREATE TABLE booking_magic_links (
+    id SERIAL PRIMARY KEY,
+    booking_id INTEGER NOT NULL REFERENCES bookings(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Correos pendientes de envío; se escriben en la misma transacción que los origina
+CREATE TABLE email_outbox (
+    id SERIAL PRIMARY KEY,
+    recipient VARCHAR(255) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sent', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    sent_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Índices
+CREATE INDEX idx_bookings_guest_id ON bookings(guest_id);
+CREATE INDEX idx_booking_magic_links_booking_id ON booking_magic_links(booking_id);
+CREATE INDEX idx_email_outbox_pending ON email_outbox(id) WHERE status = 'pending';
@@ -0,0 +1,814 @@
+// backend/src/handlers/admin.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::roles::{Admin, RequireRole};
+use crate::models::*;
+use crate::money::Money;
+use crate::services::notifications;
+
+pub async fn get_pending_hotels(
+    pool: web::Data<PgPool>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} consultando hoteles pendientes", admin.user.id);
+
+    let hotels = sqlx::query!(
+        r#"
+        SELECT h.id, h.name, h.location, h.address, h.price, h.currency, h.total_rooms, h.created_at,
+               u.id as "owner_id?", u.first_name || ' ' || u.last_name as "owner_name?",
+               u.email as "owner_email?"
+        FROM hotels h
+        LEFT JOIN users u ON h.owner_id = u.id
+        WHERE h.status = 'pending'
+        ORDER BY h.created_at ASC
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match hotels {
+        Ok(hotel_list) => {
+            let hotels_json: Vec<serde_json::Value> = hotel_list
+                .into_iter()
+                .map(|h| json!({
+                    "id": h.id,
+                    "name": h.name,
+                    "location": h.location,
+                    "address": h.address,
+                    "price": Money::from_db(h.price, &h.currency),
+                    "total_rooms": h.total_rooms,
+                    "created_at": h.created_at,
+                    "owner": {
+                        "id": h.owner_id,
+                        "name": h.owner_name,
+                        "email": h.owner_email
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "hotels": hotels_json,
+                "total": hotels_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [ADMIN] Error obteniendo hoteles pendientes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los hoteles pendientes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn approve_hotel(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    change_hotel_status(pool.get_ref(), path.into_inner(), &admin.user, HotelStatus::Approved, None).await
+}
+
+pub async fn reject_hotel(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo del rechazo",
+            "details": errors
+        })));
+    }
+
+    change_hotel_status(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        HotelStatus::Rejected,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+pub async fn suspend_hotel(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo de la suspensión",
+            "details": errors
+        })));
+    }
+
+    change_hotel_status(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        HotelStatus::Suspended,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+pub async fn get_hotel_audit(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    _admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    let hotel_id = path.into_inner();
+
+    let entries = sqlx::query!(
+        r#"
+        SELECT a.id, a.from_status, a.to_status, a.reason, a.changed_at,
+               u.id as changed_by_id, u.first_name || ' ' || u.last_name as "changed_by_name!"
+        FROM hotel_status_audit a
+        JOIN users u ON a.changed_by = u.id
+        WHERE a.hotel_id = $1
+        ORDER BY a.changed_at DESC
+        "#,
+        hotel_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match entries {
+        Ok(entries) => {
+            let audit: Vec<serde_json::Value> = entries
+                .into_iter()
+                .map(|a| json!({
+                    "id": a.id,
+                    "from_status": a.from_status,
+                    "to_status": a.to_status,
+                    "reason": a.reason,
+                    "changed_at": a.changed_at,
+                    "changed_by": {
+                        "id": a.changed_by_id,
+                        "name": a.changed_by_name
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "audit": audit })))
+        }
+        Err(e) => {
+            println!("💥 [ADMIN] Error obteniendo bitácora del hotel {}: {}", hotel_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener la bitácora del hotel",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Aplica una transición de moderación: valida el estado actual, guarda la
+// bitácora y notifica al dueño, todo en una misma transacción
+async fn change_hotel_status(
+    pool: &PgPool,
+    hotel_id: i32,
+    admin: &UserInfo,
+    target: HotelStatus,
+    reason: Option<&str>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} cambiando hotel {} a '{}'", admin.id, hotel_id, target.as_str());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ADMIN] Error moderando hotel {}: {}", hotel_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar el estado del hotel",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let hotel = match sqlx::query!(
+        "SELECT name, status, owner_id FROM hotels WHERE id = $1 FOR UPDATE",
+        hotel_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(hotel)) => hotel,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Hotel no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = HotelStatus::from_db(&hotel.status).unwrap_or(HotelStatus::Pending);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar un hotel de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        "UPDATE hotels SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        hotel_id,
+        target.as_str()
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    let audit = match sqlx::query!(
+        r#"
+        INSERT INTO hotel_status_audit (hotel_id, from_status, to_status, reason, changed_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, changed_at
+        "#,
+        hotel_id,
+        current.as_str(),
+        target.as_str(),
+        reason,
+        admin.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(audit) => audit,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Some(owner_id) = hotel.owner_id {
+        let (title, message) = match target {
+            HotelStatus::Approved => (
+                "Tu hotel fue aprobado",
+                format!("'{}' ya está visible para los viajeros.", hotel.name),
+            ),
+            HotelStatus::Rejected => (
+                "Tu hotel fue rechazado",
+                format!("'{}' no fue aprobado. Motivo: {}", hotel.name, reason.unwrap_or("")),
+            ),
+            HotelStatus::Suspended => (
+                "Tu hotel fue suspendido",
+                format!("'{}' dejó de estar visible. Motivo: {}", hotel.name, reason.unwrap_or("")),
+            ),
+            HotelStatus::Pending => ("Tu hotel está en revisión", format!("'{}' está en revisión.", hotel.name)),
+        };
+
+        if let Err(e) = notifications::notify(
+            &mut tx,
+            owner_id,
+            "hotel_status",
+            title,
+            &message,
+            Some(json!({ "hotel_id": hotel_id, "status": target.as_str(), "reason": reason })),
+        )
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ADMIN] Hotel {} ahora está '{}'", hotel_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Estado del hotel actualizado",
+        "hotel": {
+            "id": hotel_id,
+            "name": hotel.name,
+            "status": target.as_str()
+        },
+        "audit": {
+            "id": audit.id,
+            "from_status": current.as_str(),
+            "to_status": target.as_str(),
+            "reason": reason,
+            "changed_by": admin.id,
+            "changed_at": audit.changed_at
+        }
+    })))
+}
+
+pub async fn get_pending_restaurants(
+    pool: web::Data<PgPool>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} consultando restaurantes pendientes", admin.user.id);
+
+    let restaurants = sqlx::query!(
+        r#"
+        SELECT r.id, r.name, r.location, r.address, r.specialty, r.description, r.created_at,
+               u.id as "owner_id?", u.first_name || ' ' || u.last_name as "owner_name?",
+               u.email as "owner_email?"
+        FROM restaurants r
+        LEFT JOIN users u ON r.owner_id = u.id
+        WHERE r.status = 'pending'
+        ORDER BY r.created_at ASC
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match restaurants {
+        Ok(restaurant_list) => {
+            let restaurants_json: Vec<serde_json::Value> = restaurant_list
+                .into_iter()
+                .map(|r| json!({
+                    "id": r.id,
+                    "name": r.name,
+                    "location": r.location,
+                    "address": r.address,
+                    "specialty": r.specialty,
+                    "description": r.description,
+                    "created_at": r.created_at,
+                    "owner": {
+                        "id": r.owner_id,
+                        "name": r.owner_name,
+                        "email": r.owner_email
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "restaurants": restaurants_json,
+                "total": restaurants_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [ADMIN] Error obteniendo restaurantes pendientes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los restaurantes pendientes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn approve_restaurant(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    change_restaurant_status(pool.get_ref(), path.into_inner(), &admin.user, RestaurantStatus::Approved, None).await
+}
+
+pub async fn reject_restaurant(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo del rechazo",
+            "details": errors
+        })));
+    }
+
+    change_restaurant_status(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        RestaurantStatus::Rejected,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+pub async fn suspend_restaurant(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo de la suspensión",
+            "details": errors
+        })));
+    }
+
+    change_restaurant_status(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        RestaurantStatus::Suspended,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+// Misma transición que change_hotel_status, sobre restaurants y su bitácora
+async fn change_restaurant_status(
+    pool: &PgPool,
+    restaurant_id: i32,
+    admin: &UserInfo,
+    target: RestaurantStatus,
+    reason: Option<&str>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} cambiando restaurante {} a '{}'", admin.id, restaurant_id, target.as_str());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ADMIN] Error moderando restaurante {}: {}", restaurant_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar el estado del restaurante",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let restaurant = match sqlx::query!(
+        "SELECT name, status, owner_id FROM restaurants WHERE id = $1 FOR UPDATE",
+        restaurant_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Restaurante no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = RestaurantStatus::from_db(&restaurant.status).unwrap_or(RestaurantStatus::Pending);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar un restaurante de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        "UPDATE restaurants SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        restaurant_id,
+        target.as_str()
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    let audit = match sqlx::query!(
+        r#"
+        INSERT INTO restaurant_status_audit (restaurant_id, from_status, to_status, reason, changed_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, changed_at
+        "#,
+        restaurant_id,
+        current.as_str(),
+        target.as_str(),
+        reason,
+        admin.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(audit) => audit,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Some(owner_id) = restaurant.owner_id {
+        let (title, message) = match target {
+            RestaurantStatus::Approved => (
+                "Tu restaurante fue aprobado",
+                format!("'{}' ya está visible para los viajeros.", restaurant.name),
+            ),
+            RestaurantStatus::Rejected => (
+                "Tu restaurante fue rechazado",
+                format!("'{}' no fue aprobado. Motivo: {}", restaurant.name, reason.unwrap_or("")),
+            ),
+            RestaurantStatus::Suspended => (
+                "Tu restaurante fue suspendido",
+                format!("'{}' dejó de estar visible. Motivo: {}", restaurant.name, reason.unwrap_or("")),
+            ),
+            RestaurantStatus::Pending => (
+                "Tu restaurante está en revisión",
+                format!("'{}' está en revisión.", restaurant.name),
+            ),
+        };
+
+        if let Err(e) = notifications::notify(
+            &mut tx,
+            owner_id,
+            "restaurant_status",
+            title,
+            &message,
+            Some(json!({ "restaurant_id": restaurant_id, "status": target.as_str(), "reason": reason })),
+        )
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ADMIN] Restaurante {} ahora está '{}'", restaurant_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Estado del restaurante actualizado",
+        "restaurant": {
+            "id": restaurant_id,
+            "name": restaurant.name,
+            "status": target.as_str()
+        },
+        "audit": {
+            "id": audit.id,
+            "from_status": current.as_str(),
+            "to_status": target.as_str(),
+            "reason": reason,
+            "changed_by": admin.id,
+            "changed_at": audit.changed_at
+        }
+    })))
+}
+
+// === CERTIFICACIÓN DE CONDUCTORES ===
+
+pub async fn get_pending_drivers(
+    pool: web::Data<PgPool>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} consultando conductores pendientes", admin.user.id);
+
+    let drivers = sqlx::query!(
+        r#"
+        SELECT d.id, d.name, d.phone, d.vehicle, d.plate, d.license_number, d.license_expires_on,
+               d.created_at, u.id as "user_id?", u.email as "email?"
+        FROM drivers d
+        LEFT JOIN users u ON d.user_id = u.id
+        WHERE d.certification_status = 'pending'
+        ORDER BY d.created_at ASC
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match drivers {
+        Ok(driver_list) => {
+            let drivers_json: Vec<serde_json::Value> = driver_list
+                .into_iter()
+                .map(|d| json!({
+                    "id": d.id,
+                    "name": d.name,
+                    "phone": d.phone,
+                    "vehicle": d.vehicle,
+                    "plate": d.plate,
+                    "license_number": d.license_number,
+                    "license_expires_on": d.license_expires_on,
+                    "created_at": d.created_at,
+                    "user": {
+                        "id": d.user_id,
+                        "email": d.email
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "drivers": drivers_json,
+                "total": drivers_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [ADMIN] Error obteniendo conductores pendientes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los conductores pendientes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn approve_driver(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    change_driver_certification(pool.get_ref(), path.into_inner(), &admin.user, CertificationStatus::Approved, None)
+        .await
+}
+
+pub async fn reject_driver(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo del rechazo",
+            "details": errors
+        })));
+    }
+
+    change_driver_certification(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        CertificationStatus::Rejected,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+pub async fn suspend_driver(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    moderation_req: web::Json<ModerationRequest>,
+    admin: RequireRole<Admin>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = moderation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Debes indicar el motivo de la suspensión",
+            "details": errors
+        })));
+    }
+
+    change_driver_certification(
+        pool.get_ref(),
+        path.into_inner(),
+        &admin.user,
+        CertificationStatus::Suspended,
+        Some(moderation_req.reason.trim()),
+    )
+    .await
+}
+
+// Igual que change_hotel_status, para la certificación de un conductor. Un
+// conductor suspendido queda fuera de línea y deja de recibir ofertas.
+async fn change_driver_certification(
+    pool: &PgPool,
+    driver_id: i32,
+    admin: &UserInfo,
+    target: CertificationStatus,
+    reason: Option<&str>,
+) -> Result<HttpResponse> {
+    println!("🛡️ [ADMIN] Usuario {} cambiando conductor {} a '{}'", admin.id, driver_id, target.as_str());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ADMIN] Error moderando conductor {}: {}", driver_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar la certificación del conductor",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let driver = match sqlx::query!(
+        r#"
+        SELECT name, certification_status, user_id,
+               license_expires_on IS NOT NULL AND license_expires_on < CURRENT_DATE as "license_expired!"
+        FROM drivers WHERE id = $1 FOR UPDATE
+        "#,
+        driver_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(driver)) => driver,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Conductor no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = CertificationStatus::from_db(&driver.certification_status).unwrap_or(CertificationStatus::Pending);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar un conductor de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if target == CertificationStatus::Approved && driver.license_expired {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": "La licencia del conductor está vencida"
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE drivers SET certification_status = $2::varchar,
+            is_online = is_online AND $2::varchar = 'approved',
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        driver_id,
+        target.as_str()
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    let audit = match sqlx::query!(
+        r#"
+        INSERT INTO driver_certification_audit (driver_id, from_status, to_status, reason, changed_by)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, changed_at
+        "#,
+        driver_id,
+        current.as_str(),
+        target.as_str(),
+        reason,
+        admin.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(audit) => audit,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Some(user_id) = driver.user_id {
+        let (title, message) = match target {
+            CertificationStatus::Approved => (
+                "Tu certificación fue aprobada",
+                "Ya puedes ponerte en línea y recibir viajes.".to_string(),
+            ),
+            CertificationStatus::Rejected => (
+                "Tu certificación fue rechazada",
+                format!("Tu registro como conductor no fue aprobado. Motivo: {}", reason.unwrap_or("")),
+            ),
+            CertificationStatus::Suspended => (
+                "Tu certificación fue suspendida",
+                format!("Ya no recibirás viajes. Motivo: {}", reason.unwrap_or("")),
+            ),
+            CertificationStatus::Pending => (
+                "Tu certificación está en revisión",
+                "Tu registro como conductor está en revisión.".to_string(),
+            ),
+        };
+
+        if let Err(e) = notifications::notify(
+            &mut tx,
+            user_id,
+            "driver_certification",
+            title,
+            &message,
+            Some(json!({ "driver_id": driver_id, "status": target.as_str(), "reason": reason })),
+        )
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ADMIN] Conductor {} ahora está '{}'", driver_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Certificación del conductor actualizada",
+        "driver": {
+            "id": driver_id,
+            "name": driver.name,
+            "certification_status": target.as_str()
+        },
+        "audit": {
+            "id": audit.id,
+            "from_status": current.as_str(),
+            "to_status": target.as_str(),
+            "reason": reason,
+            "changed_by": admin.id,
+            "changed_at": audit.changed_at
+        }
+    })))
+}
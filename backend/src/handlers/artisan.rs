@@ -0,0 +1,334 @@
+// backend/src/handlers/artisan.rs
+use actix_web::{web, HttpResponse, Result};
+use bigdecimal::{BigDecimal, Zero};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::roles::{Artisan, Product, RequireOwnerOf, RequireRole};
+use crate::models::*;
+use crate::money::Money;
+
+pub async fn create_artisan_profile(
+    pool: web::Data<PgPool>,
+    profile_req: web::Json<ArtisanProfileRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("🧶 [ARTISAN] Registrando perfil '{}' para usuario {}", profile_req.name, user.id);
+
+    if let Err(errors) = profile_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ARTISAN] Error registrando perfil: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al registrar el perfil de artesano",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Un cliente que abre su taller se convierte en artesano
+    if user.role() == Role::Customer {
+        if let Err(e) = sqlx::query!(
+            "UPDATE users SET user_type = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+            Role::Artisan.as_str(),
+            user.id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+        println!("⭐ [ARTISAN] Usuario {} ahora es artesano", user.id);
+    }
+
+    let profile = sqlx::query!(
+        r#"
+        INSERT INTO artisans (user_id, name, community, bio, image_url)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id) DO NOTHING
+        RETURNING id, created_at
+        "#,
+        user.id,
+        profile_req.name,
+        profile_req.community,
+        profile_req.bio,
+        profile_req.image_url
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let profile = match profile {
+        Ok(Some(profile)) => profile,
+        Ok(None) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": "Ya tienes un perfil de artesano"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ARTISAN] Perfil {} registrado", profile.id);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Perfil de artesano creado. Ya puedes publicar tus piezas.",
+        "artisan": {
+            "id": profile.id,
+            "name": profile_req.name,
+            "community": profile_req.community,
+            "bio": profile_req.bio,
+            "image_url": profile_req.image_url,
+            "created_at": profile.created_at
+        }
+    })))
+}
+
+pub async fn get_my_products(
+    pool: web::Data<PgPool>,
+    artisan: RequireRole<Artisan>,
+) -> Result<HttpResponse> {
+    println!("🧶 [ARTISAN] Obteniendo productos del artesano {}", artisan.user.id);
+
+    let products = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.description, p.category, p.price, p.currency, p.stock, p.images,
+               p.created_at, p.updated_at,
+               COALESCE(SUM(i.quantity), 0)::int4 as "units_sold!"
+        FROM products p
+        JOIN artisans a ON p.artisan_id = a.id
+        LEFT JOIN product_order_items i ON i.product_id = p.id
+        WHERE a.user_id = $1 AND p.is_active
+        GROUP BY p.id
+        ORDER BY p.created_at DESC
+        "#,
+        artisan.user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match products {
+        Ok(list) => {
+            let products_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|p| json!({
+                    "id": p.id,
+                    "name": p.name,
+                    "description": p.description,
+                    "category": p.category,
+                    "price": Money::from_db(p.price, &p.currency),
+                    "stock": p.stock,
+                    "images": p.images,
+                    "units_sold": p.units_sold,
+                    "created_at": p.created_at,
+                    "updated_at": p.updated_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "products": products_json,
+                "total": products_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [ARTISAN] Error obteniendo productos: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener tus productos",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_product(
+    pool: web::Data<PgPool>,
+    product_req: web::Json<CreateProductRequest>,
+    artisan: RequireRole<Artisan>,
+) -> Result<HttpResponse> {
+    println!("➕ [ARTISAN] Creando producto '{}' para usuario {}", product_req.name, artisan.user.id);
+
+    if let Err(errors) = product_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_product_price(Some(&product_req.price)) {
+        return Ok(response);
+    }
+
+    // La pieza se publica bajo el perfil de artesano de la cuenta
+    let product = sqlx::query!(
+        r#"
+        INSERT INTO products (artisan_id, name, description, category, price, stock, images)
+        SELECT a.id, $2, $3, $4, $5, $6, $7 FROM artisans a WHERE a.user_id = $1
+        RETURNING id, price, currency, stock, images, created_at
+        "#,
+        artisan.user.id,
+        product_req.name,
+        product_req.description,
+        product_req.category.as_str(),
+        product_req.price,
+        product_req.stock,
+        product_req.images.as_deref().unwrap_or(&[])
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match product {
+        Ok(Some(p)) => {
+            println!("✅ [ARTISAN] Producto {} publicado", p.id);
+            Ok(HttpResponse::Created().json(json!({
+                "message": "Producto publicado",
+                "product": {
+                    "id": p.id,
+                    "name": product_req.name,
+                    "description": product_req.description,
+                    "category": product_req.category,
+                    "price": Money::from_db(p.price, &p.currency),
+                    "stock": p.stock,
+                    "images": p.images,
+                    "created_at": p.created_at
+                }
+            })))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Primero crea tu perfil de artesano"
+        }))),
+        Err(e) => {
+            println!("💥 [ARTISAN] Error creando producto: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al publicar el producto",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_product(
+    pool: web::Data<PgPool>,
+    product_req: web::Json<UpdateProductRequest>,
+    owner: RequireOwnerOf<Product>,
+) -> Result<HttpResponse> {
+    let product_id = owner.resource_id;
+    println!("✏️ [ARTISAN] Actualizando producto {} por usuario {}", product_id, owner.user.id);
+
+    if let Err(errors) = product_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_product_price(product_req.price.as_ref()) {
+        return Ok(response);
+    }
+
+    let product = sqlx::query!(
+        r#"
+        UPDATE products SET
+            name = COALESCE($2, name),
+            description = COALESCE($3, description),
+            category = COALESCE($4, category),
+            price = COALESCE($5, price),
+            stock = COALESCE($6, stock),
+            images = COALESCE($7, images),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND is_active
+        RETURNING id, name, description, category, price, currency, stock, images, updated_at
+        "#,
+        product_id,
+        product_req.name,
+        product_req.description,
+        product_req.category.map(|c| c.as_str()),
+        product_req.price,
+        product_req.stock,
+        product_req.images.as_deref()
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match product {
+        Ok(Some(p)) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Producto actualizado",
+            "product": {
+                "id": p.id,
+                "name": p.name,
+                "description": p.description,
+                "category": p.category,
+                "price": Money::from_db(p.price, &p.currency),
+                "stock": p.stock,
+                "images": p.images,
+                "updated_at": p.updated_at
+            }
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Producto no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [ARTISAN] Error actualizando producto {}: {}", product_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el producto",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Se retira del catálogo sin borrarlo para conservar el historial de compras
+pub async fn delete_product(
+    pool: web::Data<PgPool>,
+    owner: RequireOwnerOf<Product>,
+) -> Result<HttpResponse> {
+    let product_id = owner.resource_id;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE products SET is_active = FALSE, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND is_active
+        "#,
+        product_id
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => {
+            println!("🗑️ [ARTISAN] Producto {} retirado del catálogo", product_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Producto retirado del catálogo"
+            })))
+        }
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Producto no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [ARTISAN] Error retirando producto {}: {}", product_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al retirar el producto",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+fn invalid_product_price(price: Option<&BigDecimal>) -> Option<HttpResponse> {
+    match price {
+        Some(price) if *price <= BigDecimal::zero() => Some(HttpResponse::BadRequest().json(json!({
+            "error": "El precio del producto debe ser mayor a cero"
+        }))),
+        _ => None,
+    }
+}
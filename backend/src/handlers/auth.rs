@@ -1,51 +1,22 @@
 // backend/src/handlers/auth.rs
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{web, HttpRequest, HttpResponse, Result};
 use sqlx::PgPool;
 use serde_json::json;
-use jsonwebtoken::{encode, Header, EncodingKey};
-use chrono::{Utc, Duration};
 use validator::Validate;
 
+use crate::middleware::auth::AuthSession;
 use crate::models::*;
+use crate::services::guests;
+use crate::services::password::{self, PasswordCheck};
+use crate::services::session::{self, SessionError};
 
-// Función auxiliar para hashear contraseñas (simplificado para demo)
-// En producción deberías usar bcrypt o argon2
-fn hash_password(password: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    
-    let mut hasher = DefaultHasher::new();
-    password.hash(&mut hasher);
-    format!("hash_{}", hasher.finish())
-}
-
-// Función auxiliar para verificar contraseñas
-fn verify_password(password: &str, hash: &str) -> bool {
-    hash_password(password) == hash
-}
-
-// Función auxiliar para crear JWT
-fn create_jwt(user: &UserInfo, secret: &str) -> Result<String, jsonwebtoken::errors::Error> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::days(7)) // Token válido por 7 días
-        .expect("valid timestamp")
-        .timestamp() as usize;
-
-    let claims = JwtClaims {
-        user_id: user.id,
-        email: user.email.clone(),
-        user_type: user.user_type.clone(),
-        exp: expiration,
-    };
-
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )
+// User-Agent del cliente para identificar el dispositivo de la sesión
+fn user_agent(req: &HttpRequest) -> Option<&str> {
+    req.headers().get("User-Agent").and_then(|ua| ua.to_str().ok())
 }
 
 pub async fn register(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     register_req: web::Json<RegisterRequest>,
 ) -> Result<HttpResponse> {
@@ -87,15 +58,30 @@ pub async fn register(
         }
     }
 
-    // Hashear contraseña
-    let password_hash = hash_password(&register_req.password);
+    // Hashear contraseña (Argon2id es costoso, se ejecuta fuera del hilo del servidor)
+    let plain_password = register_req.password.clone();
+    let password_hash = match web::block(move || password::hash_password(&plain_password)).await {
+        Ok(Ok(hash)) => hash,
+        Ok(Err(e)) => {
+            println!("💥 [AUTH] Error hasheando contraseña: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al crear la cuenta"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [AUTH] Error ejecutando hash de contraseña: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al crear la cuenta"
+            })));
+        }
+    };
 
     // Crear usuario en la base de datos
     let user_result = sqlx::query!(
         r#"
         INSERT INTO users (first_name, last_name, email, password_hash, user_type) 
         VALUES ($1, $2, $3, $4, 'customer') 
-        RETURNING id, first_name, last_name, email, user_type, created_at
+        RETURNING id, first_name, last_name, email, user_type as "user_type!", created_at
         "#,
         register_req.first_name,
         register_req.last_name,
@@ -117,19 +103,27 @@ pub async fn register(
                 user_type: user_record.user_type,
             };
 
-            // Generar JWT
-            let secret = std::env::var("JWT_SECRET_KEY").unwrap_or_else(|_| {
-                println!("⚠️ [AUTH] JWT_SECRET_KEY no encontrado en .env, usando valor por defecto");
-                "esta_es_una_clave_diferente_para_la_copia".to_string()
-            });
+            // Reservas hechas sin cuenta con este email, si se registra desde el enlace recibido
+            let merged = match register_req.guest_token.as_deref() {
+                Some(token) => merge_guest_bookings(pool.get_ref(), token, &user_info).await,
+                None => 0,
+            };
+            let message = if merged > 0 {
+                format!("Usuario registrado exitosamente. Agregamos {} reservas a tu cuenta.", merged)
+            } else {
+                "Usuario registrado exitosamente".to_string()
+            };
 
-            match create_jwt(&user_info, &secret) {
-                Ok(token) => {
+            // Iniciar sesión y generar tokens
+            match session::start_session(pool.get_ref(), &user_info, user_agent(&req)).await {
+                Ok(issued) => {
                     println!("🎫 [AUTH] JWT generado exitosamente");
 
                     let response = AuthResponse {
-                        message: "Usuario registrado exitosamente".to_string(),
-                        token,
+                        message,
+                        token: issued.access_token,
+                        refresh_token: issued.refresh_token,
+                        expires_in: issued.expires_in,
                         user: user_info,
                     };
 
@@ -162,6 +156,7 @@ pub async fn register(
 }
 
 pub async fn login(
+    req: HttpRequest,
     pool: web::Data<PgPool>,
     login_req: web::Json<LoginRequest>,
 ) -> Result<HttpResponse> {
@@ -179,7 +174,7 @@ pub async fn login(
     // Buscar usuario en la base de datos
     let user_result = sqlx::query_as!(
         User,
-        "SELECT id, first_name, last_name, email, password_hash, user_type, created_at, updated_at FROM users WHERE email = $1",
+        r#"SELECT id, first_name, last_name, email, password_hash, user_type as "user_type!", created_at as "created_at!", updated_at FROM users WHERE email = $1"#,
         login_req.email
     )
     .fetch_optional(pool.get_ref())
@@ -190,9 +185,19 @@ pub async fn login(
             println!("👤 [AUTH] Usuario encontrado: {}", user.email);
 
             // Verificar contraseña
-            if verify_password(&login_req.password, &user.password_hash) {
+            let plain_password = login_req.password.clone();
+            let stored_hash = user.password_hash.clone();
+            let check = web::block(move || password::verify_password(&plain_password, &stored_hash))
+                .await
+                .unwrap_or(PasswordCheck::Invalid);
+
+            if check != PasswordCheck::Invalid {
                 println!("✅ [AUTH] Contraseña correcta");
 
+                if check == PasswordCheck::ValidNeedsRehash {
+                    rehash_password(pool.get_ref(), user.id, &login_req.password).await;
+                }
+
                 let user_info = UserInfo {
                     id: user.id,
                     first_name: user.first_name,
@@ -201,19 +206,16 @@ pub async fn login(
                     user_type: user.user_type,
                 };
 
-                // Generar JWT
-                let secret = std::env::var("JWT_SECRET_KEY").unwrap_or_else(|_| {
-                    println!("⚠️ [AUTH] JWT_SECRET_KEY no encontrado en .env, usando valor por defecto");
-                    "esta_es_una_clave_diferente_para_la_copia".to_string()
-                });
-
-                match create_jwt(&user_info, &secret) {
-                    Ok(token) => {
+                // Iniciar sesión y generar tokens
+                match session::start_session(pool.get_ref(), &user_info, user_agent(&req)).await {
+                    Ok(issued) => {
                         println!("🎫 [AUTH] Login exitoso, JWT generado");
 
                         let response = AuthResponse {
                             message: "Login exitoso".to_string(),
-                            token,
+                            token: issued.access_token,
+                            refresh_token: issued.refresh_token,
+                            expires_in: issued.expires_in,
                             user: user_info,
                         };
 
@@ -250,10 +252,141 @@ pub async fn login(
     }
 }
 
+pub async fn refresh(
+    pool: web::Data<PgPool>,
+    refresh_req: web::Json<RefreshRequest>,
+) -> Result<HttpResponse> {
+    println!("🔄 [AUTH] Renovando access token...");
+
+    match session::rotate_session(pool.get_ref(), &refresh_req.refresh_token).await {
+        Ok((user_info, issued)) => {
+            println!("✅ [AUTH] Sesión {} renovada para usuario {}", issued.session_id, user_info.id);
+
+            let response = AuthResponse {
+                message: "Token renovado".to_string(),
+                token: issued.access_token,
+                refresh_token: issued.refresh_token,
+                expires_in: issued.expires_in,
+                user: user_info,
+            };
+
+            Ok(HttpResponse::Ok().json(response))
+        }
+        Err(e @ (SessionError::Database(_) | SessionError::Jwt(_))) => {
+            println!("💥 [AUTH] Error renovando sesión: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error renovando la sesión",
+                "details": e.to_string()
+            })))
+        }
+        Err(e) => {
+            println!("❌ [AUTH] Refresh rechazado: {}", e);
+            Ok(HttpResponse::Unauthorized().json(json!({
+                "error": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn logout(
+    pool: web::Data<PgPool>,
+    auth: AuthSession,
+) -> Result<HttpResponse> {
+    println!("👋 [AUTH] Cerrando sesión {} del usuario {}", auth.session_id, auth.user.id);
+
+    match session::revoke_session(pool.get_ref(), auth.session_id, auth.user.id).await {
+        Ok(_) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Sesión cerrada exitosamente"
+        }))),
+        Err(e) => {
+            println!("💥 [AUTH] Error cerrando sesión: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al cerrar la sesión",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn logout_all(
+    pool: web::Data<PgPool>,
+    auth: AuthSession,
+) -> Result<HttpResponse> {
+    println!("👋 [AUTH] Cerrando todas las sesiones del usuario {}", auth.user.id);
+
+    match session::revoke_all_sessions(pool.get_ref(), auth.user.id).await {
+        Ok(count) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Sesión cerrada en todos los dispositivos",
+            "sessions_revoked": count
+        }))),
+        Err(e) => {
+            println!("💥 [AUTH] Error cerrando sesiones: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al cerrar las sesiones",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
 pub async fn me(user: UserInfo) -> Result<HttpResponse> {
     println!("👤 [AUTH] Obteniendo información del usuario: {}", user.email);
     
     Ok(HttpResponse::Ok().json(json!({
         "user": user
     })))
-}
\ No newline at end of file
+}
+
+// Reemplaza un hash antiguo por uno Argon2id. Si falla, el login continúa
+// y se volverá a intentar en el siguiente inicio de sesión.
+async fn rehash_password(pool: &PgPool, user_id: i32, plain_password: &str) {
+    let plain_password = plain_password.to_string();
+    let new_hash = match web::block(move || password::hash_password(&plain_password)).await {
+        Ok(Ok(hash)) => hash,
+        _ => {
+            println!("⚠️ [AUTH] No se pudo regenerar el hash del usuario {}", user_id);
+            return;
+        }
+    };
+
+    let result = sqlx::query!(
+        "UPDATE users SET password_hash = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+        new_hash,
+        user_id
+    )
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(_) => println!("🔄 [AUTH] Hash de contraseña migrado a Argon2id para usuario {}", user_id),
+        Err(e) => println!("⚠️ [AUTH] Error guardando nuevo hash para usuario {}: {}", user_id, e),
+    }
+}
+
+// Une las reservas de huésped a la cuenta recién creada. Si falla, el registro
+// continúa y el usuario puede reclamarlas después con el mismo enlace.
+async fn merge_guest_bookings(pool: &PgPool, token: &str, user: &UserInfo) -> u64 {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            println!("⚠️ [AUTH] No se pudieron unir las reservas de huésped del usuario {}: {}", user.id, e);
+            return 0;
+        }
+    };
+
+    let merged = match guests::merge_into_account(&mut tx, token, user.id, &user.email).await {
+        Ok(merged) => merged,
+        Err(e) => {
+            println!("⚠️ [AUTH] No se pudieron unir las reservas de huésped del usuario {}: {}", user.id, e);
+            return 0;
+        }
+    };
+
+    match tx.commit().await {
+        Ok(_) => merged,
+        Err(e) => {
+            println!("⚠️ [AUTH] No se pudieron unir las reservas de huésped del usuario {}: {}", user.id, e);
+            0
+        }
+    }
+}
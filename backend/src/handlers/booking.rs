@@ -1,12 +1,17 @@
 use actix_web::{web, HttpResponse, Result};
+use bigdecimal::Zero;
 use sqlx::PgPool;
 use serde_json::json;
-use bigdecimal::BigDecimal;
-use std::str::FromStr;
 use validator::Validate;
 
 use crate::models::*;
-use crate::handlers::hotel::verify_hotel_ownership;
+use crate::money::Money;
+use crate::middleware::roles::{Hotel, RequireOwnerOf};
+use crate::services::bookings::{self, BookingError, BookingHolder};
+use crate::services::inventory;
+use crate::services::notifications;
+use crate::services::payments::PaymentProviders;
+use crate::services::refunds::{self, RefundError};
 
 #[derive(serde::Serialize)]
 pub struct HotelBookingDetail {
@@ -14,11 +19,13 @@ pub struct HotelBookingDetail {
     check_in: chrono::NaiveDate,
     check_out: chrono::NaiveDate,
     guests: i32,
-    total_price: f64,
+    total_price: Money,
     status: String,
     created_at: chrono::DateTime<chrono::Utc>,
     customer_name: String,
     customer_email: String,
+    // Reservada sin cuenta desde el checkout de huéspedes
+    guest_checkout: bool,
 }
 
 pub async fn create_booking(
@@ -38,173 +45,76 @@ pub async fn create_booking(
         })));
     }
 
-    // Validar fechas
-    if booking_req.check_out <= booking_req.check_in {
-        println!("❌ [BOOKING] Error: fecha checkout <= checkin");
-        return Ok(HttpResponse::BadRequest().json(json!({ 
-            "error": "La fecha de check-out debe ser posterior a la de check-in" 
-        })));
-    }
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [BOOKING] Error del servidor: {}", e);
+        HttpResponse::InternalServerError().json(json!({ 
+            "error": "Error del servidor al crear la reserva",
+            "details": e.to_string()
+        }))
+    };
 
-    // Verificar que el hotel existe y está aprobado
-    println!("🏨 [BOOKING] Verificando hotel ID: {}", booking_req.hotel_id);
-    let hotel = match sqlx::query!(
-        "SELECT id, name, location, address, price FROM hotels WHERE id = $1 AND status = 'approved'",
-        booking_req.hotel_id
-    )
-    .fetch_optional(pool.get_ref()).await {
-        Ok(Some(hotel)) => {
-            println!("✅ [BOOKING] Hotel encontrado: {}", hotel.name);
-            hotel
-        },
-        Ok(None) => {
+    // Cotizar, apartar habitaciones y crear la reserva en una sola transacción
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let booking = match bookings::book_stay(&mut tx, BookingHolder::User(user.id), &booking_req).await {
+        Ok(booking) => booking,
+        Err(BookingError::Database(e)) => return Ok(internal_error(e)),
+        Err(BookingError::HotelUnavailable) => {
             println!("❌ [BOOKING] Hotel no encontrado o no aprobado");
             return Ok(HttpResponse::NotFound().json(json!({ 
-                "error": "Hotel no encontrado o no disponible" 
-            })));
-        },
-        Err(e) => {
-            println!("💥 [BOOKING] Error BD al verificar hotel: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(json!({ 
-                "error": "Error del servidor al verificar hotel",
-                "details": e.to_string()
+                "error": BookingError::HotelUnavailable.to_string() 
             })));
         }
-    };
+        Err(BookingError::SoldOut { night, rooms_left }) => {
+            // Liberar los bloqueos antes de responder
+            let _ = tx.rollback().await;
 
-    // Calcular precio total
-    let nights = (booking_req.check_out - booking_req.check_in).num_days();
-    println!("📊 [BOOKING] Noches: {}", nights);
-    
-    let hotel_price: f64 = match hotel.price.to_string().parse() {
-        Ok(price) => price,
-        Err(e) => {
-            println!("❌ [BOOKING] Error parseando precio del hotel: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(json!({ 
-                "error": "Error en el precio del hotel" 
+            if booking_req.join_waitlist.unwrap_or(false) {
+                return add_to_waitlist(pool.get_ref(), &booking_req, &user, night).await;
+            }
+
+            return Ok(HttpResponse::Conflict().json(json!({ 
+                "error": format!("No hay habitaciones disponibles para la noche del {}", night),
+                "night": night,
+                "rooms_left": rooms_left
             })));
         }
-    };
-    
-    let base_price = hotel_price * nights as f64 * booking_req.rooms as f64;
-    println!("💰 [BOOKING] Precio base: {}", base_price);
-    
-    let addon_price = if let Some(addons) = &booking_req.addon_services {
-        calculate_addon_price(addons)
-    } else { 0.0 };
-    println!("➕ [BOOKING] Precio addons: {}", addon_price);
-
-    let total_price = base_price + addon_price;
-    println!("🎯 [BOOKING] Precio total: {}", total_price);
-    
-    let total_decimal = match BigDecimal::from_str(&total_price.to_string()) {
-        Ok(decimal) => decimal,
         Err(e) => {
-            println!("❌ [BOOKING] Error convirtiendo precio a decimal: {}", e);
-            return Ok(HttpResponse::InternalServerError().json(json!({ 
-                "error": "Error calculando precio total" 
+            println!("❌ [BOOKING] Reserva inválida: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({ 
+                "error": e.to_string() 
             })));
         }
     };
-    
-    // Generar referencia única
-    let booking_reference = loop {
-        let reference = format!("MY{}", uuid::Uuid::new_v4().to_string().replace("-", "")[..6].to_uppercase());
-        
-        // Verificar que no exista
-        let exists = sqlx::query!(
-            "SELECT id FROM bookings WHERE booking_reference = $1",
-            reference
-        )
-        .fetch_optional(pool.get_ref())
-        .await;
-        
-        match exists {
-            Ok(None) => break reference, // No existe, podemos usarla
-            Ok(Some(_)) => continue, // Existe, generar otra
-            Err(e) => {
-                println!("❌ [BOOKING] Error verificando referencia: {}", e);
-                return Ok(HttpResponse::InternalServerError().json(json!({ 
-                    "error": "Error generando referencia de reserva" 
-                })));
-            }
-        }
-    };
-    
-    println!("🎫 [BOOKING] Referencia generada: {}", booking_reference);
 
-    // Crear la reserva en la base de datos
-    println!("💾 [BOOKING] Insertando en BD...");
-    let booking = sqlx::query!(
-        r#"
-        INSERT INTO bookings 
-        (user_id, hotel_id, check_in, check_out, guests, rooms, total_price, 
-         special_requests, addon_services, status, payment_status, booking_reference)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'pending', 'pending', $10)
-        RETURNING id, created_at
-        "#,
-        user.id,
-        booking_req.hotel_id,
-        booking_req.check_in,
-        booking_req.check_out,
-        booking_req.guests,
-        booking_req.rooms,
-        total_decimal,
-        booking_req.special_requests,
-        booking_req.addon_services.as_ref(),
-        booking_reference
-    )
-    .fetch_one(pool.get_ref())
-    .await;
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
 
-    match booking {
-        Ok(booking_record) => {
-            println!("✅ [BOOKING] Reserva creada exitosamente! ID: {}", booking_record.id);
-            
-            let response_data = json!({
-                "message": "Reserva creada exitosamente",
-                "booking": {
-                    "id": booking_record.id,
-                    "reference": booking_reference,
-                    "hotel_name": hotel.name,
-                    "hotel_location": hotel.location,
-                    "check_in": booking_req.check_in,
-                    "check_out": booking_req.check_out,
-                    "guests": booking_req.guests,
-                    "rooms": booking_req.rooms,
-                    "total_price": total_price,
-                    "status": "pending",
-                    "created_at": booking_record.created_at
-                }
-            });
-            
-            println!("📤 [BOOKING] Enviando respuesta: {}", response_data);
-            Ok(HttpResponse::Created().json(response_data))
-        },
-        Err(e) => {
-            println!("💥 [BOOKING] ERROR AL INSERTAR EN BD: {}", e);
-            
-            // Analizar el tipo de error para dar mejor feedback
-            let error_message = if e.to_string().contains("foreign key") {
-                if e.to_string().contains("user_id") {
-                    "Error: Usuario no válido"
-                } else if e.to_string().contains("hotel_id") {
-                    "Error: Hotel no válido"
-                } else {
-                    "Error: Referencia de datos inválida"
-                }
-            } else if e.to_string().contains("unique") {
-                "Error: Referencia de reserva duplicada"
-            } else {
-                "Error al guardar la reserva en la base de datos"
-            };
-            
-            Ok(HttpResponse::InternalServerError().json(json!({ 
-                "error": error_message,
-                "technical_details": e.to_string()
-            })))
+    println!("✅ [BOOKING] Reserva creada exitosamente! ID: {} ({})", booking.id, booking.reference);
+    println!("🎯 [BOOKING] Precio total: {} {}", booking.total_price.amount(), booking.total_price.currency().as_str());
+
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Reserva creada exitosamente",
+        "booking": {
+            "id": booking.id,
+            "reference": booking.reference,
+            "hotel_name": booking.hotel_name,
+            "hotel_location": booking.hotel_location,
+            "check_in": booking.check_in,
+            "check_out": booking.check_out,
+            "guests": booking.guests,
+            "rooms": booking.rooms,
+            "addon_services": booking.price_breakdown.addons,
+            "total_price": booking.total_price,
+            "price_breakdown": booking.price_breakdown,
+            "status": booking.status,
+            "created_at": booking.created_at
         }
-    }
+    })))
 }
 
 pub async fn get_my_bookings(
@@ -216,7 +126,7 @@ pub async fn get_my_bookings(
     let bookings = sqlx::query!(
         r#"
         SELECT b.id, b.check_in, b.check_out, b.guests, b.rooms, 
-               b.total_price::text as total_price_text, b.status, b.payment_status,
+               b.total_price, b.currency, b.status, b.payment_status,
                b.special_requests, b.addon_services, b.created_at, b.booking_reference,
                h.name as hotel_name, h.location as hotel_location, h.address as hotel_address
         FROM bookings b
@@ -244,7 +154,7 @@ pub async fn get_my_bookings(
                     "check_out": b.check_out, 
                     "guests": b.guests, 
                     "rooms": b.rooms,
-                    "total_price": b.total_price_text.unwrap_or("0.0".to_string()).parse::<f64>().unwrap_or(0.0),
+                    "total_price": Money::from_db(b.total_price, &b.currency),
                     "status": b.status, 
                     "payment_status": b.payment_status, 
                     "special_requests": b.special_requests,
@@ -269,75 +179,338 @@ pub async fn get_my_bookings(
     }
 }
 
+// Cambia fechas, habitaciones o huéspedes; responde con la diferencia de precio
+pub async fn modify_booking(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    modify_req: web::Json<ModifyBookingRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let booking_id = path.into_inner();
+    println!("✏️ [BOOKING] Modificando reserva {} para usuario {}", booking_id, user.id);
+
+    if let Err(errors) = modify_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({ 
+            "error": "Datos inválidos", 
+            "details": errors 
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [BOOKING] Error modificando reserva {}: {}", booking_id, e);
+        HttpResponse::InternalServerError().json(json!({ 
+            "error": "Error del servidor al modificar la reserva",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let modified = match bookings::modify_stay(&mut tx, booking_id, user.id, &modify_req).await {
+        Ok(modified) => modified,
+        Err(BookingError::Database(e)) => return Ok(internal_error(e)),
+        Err(BookingError::NotFound) => {
+            return Ok(HttpResponse::NotFound().json(json!({ 
+                "error": BookingError::NotFound.to_string() 
+            })));
+        }
+        Err(BookingError::SoldOut { night, rooms_left }) => {
+            return Ok(HttpResponse::Conflict().json(json!({ 
+                "error": format!("No hay habitaciones disponibles para la noche del {}", night),
+                "night": night,
+                "rooms_left": rooms_left
+            })));
+        }
+        Err(
+            e @ (BookingError::NotModifiable(_)
+            | BookingError::StayStarted
+            | BookingError::PartOfTrip
+            | BookingError::PaymentStarted
+            | BookingError::CurrencyChanged),
+        ) => {
+            return Ok(HttpResponse::Conflict().json(json!({ 
+                "error": e.to_string() 
+            })));
+        }
+        Err(e) => {
+            println!("❌ [BOOKING] Modificación inválida: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({ 
+                "error": e.to_string() 
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!(
+        "✅ [BOOKING] Reserva {} en versión {} (diferencia {} {})",
+        booking_id,
+        modified.version,
+        modified.price_difference.amount(),
+        modified.price_difference.currency().as_str()
+    );
+
+    let message = if modified.price_difference.is_negative() {
+        "Reserva modificada. El nuevo precio es menor al anterior."
+    } else if modified.price_difference.amount().is_zero() {
+        "Reserva modificada sin cambio de precio."
+    } else {
+        "Reserva modificada. El nuevo precio es mayor al anterior."
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": message,
+        "booking": modified.booking,
+        "version": modified.version,
+        "previous_total": modified.previous_total,
+        "price_difference": modified.price_difference
+    })))
+}
+
+// Versiones anteriores de una reserva del usuario, de la más reciente a la primera
+pub async fn get_booking_versions(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let booking_id = path.into_inner();
+
+    let versions = sqlx::query!(
+        r#"
+        SELECT v.version, v.check_in, v.check_out, v.guests, v.rooms, v.total_price, v.currency,
+               v.addon_services, v.special_requests, v.replaced_at
+        FROM booking_versions v
+        JOIN bookings b ON v.booking_id = b.id
+        WHERE v.booking_id = $1 AND b.user_id = $2
+        ORDER BY v.version DESC
+        "#,
+        booking_id,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match versions {
+        Ok(rows) => {
+            let version_list: Vec<serde_json::Value> = rows
+                .into_iter()
+                .map(|v| json!({
+                    "version": v.version,
+                    "check_in": v.check_in,
+                    "check_out": v.check_out,
+                    "guests": v.guests,
+                    "rooms": v.rooms,
+                    "total_price": Money::from_db(v.total_price, &v.currency),
+                    "addon_services": v.addon_services,
+                    "special_requests": v.special_requests,
+                    "replaced_at": v.replaced_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "versions": version_list })))
+        }
+        Err(e) => {
+            println!("💥 [BOOKING] Error obteniendo versiones de la reserva {}: {}", booking_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({ 
+                "error": "No se pudieron obtener las versiones de la reserva",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
 pub async fn cancel_booking(
     pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
     path: web::Path<i32>,
     cancel_req: web::Json<UpdateBookingStatusRequest>,
     user: UserInfo,
 ) -> Result<HttpResponse> {
     let booking_id = path.into_inner();
     println!("🚫 [BOOKING] Cancelando reserva {} para usuario {}", booking_id, user.id);
-    
-    let result = sqlx::query!(
+
+    Ok(cancel_stay(
+        pool.get_ref(),
+        providers.get_ref(),
+        booking_id,
+        BookingHolder::User(user.id),
+        cancel_req.cancellation_reason.as_deref(),
+    ).await)
+}
+
+// Cancelación por parte del titular (usuario o huésped sin cuenta): política,
+// cancelación, inventario y reembolso en una sola transacción
+pub(crate) async fn cancel_stay(
+    pool: &PgPool,
+    providers: &PaymentProviders,
+    booking_id: i32,
+    holder: BookingHolder,
+    reason: Option<&str>,
+) -> HttpResponse {
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [BOOKING] Error cancelando reserva: {}", e);
+        HttpResponse::InternalServerError().json(json!({ 
+            "error": "Error al cancelar la reserva",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_error(e),
+    };
+
+    let quote = match refunds::quote_cancellation(&mut tx, booking_id, holder).await {
+        Ok(quote) => quote,
+        Err(e) => return refund_error_response(e),
+    };
+
+    // Las partes de un viaje sin pagar solo se cancelan con el viaje completo
+    match sqlx::query!(
+        r#"
+        SELECT t.trip_reference FROM bookings b
+        JOIN trips t ON b.trip_id = t.id
+        WHERE b.id = $1 AND t.payment_status <> 'paid'
+        "#,
+        booking_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(trip)) => {
+            return HttpResponse::Conflict().json(json!({
+                "error": format!(
+                    "Esta reserva es parte del viaje {}, pendiente de pago; cancela el viaje completo",
+                    trip.trip_reference
+                )
+            }));
+        }
+        Ok(None) => {}
+        Err(e) => return internal_error(e),
+    }
+
+    let cancelled = match sqlx::query!(
         r#"
-        UPDATE bookings SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+        UPDATE bookings SET status = $2, cancelled_at = CURRENT_TIMESTAMP,
             cancellation_reason = $3, updated_at = CURRENT_TIMESTAMP
-        WHERE id = $1 AND user_id = $2 AND status != 'cancelled'
+        WHERE id = $1
+        RETURNING hotel_id, check_in, check_out, rooms
         "#,
-        booking_id, user.id, cancel_req.cancellation_reason
+        booking_id, BookingStatus::Cancelled.as_str(), reason
     )
-    .execute(pool.get_ref()).await;
-    
-    match result {
-        Ok(res) if res.rows_affected() > 0 => {
-            println!("✅ [BOOKING] Reserva {} cancelada", booking_id);
-            Ok(HttpResponse::Ok().json(json!({ 
-                "message": "Reserva cancelada exitosamente" 
-            })))
-        },
-        Ok(_) => {
-            println!("❌ [BOOKING] Reserva {} no encontrada o ya cancelada", booking_id);
-            Ok(HttpResponse::NotFound().json(json!({ 
-                "error": "Reserva no encontrada o ya cancelada" 
-            })))
+    .fetch_one(&mut *tx).await {
+        Ok(cancelled) => cancelled,
+        Err(e) => return internal_error(e),
+    };
+
+    if let Err(e) = bookings::record_status_change(
+        &mut tx,
+        booking_id,
+        Some(quote.status),
+        BookingStatus::Cancelled,
+        holder.user_id(),
+        reason,
+    ).await {
+        return internal_error(e);
+    }
+
+    // Devolver las habitaciones al inventario
+    if let Err(e) = inventory::release_rooms(
+        &mut tx,
+        cancelled.hotel_id,
+        cancelled.check_in,
+        cancelled.check_out,
+        cancelled.rooms,
+    ).await {
+        return internal_error(e);
+    }
+
+    let refund = match refunds::issue_refund(&mut tx, providers, &quote, reason).await {
+        Ok(refund) => refund,
+        Err(e) => return refund_error_response(e),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_error(e);
+    }
+
+    println!("✅ [BOOKING] Reserva {} cancelada (reembolso {} {})", booking_id, quote.refund.amount(), quote.refund.currency().as_str());
+    HttpResponse::Ok().json(json!({ 
+        "message": match &refund {
+            Some(_) => "Reserva cancelada. Tu reembolso está en camino.",
+            None => "Reserva cancelada exitosamente",
         },
-        Err(e) => {
-            println!("💥 [BOOKING] Error cancelando reserva: {}", e);
-            Ok(HttpResponse::InternalServerError().json(json!({ 
+        "cancellation": quote,
+        "refund": refund
+    }))
+}
+
+// Muestra cuánto se reembolsaría si el usuario cancela hoy
+pub async fn preview_refund(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let booking_id = path.into_inner();
+
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => return Ok(refund_error_response(e.into())),
+    };
+
+    match refunds::quote_cancellation(&mut conn, booking_id, BookingHolder::User(user.id)).await {
+        Ok(quote) => Ok(HttpResponse::Ok().json(json!({ "cancellation": quote }))),
+        Err(e) => Ok(refund_error_response(e)),
+    }
+}
+
+pub(crate) fn refund_error_response(error: RefundError) -> HttpResponse {
+    match error {
+        RefundError::BookingNotFound => HttpResponse::NotFound().json(json!({ 
+            "error": error.to_string() 
+        })),
+        RefundError::NotCancellable(_) | RefundError::StayStarted => HttpResponse::Conflict().json(json!({ 
+            "error": error.to_string() 
+        })),
+        RefundError::ProviderUnavailable(_) | RefundError::Payment(_) => {
+            println!("💥 [BOOKING] Error emitiendo reembolso: {}", error);
+            HttpResponse::BadGateway().json(json!({ 
+                "error": "No se pudo procesar el reembolso; la reserva sigue activa",
+                "details": error.to_string()
+            }))
+        }
+        RefundError::Database(e) => {
+            println!("💥 [BOOKING] Error calculando reembolso: {}", e);
+            HttpResponse::InternalServerError().json(json!({ 
                 "error": "Error al cancelar la reserva",
                 "details": e.to_string()
-            })))
+            }))
         }
     }
 }
 
 pub async fn get_hotel_bookings(
     pool: web::Data<PgPool>,
-    path: web::Path<i32>,
-    user: UserInfo,
+    owner: RequireOwnerOf<Hotel>,
 ) -> Result<HttpResponse> {
-    let hotel_id = path.into_inner();
-    
-    match verify_hotel_ownership(pool.get_ref(), hotel_id, &user).await {
-        Ok(true) => (),
-        Ok(false) => return Ok(HttpResponse::Forbidden().json(json!({
-            "error": "No tienes permiso para ver las reservas de este hotel"
-        }))),
-        Err(_) => return Ok(HttpResponse::InternalServerError().json(json!({
-            "error": "Error al verificar permisos"
-        }))),
-    }
+    let hotel_id = owner.resource_id;
+    println!("📋 [BOOKING] Usuario {} ({}) consulta reservas del hotel {}", owner.user.id, owner.user.role().as_str(), hotel_id);
     
-    let bookings = sqlx::query_as!(
-        HotelBookingDetail,
+    let bookings = sqlx::query!(
         r#"
-        SELECT b.id, b.check_in, b.check_out, b.guests,
-               b.total_price::numeric::float8 as "total_price!",
+        SELECT b.id, b.check_in, b.check_out, b.guests, b.total_price, b.currency,
                b.status as "status!", b.created_at as "created_at!",
-               u.first_name || ' ' || u.last_name as "customer_name!",
-               u.email as "customer_email!"
+               COALESCE(u.first_name || ' ' || u.last_name, g.first_name || ' ' || g.last_name) as "customer_name!",
+               COALESCE(u.email, g.email) as "customer_email!",
+               b.user_id IS NULL as "guest_checkout!"
         FROM bookings b 
-        JOIN users u ON b.user_id = u.id
+        LEFT JOIN users u ON b.user_id = u.id
+        LEFT JOIN guests g ON b.guest_id = g.id
         WHERE b.hotel_id = $1 
         ORDER BY b.check_in DESC
         "#,
@@ -345,9 +518,27 @@ pub async fn get_hotel_bookings(
     ).fetch_all(pool.get_ref()).await;
     
     match bookings {
-        Ok(booking_list) => Ok(HttpResponse::Ok().json(json!({ 
-            "bookings": booking_list 
-        }))),
+        Ok(rows) => {
+            let booking_list: Vec<HotelBookingDetail> = rows
+                .into_iter()
+                .map(|b| HotelBookingDetail {
+                    id: b.id,
+                    check_in: b.check_in,
+                    check_out: b.check_out,
+                    guests: b.guests,
+                    total_price: Money::from_db(b.total_price, &b.currency),
+                    status: b.status,
+                    created_at: b.created_at,
+                    customer_name: b.customer_name,
+                    customer_email: b.customer_email,
+                    guest_checkout: b.guest_checkout,
+                })
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ 
+                "bookings": booking_list 
+            })))
+        },
         Err(e) => {
             println!("💥 [HOTEL_BOOKINGS] Error: {}", e);
             Ok(HttpResponse::InternalServerError().json(json!({ 
@@ -358,12 +549,405 @@ pub async fn get_hotel_bookings(
     }
 }
 
-fn calculate_addon_price(addons: &serde_json::Value) -> f64 {
-    if let Some(addon_array) = addons.as_array() {
-        addon_array.iter().fold(0.0, |acc, addon| {
-            if let Some(price) = addon.get("price").and_then(|p| p.as_f64()) {
-                acc + price
-            } else { acc }
-        })
-    } else { 0.0 }
-}
\ No newline at end of file
+// Transiciones del hotel: confirmar o rechazar, registrar llegada, salida o no-show
+pub async fn update_booking_status(
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<(i32, i32)>,
+    status_req: web::Json<UpdateBookingStatusRequest>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    let (_, booking_id) = path.into_inner();
+
+    let target = match status_req.status {
+        Some(BookingStatus::Pending) | Some(BookingStatus::Cancelled) | None => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Indica el nuevo estado: confirmed, rejected, checked_in, checked_out o no_show"
+            })));
+        }
+        Some(target) => target,
+    };
+    println!("🏨 [BOOKING] Hotel {} cambiando reserva {} a '{}'", hotel_id, booking_id, target.as_str());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [BOOKING] Error actualizando reserva {}: {}", booking_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar el estado de la reserva",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let booking = match sqlx::query!(
+        r#"
+        SELECT b.user_id, b.guest_id, b.status, b.booking_reference, b.check_in, b.check_out, b.rooms,
+               b.check_in <= CURRENT_DATE as "arrival_due!", h.name as hotel_name
+        FROM bookings b
+        JOIN hotels h ON b.hotel_id = h.id
+        WHERE b.id = $1 AND b.hotel_id = $2
+        FOR UPDATE OF b
+        "#,
+        booking_id,
+        hotel_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(booking)) => booking,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Reserva no encontrada"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = BookingStatus::from_db(&booking.status).unwrap_or(BookingStatus::Pending);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar una reserva de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if matches!(target, BookingStatus::CheckedIn | BookingStatus::NoShow) && !booking.arrival_due {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!("La llegada está programada para el {}", booking.check_in)
+        })));
+    }
+
+    let reason = status_req.cancellation_reason.as_deref();
+
+    // Al rechazar se calcula el reembolso total antes de cambiar el estado
+    let rejection_quote = if target == BookingStatus::Rejected {
+        match refunds::quote_hotel_rejection(&mut tx, booking_id).await {
+            Ok(quote) => Some(quote),
+            Err(e) => return Ok(refund_error_response(e)),
+        }
+    } else {
+        None
+    };
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE bookings SET status = $2::varchar,
+            confirmed_at = CASE WHEN $2::varchar = 'confirmed' THEN CURRENT_TIMESTAMP ELSE confirmed_at END,
+            checked_in_at = CASE WHEN $2::varchar = 'checked_in' THEN CURRENT_TIMESTAMP ELSE checked_in_at END,
+            checked_out_at = CASE WHEN $2::varchar = 'checked_out' THEN CURRENT_TIMESTAMP ELSE checked_out_at END,
+            cancelled_at = CASE WHEN $2::varchar = 'rejected' THEN CURRENT_TIMESTAMP ELSE cancelled_at END,
+            cancellation_reason = CASE WHEN $2::varchar = 'rejected' THEN $3 ELSE cancellation_reason END,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        booking_id,
+        target.as_str(),
+        reason
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = bookings::record_status_change(&mut tx, booking_id, Some(current), target, Some(owner.user.id), reason).await {
+        return Ok(internal_error(e));
+    }
+
+    let mut refund = None;
+    if let Some(quote) = &rejection_quote {
+        if let Err(e) = inventory::release_rooms(
+            &mut tx,
+            hotel_id,
+            booking.check_in,
+            booking.check_out,
+            booking.rooms,
+        ).await {
+            return Ok(internal_error(e));
+        }
+
+        refund = match refunds::issue_refund(&mut tx, providers.get_ref(), quote, reason).await {
+            Ok(refund) => refund,
+            Err(e) => return Ok(refund_error_response(e)),
+        };
+    }
+
+    let (title, message) = match target {
+        BookingStatus::Confirmed => ("Reserva confirmada", format!("{} confirmó tu reserva {}.", booking.hotel_name, booking.booking_reference)),
+        BookingStatus::Rejected => ("Reserva rechazada", format!(
+            "{} no pudo aceptar tu reserva {}. {}",
+            booking.hotel_name,
+            booking.booking_reference,
+            reason.unwrap_or("")
+        )),
+        BookingStatus::CheckedIn => ("¡Bienvenido!", format!("Registramos tu llegada a {}. ¡Disfruta tu estancia!", booking.hotel_name)),
+        BookingStatus::CheckedOut => ("Gracias por tu visita", format!("Tu salida de {} quedó registrada.", booking.hotel_name)),
+        BookingStatus::NoShow => ("No registramos tu llegada", format!(
+            "{} marcó tu reserva {} como no presentada.",
+            booking.hotel_name,
+            booking.booking_reference
+        )),
+        BookingStatus::Pending | BookingStatus::Cancelled => ("Reserva actualizada", format!("Tu reserva {} fue actualizada.", booking.booking_reference)),
+    };
+
+    if let Err(e) = notifications::notify_booking_holder(
+        &mut tx,
+        booking.user_id,
+        booking.guest_id,
+        "booking_status",
+        title,
+        message.trim(),
+        Some(json!({ "booking_id": booking_id, "status": target.as_str() })),
+    )
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [BOOKING] Reserva {} ahora está '{}'", booking_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Estado de la reserva actualizado",
+        "booking": {
+            "id": booking_id,
+            "reference": booking.booking_reference,
+            "from_status": current.as_str(),
+            "status": target.as_str()
+        },
+        "refund": refund
+    })))
+}
+
+pub async fn get_booking_history(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let (_, booking_id) = path.into_inner();
+
+    let entries = sqlx::query!(
+        r#"
+        SELECT s.id, s.from_status, s.to_status, s.reason, s.changed_at,
+               u.id as "changed_by_id?",
+               COALESCE(u.first_name || ' ' || u.last_name, 'Huésped sin cuenta') as "changed_by_name!"
+        FROM booking_status_history s
+        JOIN bookings b ON s.booking_id = b.id
+        LEFT JOIN users u ON s.changed_by = u.id
+        WHERE s.booking_id = $1 AND b.hotel_id = $2
+        ORDER BY s.changed_at, s.id
+        "#,
+        booking_id,
+        owner.resource_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match entries {
+        Ok(entries) => {
+            let history: Vec<serde_json::Value> = entries
+                .into_iter()
+                .map(|h| json!({
+                    "id": h.id,
+                    "from_status": h.from_status,
+                    "to_status": h.to_status,
+                    "reason": h.reason,
+                    "changed_at": h.changed_at,
+                    "changed_by": {
+                        "id": h.changed_by_id,
+                        "name": h.changed_by_name
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "history": history })))
+        }
+        Err(e) => {
+            println!("💥 [BOOKING] Error obteniendo historial de la reserva {}: {}", booking_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener el historial de la reserva",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Reserva por referencia para el titular, el dueño del hotel (recepción) o un administrador
+pub async fn get_booking_by_reference(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let reference = path.into_inner();
+    println!("🔎 [BOOKING] Usuario {} consulta la reserva {}", user.id, reference);
+
+    match load_booking_by_reference(pool.get_ref(), &reference).await {
+        Ok(Some(booking))
+            if booking.user_id == Some(user.id)
+                || booking.owner_id == Some(user.id)
+                || user.role() == Role::Admin =>
+        {
+            Ok(HttpResponse::Ok().json(json!({ "booking": booking })))
+        }
+        // Sin permiso se responde igual que si no existiera
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({ 
+            "error": "Reserva no encontrada" 
+        }))),
+        Err(e) => Ok(lookup_error(e)),
+    }
+}
+
+// Variante para huéspedes sin sesión: la referencia sola no basta, también
+// debe coincidir el email del titular
+pub async fn lookup_booking(
+    pool: web::Data<PgPool>,
+    lookup_req: web::Json<BookingLookupRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = lookup_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({ 
+            "error": "Datos inválidos", 
+            "details": errors 
+        })));
+    }
+
+    match load_booking_by_reference(pool.get_ref(), &lookup_req.reference).await {
+        Ok(Some(booking)) if booking.guest_email.eq_ignore_ascii_case(lookup_req.email.trim()) => {
+            Ok(HttpResponse::Ok().json(json!({ "booking": booking })))
+        }
+        Ok(_) => {
+            println!("❌ [BOOKING] Consulta sin coincidencia para la referencia {}", lookup_req.reference.trim());
+            Ok(HttpResponse::NotFound().json(json!({ 
+                "error": "No encontramos una reserva con esa referencia y email" 
+            })))
+        }
+        Err(e) => Ok(lookup_error(e)),
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct BookingByReference {
+    id: i32,
+    reference: String,
+    #[serde(skip)]
+    user_id: Option<i32>,
+    #[serde(skip)]
+    owner_id: Option<i32>,
+    hotel_id: i32,
+    hotel_name: String,
+    hotel_location: String,
+    hotel_address: Option<String>,
+    guest_name: String,
+    guest_email: String,
+    check_in: chrono::NaiveDate,
+    check_out: chrono::NaiveDate,
+    guests: i32,
+    rooms: i32,
+    total_price: Money,
+    status: String,
+    payment_status: String,
+    special_requests: Option<String>,
+    addon_services: Option<serde_json::Value>,
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub(crate) async fn load_booking_by_reference(pool: &PgPool, reference: &str) -> Result<Option<BookingByReference>, sqlx::Error> {
+    let booking = sqlx::query!(
+        r#"
+        SELECT b.id, b.booking_reference, b.user_id, b.hotel_id, b.check_in, b.check_out, b.guests, b.rooms,
+               b.total_price, b.currency, b.status, b.payment_status, b.special_requests,
+               b.addon_services, b.created_at,
+               h.name as hotel_name, h.location as hotel_location, h.address as hotel_address, h.owner_id,
+               COALESCE(u.first_name || ' ' || u.last_name, g.first_name || ' ' || g.last_name) as "guest_name!",
+               COALESCE(u.email, g.email) as "guest_email!"
+        FROM bookings b
+        JOIN hotels h ON b.hotel_id = h.id
+        LEFT JOIN users u ON b.user_id = u.id
+        LEFT JOIN guests g ON b.guest_id = g.id
+        WHERE b.booking_reference = $1
+        "#,
+        reference.trim().to_uppercase()
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(booking.map(|b| BookingByReference {
+        id: b.id,
+        reference: b.booking_reference,
+        user_id: b.user_id,
+        owner_id: b.owner_id,
+        hotel_id: b.hotel_id,
+        hotel_name: b.hotel_name,
+        hotel_location: b.hotel_location,
+        hotel_address: b.hotel_address,
+        guest_name: b.guest_name,
+        guest_email: b.guest_email,
+        check_in: b.check_in,
+        check_out: b.check_out,
+        guests: b.guests,
+        rooms: b.rooms,
+        total_price: Money::from_db(b.total_price, &b.currency),
+        status: b.status,
+        payment_status: b.payment_status,
+        special_requests: b.special_requests,
+        addon_services: b.addon_services,
+        created_at: b.created_at,
+    }))
+}
+
+fn lookup_error(e: sqlx::Error) -> HttpResponse {
+    println!("💥 [BOOKING] Error consultando reserva por referencia: {}", e);
+    HttpResponse::InternalServerError().json(json!({ 
+        "error": "Error al consultar la reserva",
+        "details": e.to_string()
+    }))
+}
+
+async fn add_to_waitlist(
+    pool: &PgPool,
+    booking_req: &CreateBookingRequest,
+    user: &UserInfo,
+    night: chrono::NaiveDate,
+) -> Result<HttpResponse> {
+    let entry = sqlx::query!(
+        r#"
+        INSERT INTO booking_waitlist (user_id, hotel_id, check_in, check_out, guests, rooms)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+        user.id,
+        booking_req.hotel_id,
+        booking_req.check_in,
+        booking_req.check_out,
+        booking_req.guests,
+        booking_req.rooms
+    )
+    .fetch_one(pool)
+    .await;
+
+    match entry {
+        Ok(entry) => {
+            println!("📝 [BOOKING] Usuario {} agregado a lista de espera ({})", user.id, entry.id);
+            Ok(HttpResponse::Accepted().json(json!({
+                "message": format!("No hay habitaciones disponibles para la noche del {}. Te agregamos a la lista de espera.", night),
+                "waitlist_id": entry.id,
+                "status": "waiting"
+            })))
+        }
+        Err(e) => {
+            println!("💥 [BOOKING] Error agregando a lista de espera: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({ 
+                "error": "Error al agregar a la lista de espera",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
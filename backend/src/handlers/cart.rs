@@ -0,0 +1,354 @@
+// backend/src/handlers/cart.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::models::*;
+use crate::money::{Currency, Money};
+use crate::services::products::{self, ProductError, MAX_PRODUCT_QUANTITY};
+
+pub async fn get_cart(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    match load_cart(pool.get_ref(), user.id).await {
+        Ok(cart) => Ok(HttpResponse::Ok().json(cart)),
+        Err(e) => {
+            println!("💥 [CART] Error obteniendo carrito de usuario {}: {}", user.id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener el carrito",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Agrega piezas al carrito; si el producto ya estaba se suma la cantidad
+pub async fn add_cart_item(
+    pool: web::Data<PgPool>,
+    item_req: web::Json<AddCartItemRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("🛒 [CART] Usuario {} agrega producto {}", user.id, item_req.product_id);
+
+    if let Err(errors) = item_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [CART] Error agregando al carrito: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al agregar al carrito",
+            "details": e.to_string()
+        }))
+    };
+
+    let stock = match sqlx::query_scalar!(
+        "SELECT stock FROM products WHERE id = $1 AND is_active",
+        item_req.product_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(stock)) => stock,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Producto no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let quantity = match sqlx::query_scalar!(
+        r#"
+        INSERT INTO cart_items (user_id, product_id, quantity)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, product_id) DO UPDATE
+            SET quantity = cart_items.quantity + EXCLUDED.quantity, updated_at = CURRENT_TIMESTAMP
+            WHERE cart_items.quantity + EXCLUDED.quantity <= $4
+        RETURNING quantity
+        "#,
+        user.id,
+        item_req.product_id,
+        item_req.quantity.unwrap_or(1),
+        MAX_PRODUCT_QUANTITY
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(quantity)) => quantity,
+        Ok(None) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": format!("No puedes llevar más de {} piezas del mismo producto", MAX_PRODUCT_QUANTITY)
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // El inventario se aparta al pagar; aquí solo se avisa si ya no alcanza
+    let warning = (quantity > stock).then(|| format!("Solo quedan {} piezas disponibles", stock));
+
+    match load_cart(pool.get_ref(), user.id).await {
+        Ok(cart) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Producto agregado al carrito",
+            "warning": warning,
+            "cart": cart
+        }))),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+pub async fn update_cart_item(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    item_req: web::Json<UpdateCartItemRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let product_id = path.into_inner();
+
+    if let Err(errors) = item_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE cart_items SET quantity = $3, updated_at = CURRENT_TIMESTAMP
+        WHERE user_id = $1 AND product_id = $2
+        "#,
+        user.id,
+        product_id,
+        item_req.quantity
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => cart_response(pool.get_ref(), user.id, "Cantidad actualizada").await,
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "El producto no está en tu carrito"
+        }))),
+        Err(e) => {
+            println!("💥 [CART] Error actualizando producto {} del carrito: {}", product_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el carrito",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn remove_cart_item(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let product_id = path.into_inner();
+
+    let result = sqlx::query!(
+        "DELETE FROM cart_items WHERE user_id = $1 AND product_id = $2",
+        user.id,
+        product_id
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => cart_response(pool.get_ref(), user.id, "Producto quitado del carrito").await,
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "El producto no está en tu carrito"
+        }))),
+        Err(e) => {
+            println!("💥 [CART] Error quitando producto {} del carrito: {}", product_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el carrito",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn clear_cart(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let result = sqlx::query!("DELETE FROM cart_items WHERE user_id = $1", user.id)
+        .execute(pool.get_ref())
+        .await;
+
+    match result {
+        Ok(_) => cart_response(pool.get_ref(), user.id, "Carrito vaciado").await,
+        Err(e) => {
+            println!("💥 [CART] Error vaciando carrito de usuario {}: {}", user.id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al vaciar el carrito",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Convierte el carrito en una compra: precios del catálogo, inventario apartado
+// y carrito vaciado dentro de la misma transacción
+pub async fn checkout_cart(
+    pool: web::Data<PgPool>,
+    checkout_req: web::Json<CartCheckoutRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("💳 [CART] Usuario {} pagando su carrito", user.id);
+
+    if let Err(errors) = checkout_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [CART] Error en checkout: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al procesar la compra",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // FOR UPDATE evita que dos checkouts simultáneos cobren el mismo carrito
+    let items = match sqlx::query!(
+        "SELECT product_id, quantity FROM cart_items WHERE user_id = $1 ORDER BY product_id FOR UPDATE",
+        user.id
+    )
+    .fetch_all(&mut *tx)
+    .await
+    {
+        Ok(items) => items,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if items.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Tu carrito está vacío"
+        })));
+    }
+
+    let order_req = CreateProductOrderRequest {
+        items: items
+            .into_iter()
+            .map(|i| ProductSelection { product_id: i.product_id, quantity: i.quantity })
+            .collect(),
+        shipping_address: Some(checkout_req.shipping_address.trim().to_string()),
+    };
+
+    let order = match products::purchase_products(&mut tx, user.id, &order_req).await {
+        Ok(order) => order,
+        Err(ProductError::Database(e)) => return Ok(internal_error(e)),
+        Err(e @ ProductError::InsufficientStock { .. }) => {
+            println!("❌ [CART] Sin inventario: {}", e);
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            })));
+        }
+        Err(e) => {
+            println!("❌ [CART] Carrito inválido: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = sqlx::query!("DELETE FROM cart_items WHERE user_id = $1", user.id)
+        .execute(&mut *tx)
+        .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [CART] Carrito de usuario {} pagado: compra {} ({})", user.id, order.id, order.reference);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Compra realizada exitosamente",
+        "order": order
+    })))
+}
+
+async fn cart_response(pool: &PgPool, user_id: i32, message: &str) -> Result<HttpResponse> {
+    match load_cart(pool, user_id).await {
+        Ok(cart) => Ok(HttpResponse::Ok().json(json!({
+            "message": message,
+            "cart": cart
+        }))),
+        Err(e) => {
+            println!("💥 [CART] Error obteniendo carrito de usuario {}: {}", user_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener el carrito",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Carrito con precios vigentes del catálogo. Las piezas retiradas o agotadas
+// se muestran marcadas y no suman al total.
+async fn load_cart(pool: &PgPool, user_id: i32) -> Result<serde_json::Value, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT c.product_id, c.quantity, p.name, p.price, p.currency, p.stock, p.is_active, p.images,
+               a.name as artisan_name
+        FROM cart_items c
+        JOIN products p ON c.product_id = p.id
+        JOIN artisans a ON p.artisan_id = a.id
+        WHERE c.user_id = $1
+        ORDER BY c.created_at, c.id
+        "#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let currency = rows
+        .first()
+        .and_then(|r| Currency::from_code(&r.currency))
+        .unwrap_or(Currency::MXN);
+    let mut total = Money::zero(currency);
+    let mut items = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let unit_price = Money::from_db(row.price, &row.currency);
+        let subtotal = unit_price.times(row.quantity as i64);
+        let available = row.is_active && row.stock >= row.quantity;
+        if available && subtotal.currency() == currency {
+            total = total + subtotal.clone();
+        }
+
+        items.push(json!({
+            "product_id": row.product_id,
+            "name": row.name,
+            "artisan": row.artisan_name,
+            "image": row.images.first(),
+            "unit_price": unit_price,
+            "quantity": row.quantity,
+            "subtotal": subtotal,
+            "stock": row.stock,
+            "available": available
+        }));
+    }
+
+    Ok(json!({
+        "items": items,
+        "total_items": items.len(),
+        "total": total
+    }))
+}
@@ -0,0 +1,439 @@
+// backend/src/handlers/driver.rs
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use actix_ws::{Message, Session};
+use futures_util::StreamExt;
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::auth;
+use crate::middleware::roles::{Driver, RequireRole};
+use crate::models::*;
+use crate::services::dispatch::{self, DispatchHub};
+use crate::services::rides::RideError;
+
+// Registro de conductor: queda pendiente hasta que un administrador lo certifique
+pub async fn register_driver(
+    pool: web::Data<PgPool>,
+    driver_req: web::Json<DriverProfileRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("🪪 [DRIVERS] Registrando conductor para usuario {}", user.id);
+
+    if let Err(errors) = driver_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [DRIVERS] Error registrando conductor: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al registrar el conductor",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Un cliente que se registra para manejar se convierte en conductor
+    if user.role() == Role::Customer {
+        if let Err(e) = sqlx::query!(
+            "UPDATE users SET user_type = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+            Role::Driver.as_str(),
+            user.id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+        println!("⭐ [DRIVERS] Usuario {} ahora es conductor", user.id);
+    }
+
+    let driver = sqlx::query!(
+        r#"
+        INSERT INTO drivers (user_id, name, phone, vehicle, plate, license_number, license_expires_on, is_available)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, TRUE)
+        ON CONFLICT (user_id) DO NOTHING
+        RETURNING id, certification_status, created_at
+        "#,
+        user.id,
+        format!("{} {}", user.first_name, user.last_name),
+        driver_req.phone.trim(),
+        driver_req.vehicle.trim(),
+        driver_req.plate.trim().to_uppercase(),
+        driver_req.license_number.trim().to_uppercase(),
+        driver_req.license_expires_on
+    )
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let driver = match driver {
+        Ok(Some(driver)) => driver,
+        Ok(None) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": "Ya tienes un registro de conductor"
+            })));
+        }
+        Err(e) if e.to_string().contains("unique") => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": "La placa o la licencia ya están registradas con otro conductor"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [DRIVERS] Conductor {} registrado, pendiente de certificación", driver.id);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Registro recibido. Podrás recibir viajes cuando un administrador apruebe tu certificación.",
+        "driver": {
+            "id": driver.id,
+            "vehicle": driver_req.vehicle.trim(),
+            "plate": driver_req.plate.trim().to_uppercase(),
+            "license_expires_on": driver_req.license_expires_on,
+            "certification_status": driver.certification_status,
+            "created_at": driver.created_at
+        }
+    })))
+}
+
+pub async fn get_driver_profile(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    driver: RequireRole<Driver>,
+) -> Result<HttpResponse> {
+    let profile = sqlx::query!(
+        r#"
+        SELECT id, name, phone, vehicle, plate, license_number, license_expires_on,
+               certification_status, is_online, is_available, latitude, longitude, created_at
+        FROM drivers WHERE user_id = $1
+        "#,
+        driver.user.id
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match profile {
+        Ok(Some(p)) => Ok(HttpResponse::Ok().json(json!({
+            "driver": {
+                "id": p.id,
+                "name": p.name,
+                "phone": p.phone,
+                "vehicle": p.vehicle,
+                "plate": p.plate,
+                "license_number": p.license_number,
+                "license_expires_on": p.license_expires_on,
+                "certification_status": p.certification_status,
+                "is_online": p.is_online,
+                "is_available": p.is_available,
+                "connected": hub.is_connected(p.id),
+                "location": match (p.latitude, p.longitude) {
+                    (Some(lat), Some(lng)) => Some(json!({ "lat": lat, "lng": lng })),
+                    _ => None,
+                },
+                "created_at": p.created_at
+            }
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "No tienes un registro de conductor"
+        }))),
+        Err(e) => {
+            println!("💥 [DRIVERS] Error obteniendo perfil del usuario {}: {}", driver.user.id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener el perfil de conductor",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Ponerse en línea (con la ubicación actual) o fuera de línea
+pub async fn update_driver_status(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    status_req: web::Json<DriverStatusRequest>,
+    driver: RequireRole<Driver>,
+) -> Result<HttpResponse> {
+    println!("🚦 [DRIVERS] Usuario {} cambia a en línea = {}", driver.user.id, status_req.online);
+
+    if let Err(errors) = status_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [DRIVERS] Error cambiando estado del usuario {}: {}", driver.user.id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cambiar tu estado",
+            "details": e.to_string()
+        }))
+    };
+
+    let current = match sqlx::query!(
+        "SELECT id, certification_status, latitude, longitude FROM drivers WHERE user_id = $1",
+        driver.user.id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(current)) => current,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "No tienes un registro de conductor"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if status_req.online {
+        if CertificationStatus::from_db(&current.certification_status) != Some(CertificationStatus::Approved) {
+            return Ok(HttpResponse::Forbidden().json(json!({
+                "error": format!(
+                    "Tu certificación está '{}'; podrás ponerte en línea cuando sea aprobada",
+                    current.certification_status
+                )
+            })));
+        }
+
+        let has_location = status_req.lat.is_some() && status_req.lng.is_some()
+            || current.latitude.is_some() && current.longitude.is_some();
+        if !has_location {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Comparte tu ubicación para recibir viajes cercanos"
+            })));
+        }
+    }
+
+    let updated = match sqlx::query!(
+        r#"
+        UPDATE drivers SET is_online = $2,
+            latitude = COALESCE($3, latitude),
+            longitude = COALESCE($4, longitude),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING is_online, is_available
+        "#,
+        current.id,
+        status_req.online,
+        status_req.lat,
+        status_req.lng
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(updated) => updated,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Si ya tiene abierto el WebSocket, recibe de inmediato las solicitudes pendientes
+    if updated.is_online && hub.is_connected(current.id) {
+        if let Err(e) = dispatch::send_pending_offers(pool.get_ref(), &hub, current.id).await {
+            println!("💥 [DRIVERS] Error enviando ofertas pendientes al conductor {}: {}", current.id, e);
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": if updated.is_online { "Estás en línea" } else { "Estás fuera de línea" },
+        "driver": {
+            "id": current.id,
+            "is_online": updated.is_online,
+            "is_available": updated.is_available,
+            "connected": hub.is_connected(current.id)
+        }
+    })))
+}
+
+// Alternativa REST al mensaje 'accept' del WebSocket
+pub async fn accept_ride(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    path: web::Path<i32>,
+    driver: RequireRole<Driver>,
+) -> Result<HttpResponse> {
+    let ride_id = path.into_inner();
+
+    let driver_id = match sqlx::query_scalar!("SELECT id FROM drivers WHERE user_id = $1", driver.user.id)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(driver_id)) => driver_id,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "No tienes un registro de conductor"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [DRIVERS] Error consultando conductor del usuario {}: {}", driver.user.id, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al aceptar el viaje",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    match dispatch::accept_offer(pool.get_ref(), &hub, driver_id, driver.user.id, ride_id).await {
+        Ok(ride) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Viaje aceptado. Dirígete al punto de partida.",
+            "ride": ride
+        }))),
+        Err(RideError::Database(e)) => {
+            println!("💥 [DRIVERS] Error aceptando viaje {}: {}", ride_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al aceptar el viaje",
+                "details": e.to_string()
+            })))
+        }
+        Err(e) => Ok(HttpResponse::Conflict().json(json!({
+            "error": e.to_string()
+        }))),
+    }
+}
+
+// WebSocket de despacho: GET /api/driver/ws?token=<access token>
+// Servidor → conductor: ride_offer, offer_taken, offer_withdrawn, ride_accepted, accept_rejected, error
+// Conductor → servidor: {"type": "location", "lat", "lng"} y {"type": "accept", "ride_id"}
+pub async fn driver_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<SocketAuthQuery>,
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+) -> Result<HttpResponse> {
+    let user = auth::authenticate_token(pool.get_ref(), &query.token).await?;
+
+    let driver = match sqlx::query!(
+        "SELECT id, certification_status FROM drivers WHERE user_id = $1",
+        user.id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(driver)) => driver,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "No tienes un registro de conductor"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [DISPATCH] Error consultando conductor del usuario {}: {}", user.id, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al conectar con el despacho",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    if CertificationStatus::from_db(&driver.certification_status) != Some(CertificationStatus::Approved) {
+        return Ok(HttpResponse::Forbidden().json(json!({
+            "error": "Tu certificación aún no está aprobada"
+        })));
+    }
+
+    let (response, session, mut stream) = actix_ws::handle(&req, body)?;
+    let driver_id = driver.id;
+    let driver_user_id = user.id;
+    let connection_id = hub.connect(driver_id, session.clone());
+    println!("🔌 [DISPATCH] Conductor {} conectado", driver_id);
+
+    let pool = pool.into_inner();
+    let hub = hub.into_inner();
+
+    actix_web::rt::spawn(async move {
+        let mut session = session;
+
+        if let Err(e) = dispatch::send_pending_offers(&pool, &hub, driver_id).await {
+            println!("💥 [DISPATCH] Error enviando ofertas pendientes al conductor {}: {}", driver_id, e);
+        }
+
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(Message::Text(text)) => {
+                    handle_socket_message(&pool, &hub, &mut session, driver_id, driver_user_id, &text).await;
+                }
+                Ok(Message::Ping(bytes)) => {
+                    if session.pong(&bytes).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(Message::Close(_)) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+
+        // Sin conexión el conductor ya no puede recibir ofertas
+        if hub.disconnect(driver_id, connection_id) {
+            if let Err(e) = sqlx::query!(
+                "UPDATE drivers SET is_online = FALSE, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+                driver_id
+            )
+            .execute(pool.as_ref())
+            .await
+            {
+                println!("💥 [DISPATCH] Error marcando fuera de línea al conductor {}: {}", driver_id, e);
+            }
+        }
+
+        let _ = session.close(None).await;
+        println!("🔌 [DISPATCH] Conductor {} desconectado", driver_id);
+    });
+
+    Ok(response)
+}
+
+async fn handle_socket_message(
+    pool: &PgPool,
+    hub: &DispatchHub,
+    session: &mut Session,
+    driver_id: i32,
+    driver_user_id: i32,
+    text: &str,
+) {
+    let reply = match serde_json::from_str::<DriverSocketMessage>(text) {
+        Ok(DriverSocketMessage::Location { lat, lng }) => {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+                json!({ "type": "error", "message": "Ubicación inválida" })
+            } else {
+                match sqlx::query!(
+                    "UPDATE drivers SET latitude = $2, longitude = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+                    driver_id,
+                    lat,
+                    lng
+                )
+                .execute(pool)
+                .await
+                {
+                    // La ubicación no necesita respuesta
+                    Ok(_) => return,
+                    Err(e) => {
+                        println!("💥 [DISPATCH] Error guardando ubicación del conductor {}: {}", driver_id, e);
+                        json!({ "type": "error", "message": "No se pudo guardar tu ubicación" })
+                    }
+                }
+            }
+        }
+        Ok(DriverSocketMessage::Accept { ride_id }) => {
+            match dispatch::accept_offer(pool, hub, driver_id, driver_user_id, ride_id).await {
+                Ok(ride) => json!({ "type": "ride_accepted", "ride": ride }),
+                Err(RideError::Database(e)) => {
+                    println!("💥 [DISPATCH] Error aceptando viaje {}: {}", ride_id, e);
+                    json!({ "type": "error", "message": "Error del servidor al aceptar el viaje" })
+                }
+                Err(e) => json!({ "type": "accept_rejected", "ride_id": ride_id, "message": e.to_string() }),
+            }
+        }
+        Err(_) => json!({ "type": "error", "message": "Mensaje no reconocido" }),
+    };
+
+    let _ = session.text(reply.to_string()).await;
+}
@@ -0,0 +1,363 @@
+// backend/src/handlers/experience.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::models::*;
+use crate::money::Money;
+use crate::services::experiences::{self, ExperienceError};
+
+pub async fn get_experiences(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    // `price` es la tarifa más baja para mostrar "desde" en el listado
+    let experiences = sqlx::query!(
+        r#"
+        SELECT e.id, e.experience_type, e.name, e.description, e.location, e.image_url,
+               e.duration_minutes, e.currency, MIN(f.price) as price
+        FROM experiences e
+        LEFT JOIN experience_fares f ON f.experience_id = e.id AND f.is_active
+        WHERE e.status = 'approved'
+        GROUP BY e.id
+        ORDER BY e.id
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match experiences {
+        Ok(list) => {
+            let experiences_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|e| json!({
+                    "id": e.id,
+                    "type": e.experience_type,
+                    "name": e.name,
+                    "description": e.description,
+                    "location": e.location,
+                    "image": e.image_url,
+                    "duration_minutes": e.duration_minutes,
+                    "price": e.price.map(|price| Money::from_db(price, &e.currency))
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(experiences_json))
+        }
+        Err(e) => {
+            println!("💥 [EXPERIENCES] Error: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener las experiencias",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Detalle con tarifas y salidas próximas (por defecto, los próximos 30 días)
+pub async fn get_experience(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    query: web::Query<ExperienceDeparturesQuery>,
+) -> Result<HttpResponse> {
+    let experience_id = path.into_inner();
+    let today = chrono::Utc::now().naive_utc().date();
+    let from = query.from.unwrap_or(today).max(today + chrono::Duration::days(1));
+    let to = query.to.unwrap_or(from + chrono::Duration::days(30));
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [EXPERIENCES] Error consultando experiencia {}: {}", experience_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "No se pudo obtener la experiencia",
+            "details": e.to_string()
+        }))
+    };
+
+    let experience = match sqlx::query!(
+        r#"
+        SELECT id, experience_type, name, description, location, image_url, duration_minutes,
+               min_age, requirements, currency
+        FROM experiences
+        WHERE id = $1 AND status = 'approved'
+        "#,
+        experience_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(experience)) => experience,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Experiencia no encontrada"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let fares = match sqlx::query!(
+        r#"
+        SELECT fare_type, label, price, requires_adult
+        FROM experience_fares
+        WHERE experience_id = $1 AND is_active
+        ORDER BY requires_adult, price DESC
+        "#,
+        experience_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|f| json!({
+                "fare_type": f.fare_type,
+                "label": f.label,
+                "price": Money::from_db(f.price, &experience.currency),
+                "requires_adult": f.requires_adult
+            }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let departures = match sqlx::query!(
+        r#"
+        SELECT id, departure_date, departure_time, capacity - seats_booked as "seats_left!"
+        FROM experience_departures
+        WHERE experience_id = $1 AND status = 'scheduled'
+          AND departure_date BETWEEN $2 AND $3
+        ORDER BY departure_date, departure_time
+        "#,
+        experience_id,
+        from,
+        to
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|d| json!({
+                "id": d.id,
+                "date": d.departure_date,
+                "time": d.departure_time,
+                "seats_left": d.seats_left
+            }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "experience": {
+            "id": experience.id,
+            "type": experience.experience_type,
+            "name": experience.name,
+            "description": experience.description,
+            "location": experience.location,
+            "image": experience.image_url,
+            "duration_minutes": experience.duration_minutes,
+            "min_age": experience.min_age,
+            "requirements": experience.requirements
+        },
+        "fares": fares,
+        "departures": departures
+    })))
+}
+
+pub async fn create_experience_booking(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    booking_req: web::Json<CreateExperienceBookingRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let experience_id = path.into_inner();
+    println!("🎟️ [EXPERIENCES] Usuario {} reservando experiencia {}: {:?}", user.id, experience_id, booking_req);
+
+    if let Err(errors) = booking_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [EXPERIENCES] Error creando reserva: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al crear la reserva",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let booking = match experiences::book_departure(&mut tx, user.id, experience_id, &booking_req).await {
+        Ok(booking) => booking,
+        Err(ExperienceError::Database(e)) => return Ok(internal_error(e)),
+        Err(e @ (ExperienceError::ExperienceUnavailable | ExperienceError::DepartureNotFound)) => {
+            return Ok(HttpResponse::NotFound().json(json!({ "error": e.to_string() })));
+        }
+        Err(e @ ExperienceError::SoldOut { .. }) => {
+            return Ok(HttpResponse::Conflict().json(json!({ "error": e.to_string() })));
+        }
+        Err(e) => {
+            println!("❌ [EXPERIENCES] Reserva inválida: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({ "error": e.to_string() })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [EXPERIENCES] Reserva {} creada ({})", booking.id, booking.reference);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Reserva creada exitosamente",
+        "booking": booking
+    })))
+}
+
+pub async fn get_my_experience_bookings(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("📋 [EXPERIENCES] Obteniendo reservas de experiencias para usuario {}", user.id);
+
+    let bookings = sqlx::query!(
+        r#"
+        SELECT b.id, b.booking_reference, b.participants, b.fares, b.total_price, b.currency,
+               b.special_requests, b.status, b.cancelled_at, b.created_at,
+               d.departure_date, d.departure_time,
+               e.id as experience_id, e.name as experience_name, e.experience_type,
+               e.location as experience_location
+        FROM experience_bookings b
+        JOIN experience_departures d ON b.departure_id = d.id
+        JOIN experiences e ON b.experience_id = e.id
+        WHERE b.user_id = $1
+        ORDER BY d.departure_date DESC, d.departure_time DESC
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match bookings {
+        Ok(rows) => {
+            let booking_list: Vec<serde_json::Value> = rows
+                .into_iter()
+                .map(|b| json!({
+                    "id": b.id,
+                    "reference": b.booking_reference,
+                    "experience_id": b.experience_id,
+                    "experience_name": b.experience_name,
+                    "experience_type": b.experience_type,
+                    "experience_location": b.experience_location,
+                    "departure_date": b.departure_date,
+                    "departure_time": b.departure_time,
+                    "participants": b.participants,
+                    "fares": b.fares,
+                    "total_price": Money::from_db(b.total_price, &b.currency),
+                    "special_requests": b.special_requests,
+                    "status": b.status,
+                    "cancelled_at": b.cancelled_at,
+                    "created_at": b.created_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "bookings": booking_list,
+                "total": booking_list.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [EXPERIENCES] Error obteniendo reservas: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener reservas",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn cancel_experience_booking(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    cancel_req: web::Json<UpdateBookingStatusRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let booking_id = path.into_inner();
+    println!("🚫 [EXPERIENCES] Cancelando reserva {} para usuario {}", booking_id, user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [EXPERIENCES] Error cancelando reserva {}: {}", booking_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cancelar la reserva",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Las partes de un viaje sin pagar solo se cancelan con el viaje completo
+    match sqlx::query!(
+        r#"
+        SELECT t.trip_reference FROM experience_bookings eb
+        JOIN trips t ON eb.trip_id = t.id
+        WHERE eb.id = $1 AND eb.user_id = $2 AND t.payment_status <> 'paid'
+        "#,
+        booking_id,
+        user.id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(trip)) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": format!(
+                    "Esta reserva es parte del viaje {}, pendiente de pago; cancela el viaje completo",
+                    trip.trip_reference
+                )
+            })));
+        }
+        Ok(None) => {}
+        Err(e) => return Ok(internal_error(e)),
+    }
+
+    let cancelled = match sqlx::query!(
+        r#"
+        UPDATE experience_bookings SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $3, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND user_id = $2 AND status = 'confirmed'
+        RETURNING departure_id, participants
+        "#,
+        booking_id,
+        user.id,
+        cancel_req.cancellation_reason
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(cancelled)) => cancelled,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Reserva no encontrada o ya cancelada"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Devolver los lugares a la salida
+    if let Err(e) = experiences::release_seats(&mut tx, cancelled.departure_id, cancelled.participants).await {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [EXPERIENCES] Reserva {} cancelada", booking_id);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Reserva cancelada exitosamente"
+    })))
+}
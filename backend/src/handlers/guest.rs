@@ -0,0 +1,250 @@
+// backend/src/handlers/guest.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::handlers::booking::{cancel_stay, load_booking_by_reference, refund_error_response};
+use crate::handlers::payment::start_booking_payment;
+use crate::models::*;
+use crate::services::bookings::{self, BookingError, BookingHolder};
+use crate::services::guests::{self, GuestAccess, GuestError};
+use crate::services::payments::PaymentProviders;
+use crate::services::refunds;
+
+// Reserva sin cuenta: crea (o reutiliza) el huésped, reserva y le envía por
+// email el enlace para pagar y administrar la reserva
+pub async fn create_guest_booking(
+    pool: web::Data<PgPool>,
+    booking_req: web::Json<GuestBookingRequest>,
+) -> Result<HttpResponse> {
+    println!("🧳 [GUEST] Reserva sin cuenta para {}", booking_req.email);
+
+    if let Err(errors) = booking_req.validate() {
+        println!("❌ [GUEST] Error de validación: {:?}", errors);
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let guest_id = match guests::find_or_create_guest(&mut tx, &booking_req).await {
+        Ok(guest_id) => guest_id,
+        Err(GuestError::Database(e)) => return Ok(internal_error(e)),
+        Err(e) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    let booking = match bookings::book_stay(&mut tx, BookingHolder::Guest(guest_id), &booking_req.booking).await {
+        Ok(booking) => booking,
+        Err(BookingError::Database(e)) => return Ok(internal_error(e)),
+        Err(BookingError::HotelUnavailable) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": BookingError::HotelUnavailable.to_string()
+            })));
+        }
+        Err(BookingError::SoldOut { night, rooms_left }) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": format!("No hay habitaciones disponibles para la noche del {}", night),
+                "night": night,
+                "rooms_left": rooms_left
+            })));
+        }
+        Err(e) => {
+            println!("❌ [GUEST] Reserva inválida: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    // El correo se encola en la misma transacción: sin reserva no hay enlace
+    if let Err(e) = guests::send_manage_link(&mut tx, booking.id).await {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [GUEST] Reserva {} creada para el huésped {}", booking.reference, guest_id);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Reserva creada. Te enviamos un enlace por email para pagarla y administrarla.",
+        "booking": booking
+    })))
+}
+
+// Reserva a la que da acceso el enlace mágico
+pub async fn get_guest_booking(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let access = match guest_access(pool.get_ref(), &path).await {
+        Ok(access) => access,
+        Err(response) => return Ok(response),
+    };
+
+    match load_booking_by_reference(pool.get_ref(), &access.reference).await {
+        Ok(Some(booking)) => Ok(HttpResponse::Ok().json(json!({ "booking": booking }))),
+        Ok(None) => Ok(invalid_link()),
+        Err(e) => Ok(internal_error(e)),
+    }
+}
+
+pub async fn create_guest_payment_intent(
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let access = match guest_access(pool.get_ref(), &path).await {
+        Ok(access) => access,
+        Err(response) => return Ok(response),
+    };
+    println!("💳 [GUEST] Huésped {} inicia pago de la reserva {}", access.guest_id, access.booking_id);
+
+    Ok(start_booking_payment(
+        pool.get_ref(),
+        providers.get_ref(),
+        access.booking_id,
+        BookingHolder::Guest(access.guest_id),
+    ).await)
+}
+
+pub async fn preview_guest_refund(
+    pool: web::Data<PgPool>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let access = match guest_access(pool.get_ref(), &path).await {
+        Ok(access) => access,
+        Err(response) => return Ok(response),
+    };
+
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => return Ok(refund_error_response(e.into())),
+    };
+
+    match refunds::quote_cancellation(&mut conn, access.booking_id, BookingHolder::Guest(access.guest_id)).await {
+        Ok(quote) => Ok(HttpResponse::Ok().json(json!({ "cancellation": quote }))),
+        Err(e) => Ok(refund_error_response(e)),
+    }
+}
+
+pub async fn cancel_guest_booking(
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<String>,
+    cancel_req: web::Json<UpdateBookingStatusRequest>,
+) -> Result<HttpResponse> {
+    let access = match guest_access(pool.get_ref(), &path).await {
+        Ok(access) => access,
+        Err(response) => return Ok(response),
+    };
+    println!("🚫 [GUEST] Huésped {} cancela la reserva {}", access.guest_id, access.booking_id);
+
+    Ok(cancel_stay(
+        pool.get_ref(),
+        providers.get_ref(),
+        access.booking_id,
+        BookingHolder::Guest(access.guest_id),
+        cancel_req.cancellation_reason.as_deref(),
+    ).await)
+}
+
+// Envía un enlace nuevo a quien perdió el correo. Responde lo mismo haya o no
+// coincidencia para no revelar qué reservas existen.
+pub async fn resend_guest_link(
+    pool: web::Data<PgPool>,
+    lookup_req: web::Json<BookingLookupRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = lookup_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    match guests::resend_manage_link(&mut tx, &lookup_req.reference, &lookup_req.email).await {
+        Ok(sent) => {
+            if let Err(e) = tx.commit().await {
+                return Ok(internal_error(e));
+            }
+            println!("📨 [GUEST] Reenvío de enlace para {} (coincidencia: {})", lookup_req.reference.trim(), sent);
+        }
+        Err(e) => return Ok(internal_error(e)),
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Si la reserva existe, te enviamos un enlace nuevo a tu email"
+    })))
+}
+
+// Para quien ya tiene cuenta: agrega las reservas hechas como huésped usando
+// un enlace recibido en el mismo email de la cuenta
+pub async fn claim_guest_bookings(
+    pool: web::Data<PgPool>,
+    claim_req: web::Json<ClaimGuestBookingsRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let merged = match guests::merge_into_account(&mut tx, &claim_req.token, user.id, &user.email).await {
+        Ok(merged) => merged,
+        Err(GuestError::Database(e)) => return Ok(internal_error(e)),
+        Err(GuestError::InvalidLink) => return Ok(invalid_link()),
+        Err(e) => {
+            return Ok(HttpResponse::Forbidden().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Tus reservas como huésped ya están en tu cuenta",
+        "bookings_merged": merged
+    })))
+}
+
+async fn guest_access(pool: &PgPool, token: &str) -> Result<GuestAccess, HttpResponse> {
+    let mut conn = pool.acquire().await.map_err(internal_error)?;
+
+    match guests::find_access(&mut conn, token).await {
+        Ok(Some(access)) => Ok(access),
+        Ok(None) => Err(invalid_link()),
+        Err(e) => Err(internal_error(e)),
+    }
+}
+
+fn invalid_link() -> HttpResponse {
+    HttpResponse::NotFound().json(json!({
+        "error": GuestError::InvalidLink.to_string()
+    }))
+}
+
+fn internal_error(e: sqlx::Error) -> HttpResponse {
+    println!("💥 [GUEST] Error del servidor: {}", e);
+    HttpResponse::InternalServerError().json(json!({
+        "error": "Error del servidor al procesar la reserva",
+        "details": e.to_string()
+    }))
+}
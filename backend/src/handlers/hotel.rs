@@ -1,31 +1,16 @@
 use actix_web::{web, HttpResponse, Result};
 use sqlx::PgPool;
 use serde_json::json;
-use crate::models::UserInfo;
+use validator::Validate;
 
-pub async fn verify_hotel_ownership(pool: &PgPool, hotel_id: i32, user: &UserInfo) -> Result<bool, sqlx::Error> {
-    // Si es admin, puede acceder a todo
-    if user.user_type == "admin" {
-        return Ok(true);
-    }
-    
-    // Verificar si el usuario es dueño del hotel
-    let result = sqlx::query!(
-        "SELECT owner_id FROM hotels WHERE id = $1",
-        hotel_id
-    )
-    .fetch_optional(pool)
-    .await?;
-    
-    match result {
-        Some(hotel) => Ok(hotel.owner_id == Some(user.id)),
-        None => Ok(false), // Hotel no existe
-    }
-}
+use crate::models::AvailabilityQuery;
+use crate::money::Money;
+use crate::services::pricing::{self, StayRequest};
+use crate::services::refunds::CancellationPolicy;
 
 pub async fn get_hotels(pool: web::Data<PgPool>) -> Result<HttpResponse> {
     let hotels = sqlx::query!(
-        "SELECT id, name, location, address, price FROM hotels WHERE status = 'approved'"
+        "SELECT id, name, location, address, price, currency FROM hotels WHERE status = 'approved'"
     )
     .fetch_all(pool.get_ref())
     .await;
@@ -39,7 +24,7 @@ pub async fn get_hotels(pool: web::Data<PgPool>) -> Result<HttpResponse> {
                     "name": h.name,
                     "location": h.location,
                     "address": h.address,
-                    "price": h.price
+                    "price": Money::from_db(h.price, &h.currency)
                 }))
                 .collect();
             
@@ -52,4 +37,202 @@ pub async fn get_hotels(pool: web::Data<PgPool>) -> Result<HttpResponse> {
             })))
         }
     }
-}
\ No newline at end of file
+}
+
+pub async fn search_availability(
+    pool: web::Data<PgPool>,
+    query: web::Query<AvailabilityQuery>,
+) -> Result<HttpResponse> {
+    println!("🔎 [HOTELS] Buscando disponibilidad: {:?}", query);
+
+    if let Err(errors) = query.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Parámetros inválidos",
+            "details": errors
+        })));
+    }
+
+    if query.check_out <= query.check_in {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "La fecha de check-out debe ser posterior a la de check-in"
+        })));
+    }
+
+    let guests = query.guests.unwrap_or(1);
+    let rooms = query.rooms.unwrap_or(1);
+    let location = query.location.as_deref().map(str::trim).filter(|l| !l.is_empty());
+
+    // La noche más ocupada del rango determina cuántas habitaciones quedan
+    let hotels = sqlx::query!(
+        r#"
+        SELECT h.id, h.name, h.location, h.address, h.price, h.currency, h.max_guests_per_room,
+               (h.total_rooms - COALESCE(MAX(ri.rooms_booked), 0))::int4 as "rooms_left!"
+        FROM hotels h
+        LEFT JOIN room_inventory ri
+               ON ri.hotel_id = h.id AND ri.night >= $1 AND ri.night < $2
+        WHERE h.status = 'approved'
+          AND ($3::text IS NULL OR h.location ILIKE '%' || $3 || '%')
+        GROUP BY h.id
+        HAVING h.total_rooms - COALESCE(MAX(ri.rooms_booked), 0) >= $4
+           AND h.max_guests_per_room * $4 >= $5
+        ORDER BY h.price
+        "#,
+        query.check_in,
+        query.check_out,
+        location,
+        rooms,
+        guests
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    let internal_error = |e: &dyn std::fmt::Display| {
+        println!("💥 [HOTELS] Error buscando disponibilidad: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "No se pudo consultar la disponibilidad",
+            "details": e.to_string()
+        }))
+    };
+
+    let hotel_list = match hotels {
+        Ok(hotel_list) => hotel_list,
+        Err(e) => return Ok(internal_error(&e)),
+    };
+
+    // Se cotiza igual que al reservar para que el total mostrado sea el que se cobra
+    let stays: Vec<(Money, StayRequest)> = hotel_list
+        .iter()
+        .map(|h| (
+            Money::from_db(h.price.clone(), &h.currency),
+            StayRequest {
+                hotel_id: h.id,
+                check_in: query.check_in,
+                check_out: query.check_out,
+                guests,
+                rooms,
+                addons: &[],
+            },
+        ))
+        .collect();
+
+    let quotes = match pricing::quote_rooms_batch(pool.get_ref(), &stays).await {
+        Ok(quotes) => quotes,
+        Err(e) => return Ok(internal_error(&e)),
+    };
+
+    let hotels_json: Vec<serde_json::Value> = hotel_list
+        .into_iter()
+        .zip(stays.iter().zip(quotes))
+        .map(|(h, ((price_per_night, _), quote))| json!({
+            "id": h.id,
+            "name": h.name,
+            "location": h.location,
+            "address": h.address,
+            "price": price_per_night,
+            "rooms_left": h.rooms_left,
+            "max_guests_per_room": h.max_guests_per_room,
+            "quote": {
+                "nights": quote.nights,
+                "rooms": rooms,
+                "guests": guests,
+                "price_per_night": price_per_night,
+                "total_price": quote.total,
+                "price_breakdown": quote
+            }
+        }))
+        .collect();
+
+    println!("✅ [HOTELS] {} hoteles con disponibilidad", hotels_json.len());
+    Ok(HttpResponse::Ok().json(json!({
+        "check_in": query.check_in,
+        "check_out": query.check_out,
+        "hotels": hotels_json,
+        "total": hotels_json.len()
+    })))
+}
+
+pub async fn get_hotel_addons(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    let hotel_id = path.into_inner();
+
+    let addons = sqlx::query!(
+        r#"
+        SELECT a.id, a.name, a.description, a.icon, a.price, a.currency, a.pricing_unit, a.max_quantity
+        FROM addon_services a
+        JOIN hotels h ON a.hotel_id = h.id
+        WHERE a.hotel_id = $1 AND a.is_active AND h.status = 'approved'
+        ORDER BY a.price
+        "#,
+        hotel_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match addons {
+        Ok(list) => {
+            let addons_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|a| json!({
+                    "id": a.id,
+                    "name": a.name,
+                    "description": a.description,
+                    "icon": a.icon,
+                    "price": Money::from_db(a.price, &a.currency),
+                    "pricing_unit": a.pricing_unit,
+                    "max_quantity": a.max_quantity
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "addons": addons_json })))
+        }
+        Err(e) => {
+            println!("💥 [HOTELS] Error obteniendo addons del hotel {}: {}", hotel_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los servicios adicionales"
+            })))
+        }
+    }
+}
+
+// Política de cancelación, para mostrarla antes de reservar
+pub async fn get_cancellation_policy(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    let hotel_id = path.into_inner();
+
+    let hotel = sqlx::query!(
+        r#"
+        SELECT cancellation_policy, free_cancellation_days, cancellation_penalty_percent
+        FROM hotels WHERE id = $1 AND status = 'approved'
+        "#,
+        hotel_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match hotel {
+        Ok(Some(h)) => {
+            let policy = CancellationPolicy::from_db(
+                &h.cancellation_policy,
+                h.free_cancellation_days,
+                h.cancellation_penalty_percent,
+            );
+            Ok(HttpResponse::Ok().json(json!({
+                "summary": policy.summary(),
+                "cancellation_policy": policy
+            })))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Hotel no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [HOTELS] Error obteniendo política del hotel {}: {}", hotel_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener la política de cancelación"
+            })))
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// backend/src/handlers/notification.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::UserInfo;
+
+pub async fn get_my_notifications(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("🔔 [NOTIFY] Obteniendo notificaciones del usuario {}", user.id);
+
+    let notifications = sqlx::query!(
+        r#"
+        SELECT id, kind, title, message, data, read_at, created_at
+        FROM notifications
+        WHERE user_id = $1
+        ORDER BY read_at IS NOT NULL, created_at DESC
+        LIMIT 100
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match notifications {
+        Ok(list) => {
+            let unread = list.iter().filter(|n| n.read_at.is_none()).count();
+            let notifications_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|n| json!({
+                    "id": n.id,
+                    "kind": n.kind,
+                    "title": n.title,
+                    "message": n.message,
+                    "data": n.data,
+                    "read": n.read_at.is_some(),
+                    "created_at": n.created_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "notifications": notifications_json,
+                "unread": unread
+            })))
+        }
+        Err(e) => {
+            println!("💥 [NOTIFY] Error obteniendo notificaciones: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener las notificaciones",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn mark_notification_read(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let notification_id = path.into_inner();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE notifications SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP)
+        WHERE id = $1 AND user_id = $2
+        "#,
+        notification_id,
+        user.id
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => Ok(HttpResponse::Ok().json(json!({
+            "message": "Notificación marcada como leída"
+        }))),
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Notificación no encontrada"
+        }))),
+        Err(e) => {
+            println!("💥 [NOTIFY] Error marcando notificación {}: {}", notification_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar la notificación",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
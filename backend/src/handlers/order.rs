@@ -0,0 +1,472 @@
+// backend/src/handlers/order.rs
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use validator::Validate;
+
+use crate::middleware::roles::{RequireOwnerOf, Restaurant};
+use crate::models::*;
+use crate::money::Money;
+use crate::services::notifications;
+use crate::services::orders::{self, OrderError};
+
+pub async fn create_order(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    order_req: web::Json<CreateOrderRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+    println!("🧾 [ORDERS] Usuario {} pidiendo en restaurante {}: {:?}", user.id, restaurant_id, order_req);
+
+    if let Err(errors) = order_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ORDERS] Error creando pedido: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al crear el pedido",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let order = match orders::place_order(&mut tx, user.id, restaurant_id, &order_req).await {
+        Ok(order) => order,
+        Err(OrderError::Database(e)) => return Ok(internal_error(e)),
+        Err(OrderError::RestaurantUnavailable) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": OrderError::RestaurantUnavailable.to_string()
+            })));
+        }
+        Err(e) => {
+            println!("❌ [ORDERS] Pedido inválido: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    // Avisar al restaurante que tiene un pedido nuevo
+    if let Err(e) = notify_restaurant_owner(&mut tx, restaurant_id, &order).await {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ORDERS] Pedido {} creado ({})", order.id, order.reference);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Pedido creado exitosamente",
+        "order": order
+    })))
+}
+
+pub async fn get_my_orders(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("📋 [ORDERS] Obteniendo pedidos para usuario {}", user.id);
+
+    let rows = match sqlx::query!(
+        r#"
+        SELECT o.id, o.order_reference, o.reservation_id, o.status, o.total_price, o.currency,
+               o.notes, o.cancelled_at, o.created_at, o.updated_at,
+               r.id as restaurant_id, r.name as restaurant_name, r.location as restaurant_location
+        FROM restaurant_orders o
+        JOIN restaurants r ON o.restaurant_id = r.id
+        WHERE o.user_id = $1
+        ORDER BY o.created_at DESC
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            println!("💥 [ORDERS] Error obteniendo pedidos: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener pedidos",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let order_ids: Vec<i32> = rows.iter().map(|o| o.id).collect();
+    let mut items = match load_order_items(pool.get_ref(), &order_ids).await {
+        Ok(items) => items,
+        Err(e) => {
+            println!("💥 [ORDERS] Error obteniendo platillos de pedidos: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener pedidos",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let order_list: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|o| json!({
+            "id": o.id,
+            "reference": o.order_reference,
+            "restaurant_id": o.restaurant_id,
+            "restaurant_name": o.restaurant_name,
+            "restaurant_location": o.restaurant_location,
+            "reservation_id": o.reservation_id,
+            "status": o.status,
+            "items": items.remove(&o.id).unwrap_or_default(),
+            "total_price": Money::from_db(o.total_price, &o.currency),
+            "notes": o.notes,
+            "cancelled_at": o.cancelled_at,
+            "created_at": o.created_at,
+            "updated_at": o.updated_at
+        }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "orders": order_list,
+        "total": order_list.len()
+    })))
+}
+
+// El cliente solo puede cancelar mientras el restaurante no haya aceptado el pedido
+pub async fn cancel_order(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    cancel_req: web::Json<UpdateBookingStatusRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let order_id = path.into_inner();
+    println!("🚫 [ORDERS] Cancelando pedido {} para usuario {}", order_id, user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ORDERS] Error cancelando pedido {}: {}", order_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cancelar el pedido",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let cancelled = match sqlx::query!(
+        r#"
+        UPDATE restaurant_orders SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $3, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND user_id = $2 AND status = 'placed'
+        RETURNING id
+        "#,
+        order_id,
+        user.id,
+        cancel_req.cancellation_reason
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(cancelled)) => cancelled,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Pedido no encontrado o ya no se puede cancelar"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Err(e) = orders::record_status_change(
+        &mut tx,
+        cancelled.id,
+        Some(OrderStatus::Placed),
+        OrderStatus::Cancelled,
+        user.id,
+    )
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ORDERS] Pedido {} cancelado", order_id);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Pedido cancelado exitosamente"
+    })))
+}
+
+pub async fn get_restaurant_orders(
+    pool: web::Data<PgPool>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+
+    let rows = match sqlx::query!(
+        r#"
+        SELECT o.id, o.order_reference, o.reservation_id, o.status, o.total_price, o.currency,
+               o.notes, o.created_at, o.updated_at,
+               u.first_name || ' ' || u.last_name as "customer_name!",
+               u.email as customer_email
+        FROM restaurant_orders o
+        JOIN users u ON o.user_id = u.id
+        WHERE o.restaurant_id = $1
+        ORDER BY o.status IN ('delivered', 'cancelled'), o.created_at DESC
+        "#,
+        restaurant_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            println!("💥 [RESTAURANT_ORDERS] Error: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los pedidos del restaurante",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let order_ids: Vec<i32> = rows.iter().map(|o| o.id).collect();
+    let mut items = match load_order_items(pool.get_ref(), &order_ids).await {
+        Ok(items) => items,
+        Err(e) => {
+            println!("💥 [RESTAURANT_ORDERS] Error obteniendo platillos: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los pedidos del restaurante",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let order_list: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|o| json!({
+            "id": o.id,
+            "reference": o.order_reference,
+            "reservation_id": o.reservation_id,
+            "status": o.status,
+            "items": items.remove(&o.id).unwrap_or_default(),
+            "total_price": Money::from_db(o.total_price, &o.currency),
+            "notes": o.notes,
+            "created_at": o.created_at,
+            "updated_at": o.updated_at,
+            "customer": {
+                "name": o.customer_name,
+                "email": o.customer_email
+            }
+        }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "orders": order_list,
+        "total": order_list.len()
+    })))
+}
+
+// Avanza el pedido en su ciclo de vida y avisa al cliente, en una misma transacción
+pub async fn update_order_status(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    status_req: web::Json<UpdateOrderStatusRequest>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    let (_, order_id) = path.into_inner();
+    let target = status_req.status;
+    println!("🍳 [ORDERS] Restaurante {} cambiando pedido {} a '{}'", restaurant_id, order_id, target.as_str());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [ORDERS] Error actualizando pedido {}: {}", order_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar el estado del pedido",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let order = match sqlx::query!(
+        r#"
+        SELECT o.user_id, o.status, o.order_reference, r.name as restaurant_name
+        FROM restaurant_orders o
+        JOIN restaurants r ON o.restaurant_id = r.id
+        WHERE o.id = $1 AND o.restaurant_id = $2
+        FOR UPDATE OF o
+        "#,
+        order_id,
+        restaurant_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(order)) => order,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Pedido no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = OrderStatus::from_db(&order.status).unwrap_or(OrderStatus::Placed);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar un pedido de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE restaurant_orders SET status = $2::varchar,
+            cancelled_at = CASE WHEN $2::varchar = 'cancelled' THEN CURRENT_TIMESTAMP ELSE cancelled_at END,
+            cancellation_reason = CASE WHEN $2::varchar = 'cancelled' THEN $3 ELSE cancellation_reason END,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        order_id,
+        target.as_str(),
+        status_req.cancellation_reason
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = orders::record_status_change(&mut tx, order_id, Some(current), target, owner.user.id).await {
+        return Ok(internal_error(e));
+    }
+
+    let (title, message) = match target {
+        OrderStatus::Accepted => ("Tu pedido fue aceptado", format!("{} aceptó tu pedido {}.", order.restaurant_name, order.order_reference)),
+        OrderStatus::Preparing => ("Tu pedido se está preparando", format!("{} está preparando tu pedido {}.", order.restaurant_name, order.order_reference)),
+        OrderStatus::Ready => ("Tu pedido está listo", format!("Tu pedido {} en {} está listo.", order.order_reference, order.restaurant_name)),
+        OrderStatus::Delivered => ("Pedido entregado", format!("Tu pedido {} fue entregado. ¡Buen provecho!", order.order_reference)),
+        OrderStatus::Cancelled => ("Tu pedido fue cancelado", format!(
+            "{} canceló tu pedido {}. {}",
+            order.restaurant_name,
+            order.order_reference,
+            status_req.cancellation_reason.as_deref().unwrap_or("")
+        )),
+        OrderStatus::Placed => ("Pedido recibido", format!("Tu pedido {} fue recibido.", order.order_reference)),
+    };
+
+    if let Err(e) = notifications::notify(
+        &mut tx,
+        order.user_id,
+        "order_status",
+        title,
+        message.trim(),
+        Some(json!({ "order_id": order_id, "status": target.as_str() })),
+    )
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [ORDERS] Pedido {} ahora está '{}'", order_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Estado del pedido actualizado",
+        "order": {
+            "id": order_id,
+            "reference": order.order_reference,
+            "from_status": current.as_str(),
+            "status": target.as_str()
+        }
+    })))
+}
+
+// Platillos de varios pedidos agrupados por pedido
+async fn load_order_items(
+    pool: &PgPool,
+    order_ids: &[i32],
+) -> Result<HashMap<i32, Vec<serde_json::Value>>, sqlx::Error> {
+    let mut grouped: HashMap<i32, Vec<serde_json::Value>> = HashMap::new();
+    if order_ids.is_empty() {
+        return Ok(grouped);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT i.order_id, i.menu_item_id, i.name, i.unit_price, i.quantity, i.subtotal, o.currency
+        FROM restaurant_order_items i
+        JOIN restaurant_orders o ON i.order_id = o.id
+        WHERE i.order_id = ANY($1)
+        ORDER BY i.id
+        "#,
+        order_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for item in rows {
+        grouped.entry(item.order_id).or_default().push(json!({
+            "menu_item_id": item.menu_item_id,
+            "name": item.name,
+            "unit_price": Money::from_db(item.unit_price, &item.currency),
+            "quantity": item.quantity,
+            "subtotal": Money::from_db(item.subtotal, &item.currency)
+        }));
+    }
+
+    Ok(grouped)
+}
+
+async fn notify_restaurant_owner(
+    conn: &mut PgConnection,
+    restaurant_id: i32,
+    order: &orders::PlacedOrder,
+) -> Result<(), sqlx::Error> {
+    let restaurant = sqlx::query!(
+        "SELECT owner_id, name FROM restaurants WHERE id = $1",
+        restaurant_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if let Some(owner_id) = restaurant.owner_id {
+        notifications::notify(
+            &mut *conn,
+            owner_id,
+            "new_order",
+            "Nuevo pedido",
+            &format!(
+                "Recibiste el pedido {} en {} por {} {}.",
+                order.reference,
+                restaurant.name,
+                order.total_price.amount().with_scale(2),
+                order.total_price.currency().as_str()
+            ),
+            Some(json!({ "order_id": order.id, "restaurant_id": restaurant_id })),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
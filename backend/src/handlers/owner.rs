@@ -0,0 +1,1084 @@
+// backend/src/handlers/owner.rs
+use actix_web::{web, HttpResponse, Result};
+use bigdecimal::{BigDecimal, Zero};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::roles::{Hotel, Owner, RequireOwnerOf, RequireRole, Restaurant};
+use crate::models::*;
+use crate::money::Money;
+use crate::services::refunds::CancellationPolicy;
+
+pub async fn get_my_hotels(
+    pool: web::Data<PgPool>,
+    owner: RequireRole<Owner>,
+) -> Result<HttpResponse> {
+    println!("🏨 [OWNER] Obteniendo hoteles del socio {}", owner.user.id);
+
+    let hotels = sqlx::query!(
+        r#"
+        SELECT h.id, h.name, h.location, h.address, h.price, h.currency, h.status,
+               h.total_rooms, h.max_guests_per_room, h.created_at, h.updated_at,
+               COUNT(b.id) FILTER (
+                   WHERE b.check_out >= CURRENT_DATE AND b.status IS DISTINCT FROM 'cancelled'
+               ) as "upcoming_bookings!"
+        FROM hotels h
+        LEFT JOIN bookings b ON b.hotel_id = h.id
+        WHERE h.owner_id = $1
+        GROUP BY h.id
+        ORDER BY h.created_at DESC
+        "#,
+        owner.user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match hotels {
+        Ok(hotel_list) => {
+            let hotels_json: Vec<serde_json::Value> = hotel_list
+                .into_iter()
+                .map(|h| json!({
+                    "id": h.id,
+                    "name": h.name,
+                    "location": h.location,
+                    "address": h.address,
+                    "price": Money::from_db(h.price, &h.currency),
+                    "status": h.status,
+                    "total_rooms": h.total_rooms,
+                    "max_guests_per_room": h.max_guests_per_room,
+                    "upcoming_bookings": h.upcoming_bookings,
+                    "created_at": h.created_at,
+                    "updated_at": h.updated_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "hotels": hotels_json,
+                "total": hotels_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error obteniendo hoteles: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener tus hoteles",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_hotel(
+    pool: web::Data<PgPool>,
+    hotel_req: web::Json<CreateHotelRequest>,
+    owner: RequireRole<Owner>,
+) -> Result<HttpResponse> {
+    println!("🏗️ [OWNER] Registrando hotel '{}' para socio {}", hotel_req.name, owner.user.id);
+
+    if let Err(errors) = hotel_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if hotel_req.price <= BigDecimal::zero() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "El precio por noche debe ser mayor a cero"
+        })));
+    }
+
+    // Las propiedades nuevas quedan pendientes hasta que un administrador las apruebe
+    let hotel = sqlx::query!(
+        r#"
+        INSERT INTO hotels (name, location, address, price, owner_id, status, total_rooms, max_guests_per_room)
+        VALUES ($1, $2, $3, $4, $5, 'pending', $6, $7)
+        RETURNING id, price, currency, status, created_at
+        "#,
+        hotel_req.name,
+        hotel_req.location,
+        hotel_req.address,
+        hotel_req.price,
+        owner.user.id,
+        hotel_req.total_rooms,
+        hotel_req.max_guests_per_room.unwrap_or(2)
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match hotel {
+        Ok(hotel) => {
+            println!("✅ [OWNER] Hotel {} registrado (pendiente de aprobación)", hotel.id);
+            Ok(HttpResponse::Created().json(json!({
+                "message": "Hotel registrado. Será visible cuando un administrador lo apruebe.",
+                "hotel": {
+                    "id": hotel.id,
+                    "name": hotel_req.name,
+                    "location": hotel_req.location,
+                    "address": hotel_req.address,
+                    "price": Money::from_db(hotel.price, &hotel.currency),
+                    "total_rooms": hotel_req.total_rooms,
+                    "status": hotel.status,
+                    "created_at": hotel.created_at
+                }
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error insertando hotel: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al registrar el hotel",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_hotel(
+    pool: web::Data<PgPool>,
+    hotel_req: web::Json<UpdateHotelRequest>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    println!("✏️ [OWNER] Actualizando hotel {} por usuario {}", hotel_id, owner.user.id);
+
+    if let Err(errors) = hotel_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(price) = &hotel_req.price {
+        if *price <= BigDecimal::zero() {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "El precio por noche debe ser mayor a cero"
+            })));
+        }
+    }
+
+    let hotel = sqlx::query!(
+        r#"
+        UPDATE hotels SET
+            name = COALESCE($2, name),
+            location = COALESCE($3, location),
+            address = COALESCE($4, address),
+            price = COALESCE($5, price),
+            total_rooms = COALESCE($6, total_rooms),
+            max_guests_per_room = COALESCE($7, max_guests_per_room),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING id, name, location, address, price, currency, status, total_rooms, max_guests_per_room, updated_at
+        "#,
+        hotel_id,
+        hotel_req.name,
+        hotel_req.location,
+        hotel_req.address,
+        hotel_req.price,
+        hotel_req.total_rooms,
+        hotel_req.max_guests_per_room
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match hotel {
+        Ok(h) => {
+            println!("✅ [OWNER] Hotel {} actualizado", hotel_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Hotel actualizado exitosamente",
+                "hotel": {
+                    "id": h.id,
+                    "name": h.name,
+                    "location": h.location,
+                    "address": h.address,
+                    "price": Money::from_db(h.price, &h.currency),
+                    "status": h.status,
+                    "total_rooms": h.total_rooms,
+                    "max_guests_per_room": h.max_guests_per_room,
+                    "updated_at": h.updated_at
+                }
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error actualizando hotel: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el hotel",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === SERVICIOS ADICIONALES DEL HOTEL ===
+
+pub async fn get_hotel_addons(
+    pool: web::Data<PgPool>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+
+    let addons = sqlx::query!(
+        r#"
+        SELECT id, name, description, icon, price, currency, pricing_unit, max_quantity, is_active, updated_at
+        FROM addon_services
+        WHERE hotel_id = $1
+        ORDER BY is_active DESC, name
+        "#,
+        hotel_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match addons {
+        Ok(list) => {
+            let addons_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|a| json!({
+                    "id": a.id,
+                    "name": a.name,
+                    "description": a.description,
+                    "icon": a.icon,
+                    "price": Money::from_db(a.price, &a.currency),
+                    "pricing_unit": a.pricing_unit,
+                    "max_quantity": a.max_quantity,
+                    "is_active": a.is_active,
+                    "updated_at": a.updated_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "addons": addons_json })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error obteniendo addons del hotel {}: {}", hotel_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los servicios adicionales",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_addon(
+    pool: web::Data<PgPool>,
+    addon_req: web::Json<CreateAddonRequest>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    println!("➕ [OWNER] Creando addon '{}' para hotel {}", addon_req.name, hotel_id);
+
+    if let Err(errors) = addon_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_addon_pricing(Some(&addon_req.price), addon_req.pricing_unit.as_deref()) {
+        return Ok(response);
+    }
+
+    // El addon hereda la moneda del hotel para poder sumarse al total de la reserva
+    let addon = sqlx::query!(
+        r#"
+        INSERT INTO addon_services (hotel_id, name, description, icon, price, currency, pricing_unit, max_quantity)
+        SELECT $1, $2, $3, $4, $5, h.currency, $6, $7 FROM hotels h WHERE h.id = $1
+        RETURNING id, price, currency, pricing_unit, max_quantity, is_active
+        "#,
+        hotel_id,
+        addon_req.name,
+        addon_req.description,
+        addon_req.icon,
+        addon_req.price,
+        addon_req.pricing_unit.as_deref().unwrap_or("per_stay"),
+        addon_req.max_quantity.unwrap_or(10)
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match addon {
+        Ok(a) => Ok(HttpResponse::Created().json(json!({
+            "message": "Servicio adicional creado",
+            "addon": {
+                "id": a.id,
+                "name": addon_req.name,
+                "description": addon_req.description,
+                "icon": addon_req.icon,
+                "price": Money::from_db(a.price, &a.currency),
+                "pricing_unit": a.pricing_unit,
+                "max_quantity": a.max_quantity,
+                "is_active": a.is_active
+            }
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error creando addon: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al crear el servicio adicional",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_addon(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    addon_req: web::Json<UpdateAddonRequest>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    let (_, addon_id) = path.into_inner();
+    println!("✏️ [OWNER] Actualizando addon {} del hotel {}", addon_id, hotel_id);
+
+    if let Err(errors) = addon_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_addon_pricing(addon_req.price.as_ref(), addon_req.pricing_unit.as_deref()) {
+        return Ok(response);
+    }
+
+    let addon = sqlx::query!(
+        r#"
+        UPDATE addon_services SET
+            name = COALESCE($3, name),
+            description = COALESCE($4, description),
+            icon = COALESCE($5, icon),
+            price = COALESCE($6, price),
+            pricing_unit = COALESCE($7, pricing_unit),
+            max_quantity = COALESCE($8, max_quantity),
+            is_active = COALESCE($9, is_active),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND hotel_id = $2
+        RETURNING id, name, description, icon, price, currency, pricing_unit, max_quantity, is_active
+        "#,
+        addon_id,
+        hotel_id,
+        addon_req.name,
+        addon_req.description,
+        addon_req.icon,
+        addon_req.price,
+        addon_req.pricing_unit,
+        addon_req.max_quantity,
+        addon_req.is_active
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match addon {
+        Ok(Some(a)) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Servicio adicional actualizado",
+            "addon": {
+                "id": a.id,
+                "name": a.name,
+                "description": a.description,
+                "icon": a.icon,
+                "price": Money::from_db(a.price, &a.currency),
+                "pricing_unit": a.pricing_unit,
+                "max_quantity": a.max_quantity,
+                "is_active": a.is_active
+            }
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Servicio adicional no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error actualizando addon {}: {}", addon_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el servicio adicional",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Los addons se desactivan en lugar de borrarse: las reservas guardan su snapshot
+// pero el historial del catálogo se conserva
+pub async fn delete_addon(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    let (_, addon_id) = path.into_inner();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE addon_services SET is_active = FALSE, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND hotel_id = $2
+        "#,
+        addon_id,
+        hotel_id
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => {
+            println!("🗑️ [OWNER] Addon {} del hotel {} desactivado", addon_id, hotel_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Servicio adicional eliminado"
+            })))
+        }
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Servicio adicional no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error eliminando addon {}: {}", addon_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al eliminar el servicio adicional",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === POLÍTICA DE CANCELACIÓN ===
+
+// Solo afecta a cancelaciones futuras; los reembolsos ya emitidos guardan la política aplicada
+pub async fn update_cancellation_policy(
+    pool: web::Data<PgPool>,
+    policy_req: web::Json<CancellationPolicyRequest>,
+    owner: RequireOwnerOf<Hotel>,
+) -> Result<HttpResponse> {
+    let hotel_id = owner.resource_id;
+    println!("📜 [OWNER] Actualizando política de cancelación del hotel {}", hotel_id);
+
+    if let Err(errors) = policy_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    // La penalización solo tiene sentido en la política parcial
+    let penalty_percent = match policy_req.policy {
+        CancellationPolicyKind::Partial => match policy_req.penalty_percent {
+            Some(percent) => percent,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "error": "La política parcial requiere el porcentaje de penalización"
+                })));
+            }
+        },
+        _ => 100,
+    };
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE hotels SET cancellation_policy = $2, free_cancellation_days = $3,
+            cancellation_penalty_percent = $4, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        hotel_id,
+        policy_req.policy.as_str(),
+        policy_req.free_cancellation_days,
+        penalty_percent
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(_) => {
+            let policy = CancellationPolicy::from_db(
+                policy_req.policy.as_str(),
+                policy_req.free_cancellation_days,
+                penalty_percent,
+            );
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Política de cancelación actualizada",
+                "summary": policy.summary(),
+                "cancellation_policy": policy
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error actualizando política del hotel {}: {}", hotel_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar la política de cancelación",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === RESTAURANTES ===
+
+pub async fn get_my_restaurants(
+    pool: web::Data<PgPool>,
+    owner: RequireRole<Owner>,
+) -> Result<HttpResponse> {
+    println!("🍽️ [OWNER] Obteniendo restaurantes del socio {}", owner.user.id);
+
+    let restaurants = sqlx::query!(
+        r#"
+        SELECT r.id, r.name, r.location, r.address, r.specialty, r.description, r.image_url,
+               r.status, r.created_at, r.updated_at,
+               COUNT(m.id) FILTER (WHERE m.is_active) as "menu_items!"
+        FROM restaurants r
+        LEFT JOIN menu_items m ON m.restaurant_id = r.id
+        WHERE r.owner_id = $1
+        GROUP BY r.id
+        ORDER BY r.created_at DESC
+        "#,
+        owner.user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match restaurants {
+        Ok(list) => {
+            let restaurants_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|r| json!({
+                    "id": r.id,
+                    "name": r.name,
+                    "location": r.location,
+                    "address": r.address,
+                    "specialty": r.specialty,
+                    "description": r.description,
+                    "image": r.image_url,
+                    "status": r.status,
+                    "menu_items": r.menu_items,
+                    "created_at": r.created_at,
+                    "updated_at": r.updated_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "restaurants": restaurants_json,
+                "total": restaurants_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error obteniendo restaurantes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener tus restaurantes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_restaurant(
+    pool: web::Data<PgPool>,
+    restaurant_req: web::Json<CreateRestaurantRequest>,
+    owner: RequireRole<Owner>,
+) -> Result<HttpResponse> {
+    println!("🏗️ [OWNER] Registrando restaurante '{}' para usuario {}", restaurant_req.name, owner.user.id);
+
+    if let Err(errors) = restaurant_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    // Igual que los hoteles: pendiente hasta que un administrador lo apruebe
+    let restaurant = sqlx::query!(
+        r#"
+        INSERT INTO restaurants (owner_id, name, location, address, specialty, description, image_url)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, status, created_at
+        "#,
+        owner.user.id,
+        restaurant_req.name,
+        restaurant_req.location,
+        restaurant_req.address,
+        restaurant_req.specialty,
+        restaurant_req.description,
+        restaurant_req.image_url
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match restaurant {
+        Ok(r) => {
+            println!("✅ [OWNER] Restaurante {} registrado (pendiente de aprobación)", r.id);
+            Ok(HttpResponse::Created().json(json!({
+                "message": "Restaurante registrado. Será visible cuando un administrador lo apruebe.",
+                "restaurant": {
+                    "id": r.id,
+                    "name": restaurant_req.name,
+                    "location": restaurant_req.location,
+                    "address": restaurant_req.address,
+                    "specialty": restaurant_req.specialty,
+                    "description": restaurant_req.description,
+                    "image": restaurant_req.image_url,
+                    "status": r.status,
+                    "created_at": r.created_at
+                }
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error insertando restaurante: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al registrar el restaurante",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_restaurant(
+    pool: web::Data<PgPool>,
+    restaurant_req: web::Json<UpdateRestaurantRequest>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    println!("✏️ [OWNER] Actualizando restaurante {} por usuario {}", restaurant_id, owner.user.id);
+
+    if let Err(errors) = restaurant_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let restaurant = sqlx::query!(
+        r#"
+        UPDATE restaurants SET
+            name = COALESCE($2, name),
+            location = COALESCE($3, location),
+            address = COALESCE($4, address),
+            specialty = COALESCE($5, specialty),
+            description = COALESCE($6, description),
+            image_url = COALESCE($7, image_url),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING id, name, location, address, specialty, description, image_url, status, updated_at
+        "#,
+        restaurant_id,
+        restaurant_req.name,
+        restaurant_req.location,
+        restaurant_req.address,
+        restaurant_req.specialty,
+        restaurant_req.description,
+        restaurant_req.image_url
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match restaurant {
+        Ok(r) => {
+            println!("✅ [OWNER] Restaurante {} actualizado", restaurant_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Restaurante actualizado exitosamente",
+                "restaurant": {
+                    "id": r.id,
+                    "name": r.name,
+                    "location": r.location,
+                    "address": r.address,
+                    "specialty": r.specialty,
+                    "description": r.description,
+                    "image": r.image_url,
+                    "status": r.status,
+                    "updated_at": r.updated_at
+                }
+            })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error actualizando restaurante: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el restaurante",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === MENÚ DEL RESTAURANTE ===
+
+pub async fn get_restaurant_menu(
+    pool: web::Data<PgPool>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+
+    let items = sqlx::query!(
+        r#"
+        SELECT id, name, description, price, currency, category, is_available, sort_order, updated_at
+        FROM menu_items
+        WHERE restaurant_id = $1 AND is_active
+        ORDER BY category, sort_order, name
+        "#,
+        restaurant_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match items {
+        Ok(list) => {
+            let items_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|m| json!({
+                    "id": m.id,
+                    "name": m.name,
+                    "description": m.description,
+                    "price": Money::from_db(m.price, &m.currency),
+                    "category": m.category,
+                    "is_available": m.is_available,
+                    "sort_order": m.sort_order,
+                    "updated_at": m.updated_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({ "menu_items": items_json })))
+        }
+        Err(e) => {
+            println!("💥 [OWNER] Error obteniendo menú del restaurante {}: {}", restaurant_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener el menú",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_menu_item(
+    pool: web::Data<PgPool>,
+    item_req: web::Json<CreateMenuItemRequest>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    println!("➕ [OWNER] Creando platillo '{}' para restaurante {}", item_req.name, restaurant_id);
+
+    if let Err(errors) = item_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_menu_price(Some(&item_req.price)) {
+        return Ok(response);
+    }
+
+    // El platillo hereda la moneda del restaurante
+    let item = sqlx::query!(
+        r#"
+        INSERT INTO menu_items (restaurant_id, name, description, price, currency, category, is_available, sort_order)
+        SELECT $1, $2, $3, $4, r.currency, $5, $6, $7 FROM restaurants r WHERE r.id = $1
+        RETURNING id, price, currency, category, is_available, sort_order
+        "#,
+        restaurant_id,
+        item_req.name,
+        item_req.description,
+        item_req.price,
+        item_req.category.as_deref().unwrap_or("Platos Fuertes"),
+        item_req.is_available.unwrap_or(true),
+        item_req.sort_order.unwrap_or(0)
+    )
+    .fetch_one(pool.get_ref())
+    .await;
+
+    match item {
+        Ok(m) => Ok(HttpResponse::Created().json(json!({
+            "message": "Platillo agregado al menú",
+            "menu_item": {
+                "id": m.id,
+                "name": item_req.name,
+                "description": item_req.description,
+                "price": Money::from_db(m.price, &m.currency),
+                "category": m.category,
+                "is_available": m.is_available,
+                "sort_order": m.sort_order
+            }
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error creando platillo: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al agregar el platillo",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_menu_item(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    item_req: web::Json<UpdateMenuItemRequest>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    let (_, item_id) = path.into_inner();
+    println!("✏️ [OWNER] Actualizando platillo {} del restaurante {}", item_id, restaurant_id);
+
+    if let Err(errors) = item_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(response) = invalid_menu_price(item_req.price.as_ref()) {
+        return Ok(response);
+    }
+
+    let item = sqlx::query!(
+        r#"
+        UPDATE menu_items SET
+            name = COALESCE($3, name),
+            description = COALESCE($4, description),
+            price = COALESCE($5, price),
+            category = COALESCE($6, category),
+            is_available = COALESCE($7, is_available),
+            sort_order = COALESCE($8, sort_order),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND restaurant_id = $2 AND is_active
+        RETURNING id, name, description, price, currency, category, is_available, sort_order
+        "#,
+        item_id,
+        restaurant_id,
+        item_req.name,
+        item_req.description,
+        item_req.price,
+        item_req.category,
+        item_req.is_available,
+        item_req.sort_order
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match item {
+        Ok(Some(m)) => Ok(HttpResponse::Ok().json(json!({
+            "message": "Platillo actualizado",
+            "menu_item": {
+                "id": m.id,
+                "name": m.name,
+                "description": m.description,
+                "price": Money::from_db(m.price, &m.currency),
+                "category": m.category,
+                "is_available": m.is_available,
+                "sort_order": m.sort_order
+            }
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Platillo no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error actualizando platillo {}: {}", item_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al actualizar el platillo",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Igual que los addons: se desactiva para no romper pedidos que lo referencien
+pub async fn delete_menu_item(
+    pool: web::Data<PgPool>,
+    path: web::Path<(i32, i32)>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    let (_, item_id) = path.into_inner();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE menu_items SET is_active = FALSE, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND restaurant_id = $2 AND is_active
+        "#,
+        item_id,
+        restaurant_id
+    )
+    .execute(pool.get_ref())
+    .await;
+
+    match result {
+        Ok(res) if res.rows_affected() > 0 => {
+            println!("🗑️ [OWNER] Platillo {} del restaurante {} eliminado", item_id, restaurant_id);
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Platillo eliminado del menú"
+            })))
+        }
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Platillo no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [OWNER] Error eliminando platillo {}: {}", item_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al eliminar el platillo",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === RESERVACIONES DE MESA ===
+
+pub async fn update_reservation_settings(
+    pool: web::Data<PgPool>,
+    settings_req: web::Json<ReservationSettingsRequest>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+    println!("🕒 [OWNER] Actualizando reservaciones del restaurante {}", restaurant_id);
+
+    if let Err(errors) = settings_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if let Some(hours) = &settings_req.opening_hours {
+        if hours.iter().any(|h| !(1..=7).contains(&h.weekday) || h.opens_at >= h.closes_at) {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Horario inválido: el día va de 1 (lunes) a 7 (domingo) y la apertura debe ser antes del cierre"
+            })));
+        }
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [OWNER] Error actualizando reservaciones del restaurante {}: {}", restaurant_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar la configuración de reservaciones",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = match sqlx::query!(
+        r#"
+        SELECT slot_minutes, covers_per_slot, min_party_size, max_party_size
+        FROM restaurants WHERE id = $1 FOR UPDATE
+        "#,
+        restaurant_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    {
+        Ok(current) => current,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let slot_minutes = settings_req.slot_minutes.unwrap_or(current.slot_minutes);
+    let covers_per_slot = settings_req.covers_per_slot.unwrap_or(current.covers_per_slot);
+    let min_party_size = settings_req.min_party_size.unwrap_or(current.min_party_size);
+    let max_party_size = settings_req.max_party_size.unwrap_or(current.max_party_size);
+
+    if min_party_size > max_party_size || max_party_size > covers_per_slot {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "El mínimo de comensales no puede superar al máximo, ni el máximo a los lugares por turno"
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE restaurants SET slot_minutes = $2, covers_per_slot = $3, min_party_size = $4,
+            max_party_size = $5, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        restaurant_id,
+        slot_minutes,
+        covers_per_slot,
+        min_party_size,
+        max_party_size
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    // Las reservaciones ya confirmadas se respetan aunque cambie el horario
+    if let Some(hours) = &settings_req.opening_hours {
+        if let Err(e) = sqlx::query!(
+            "DELETE FROM restaurant_opening_hours WHERE restaurant_id = $1",
+            restaurant_id
+        )
+        .execute(&mut *tx)
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+
+        for h in hours {
+            if let Err(e) = sqlx::query!(
+                r#"
+                INSERT INTO restaurant_opening_hours (restaurant_id, weekday, opens_at, closes_at)
+                VALUES ($1, $2, $3, $4)
+                "#,
+                restaurant_id,
+                h.weekday,
+                h.opens_at,
+                h.closes_at
+            )
+            .execute(&mut *tx)
+            .await
+            {
+                return Ok(internal_error(e));
+            }
+        }
+    }
+
+    let opening_hours = match sqlx::query!(
+        r#"
+        SELECT weekday, opens_at, closes_at FROM restaurant_opening_hours
+        WHERE restaurant_id = $1
+        ORDER BY weekday, opens_at
+        "#,
+        restaurant_id
+    )
+    .fetch_all(&mut *tx)
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|h| json!({ "weekday": h.weekday, "opens_at": h.opens_at, "closes_at": h.closes_at }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [OWNER] Reservaciones del restaurante {} actualizadas", restaurant_id);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Configuración de reservaciones actualizada",
+        "settings": {
+            "slot_minutes": slot_minutes,
+            "covers_per_slot": covers_per_slot,
+            "min_party_size": min_party_size,
+            "max_party_size": max_party_size,
+            "opening_hours": opening_hours
+        }
+    })))
+}
+
+fn invalid_menu_price(price: Option<&BigDecimal>) -> Option<HttpResponse> {
+    match price {
+        Some(price) if *price < BigDecimal::zero() => Some(HttpResponse::BadRequest().json(json!({
+            "error": "El precio del platillo no puede ser negativo"
+        }))),
+        _ => None,
+    }
+}
+
+fn invalid_addon_pricing(price: Option<&BigDecimal>, pricing_unit: Option<&str>) -> Option<HttpResponse> {
+    if let Some(price) = price {
+        if *price < BigDecimal::zero() {
+            return Some(HttpResponse::BadRequest().json(json!({
+                "error": "El precio del servicio adicional no puede ser negativo"
+            })));
+        }
+    }
+
+    if let Some(unit) = pricing_unit {
+        if !ADDON_PRICING_UNITS.contains(&unit) {
+            return Some(HttpResponse::BadRequest().json(json!({
+                "error": "Unidad de cobro inválida. Usa per_stay, per_night o per_guest"
+            })));
+        }
+    }
+
+    None
+}
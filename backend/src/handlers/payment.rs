@@ -0,0 +1,213 @@
+// backend/src/handlers/payment.rs
+use actix_web::{web, HttpRequest, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::*;
+use crate::services::bookings::BookingHolder;
+use crate::services::payments::{self, PaymentError, PaymentProviders, WebhookOutcome};
+
+// Crea el intento de cobro de una reserva con el proveedor configurado.
+// Repetir la solicitud devuelve el mismo intento mientras siga abierto.
+pub async fn create_payment_intent(
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let booking_id = path.into_inner();
+    println!("💳 [PAYMENTS] Usuario {} inicia pago de la reserva {}", user.id, booking_id);
+
+    Ok(start_booking_payment(pool.get_ref(), providers.get_ref(), booking_id, BookingHolder::User(user.id)).await)
+}
+
+// Cobro de una reserva de su titular, sea usuario o huésped sin cuenta
+pub(crate) async fn start_booking_payment(
+    pool: &PgPool,
+    providers: &PaymentProviders,
+    booking_id: i32,
+    holder: BookingHolder,
+) -> HttpResponse {
+    let provider = providers.default_provider();
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [PAYMENTS] Error creando intento de pago: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al iniciar el pago",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return internal_error(e),
+    };
+
+    let (intent, reused) = match payments::create_booking_intent(&mut tx, provider, booking_id, holder).await {
+        Ok(result) => result,
+        Err(PaymentError::Database(e)) => return internal_error(e),
+        Err(PaymentError::BookingNotFound) => {
+            return HttpResponse::NotFound().json(json!({
+                "error": PaymentError::BookingNotFound.to_string()
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            }));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return internal_error(e);
+    }
+
+    println!("✅ [PAYMENTS] Intento {} ({}) para la reserva {}", intent.provider_intent_id, provider.name(), booking_id);
+    let mut response = if reused { HttpResponse::Ok() } else { HttpResponse::Created() };
+    response.json(json!({
+        "message": if reused { "Ya tienes un pago en curso para esta reserva" } else { "Pago iniciado" },
+        "payment_intent": intent
+    }))
+}
+
+// Cobro del viaje completo: sus reservas no se pagan por separado
+pub async fn create_trip_payment_intent(
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let trip_id = path.into_inner();
+    let provider = providers.default_provider();
+    println!("💳 [PAYMENTS] Usuario {} inicia pago del viaje {}", user.id, trip_id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [PAYMENTS] Error creando intento de pago del viaje: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al iniciar el pago",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let (intent, reused) = match payments::create_trip_intent(&mut tx, provider, trip_id, user.id).await {
+        Ok(result) => result,
+        Err(PaymentError::Database(e)) => return Ok(internal_error(e)),
+        Err(PaymentError::TripNotFound) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": PaymentError::TripNotFound.to_string()
+            })));
+        }
+        Err(e) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [PAYMENTS] Intento {} ({}) para el viaje {}", intent.provider_intent_id, provider.name(), trip_id);
+    let mut response = if reused { HttpResponse::Ok() } else { HttpResponse::Created() };
+    Ok(response.json(json!({
+        "message": if reused { "Ya tienes un pago en curso para este viaje" } else { "Pago iniciado" },
+        "payment_intent": intent
+    })))
+}
+
+// Webhook del proveedor: POST /api/payments/webhooks/{provider}. El cuerpo se
+// recibe sin procesar porque la firma se calcula sobre los bytes exactos.
+pub async fn payment_webhook(
+    req: HttpRequest,
+    body: web::Bytes,
+    pool: web::Data<PgPool>,
+    providers: web::Data<PaymentProviders>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let provider_name = path.into_inner();
+    let provider = match providers.get(&provider_name) {
+        Some(provider) => provider,
+        None => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": format!("Proveedor de pagos '{}' no configurado", provider_name)
+            })));
+        }
+    };
+
+    let signature = req
+        .headers()
+        .get(provider.signature_header())
+        .and_then(|value| value.to_str().ok());
+
+    let event = match provider.parse_webhook(&body, signature) {
+        Ok(event) => event,
+        Err(PaymentError::InvalidSignature) => {
+            println!("⛔ [PAYMENTS] Webhook de '{}' con firma inválida", provider_name);
+            return Ok(HttpResponse::Unauthorized().json(json!({
+                "error": PaymentError::InvalidSignature.to_string()
+            })));
+        }
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    println!("📨 [PAYMENTS] Evento {} ({}) de '{}'", event.id, event.event_type, provider_name);
+
+    // Ante un error se responde 500 para que el proveedor reintente la entrega;
+    // como la transacción se descarta, el reintento no se toma como duplicado
+    let internal_error = |e: PaymentError| {
+        println!("💥 [PAYMENTS] Error procesando evento {}: {}", event.id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al procesar el evento"
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e.into())),
+    };
+
+    let outcome = match payments::apply_webhook_event(&mut tx, provider.name(), &event).await {
+        Ok(outcome) => outcome,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e.into()));
+    }
+
+    match outcome {
+        WebhookOutcome::Duplicate => {
+            println!("🔁 [PAYMENTS] Evento {} ya procesado", event.id);
+            Ok(HttpResponse::Ok().json(json!({ "received": true, "duplicate": true })))
+        }
+        WebhookOutcome::Ignored => Ok(HttpResponse::Ok().json(json!({ "received": true, "applied": false }))),
+        WebhookOutcome::Applied { booking_id, payment_status } => {
+            println!("✅ [PAYMENTS] Reserva {} ahora está '{}'", booking_id, payment_status);
+            Ok(HttpResponse::Ok().json(json!({
+                "received": true,
+                "applied": true,
+                "booking_id": booking_id,
+                "payment_status": payment_status
+            })))
+        }
+        WebhookOutcome::TripApplied { trip_id, payment_status } => {
+            println!("✅ [PAYMENTS] Viaje {} ahora está '{}'", trip_id, payment_status);
+            Ok(HttpResponse::Ok().json(json!({
+                "received": true,
+                "applied": true,
+                "trip_id": trip_id,
+                "payment_status": payment_status
+            })))
+        }
+    }
+}
@@ -0,0 +1,304 @@
+// backend/src/handlers/product.rs
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::models::*;
+use crate::money::Money;
+use crate::services::products::{self, ProductError};
+
+const DEFAULT_PER_PAGE: i64 = 12;
+
+// Catálogo público con filtros por categoría, artesano y texto
+pub async fn get_products(
+    pool: web::Data<PgPool>,
+    query: web::Query<ProductsQuery>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = query.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Parámetros inválidos",
+            "details": errors
+        })));
+    }
+
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_PER_PAGE);
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [PRODUCTS] Error obteniendo catálogo: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "No se pudieron obtener los productos",
+            "details": e.to_string()
+        }))
+    };
+
+    let total = match sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "total!"
+        FROM products p
+        JOIN artisans a ON p.artisan_id = a.id
+        WHERE p.is_active
+          AND ($1::text IS NULL OR p.category = $1)
+          AND ($2::int4 IS NULL OR p.artisan_id = $2)
+          AND ($3::text IS NULL OR p.name ILIKE '%' || $3 || '%' OR a.name ILIKE '%' || $3 || '%')
+        "#,
+        query.category.map(|c| c.as_str()),
+        query.artisan_id,
+        search
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    {
+        Ok(total) => total,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let products = match sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.description, p.category, p.price, p.currency, p.stock, p.images,
+               a.id as artisan_id, a.name as artisan_name
+        FROM products p
+        JOIN artisans a ON p.artisan_id = a.id
+        WHERE p.is_active
+          AND ($1::text IS NULL OR p.category = $1)
+          AND ($2::int4 IS NULL OR p.artisan_id = $2)
+          AND ($3::text IS NULL OR p.name ILIKE '%' || $3 || '%' OR a.name ILIKE '%' || $3 || '%')
+        ORDER BY p.created_at DESC, p.id DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        query.category.map(|c| c.as_str()),
+        query.artisan_id,
+        search,
+        per_page,
+        (page - 1) * per_page
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(products) => products,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let products_json: Vec<serde_json::Value> = products
+        .into_iter()
+        .map(|p| json!({
+            "id": p.id,
+            "name": p.name,
+            "description": p.description,
+            "category": p.category,
+            "price": Money::from_db(p.price, &p.currency),
+            "stock": p.stock,
+            "in_stock": p.stock > 0,
+            "images": p.images,
+            "artisan_id": p.artisan_id,
+            "artisan": p.artisan_name
+        }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "products": products_json,
+        "page": page,
+        "per_page": per_page,
+        "total": total,
+        "total_pages": (total + per_page - 1) / per_page
+    })))
+}
+
+pub async fn get_product(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    let product_id = path.into_inner();
+
+    let product = sqlx::query!(
+        r#"
+        SELECT p.id, p.name, p.description, p.category, p.price, p.currency, p.stock, p.images,
+               a.id as artisan_id, a.name as artisan_name, a.community as artisan_community,
+               a.bio as artisan_bio
+        FROM products p
+        JOIN artisans a ON p.artisan_id = a.id
+        WHERE p.id = $1 AND p.is_active
+        "#,
+        product_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await;
+
+    match product {
+        Ok(Some(p)) => Ok(HttpResponse::Ok().json(json!({
+            "id": p.id,
+            "name": p.name,
+            "description": p.description,
+            "category": p.category,
+            "price": Money::from_db(p.price, &p.currency),
+            "stock": p.stock,
+            "in_stock": p.stock > 0,
+            "images": p.images,
+            "artisan_id": p.artisan_id,
+            "artisan": p.artisan_name,
+            "artisan_community": p.artisan_community,
+            "artisan_bio": p.artisan_bio
+        }))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Producto no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [PRODUCTS] Error consultando producto {}: {}", product_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener el producto",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// === COMPRAS ===
+
+pub async fn create_product_order(
+    pool: web::Data<PgPool>,
+    order_req: web::Json<CreateProductOrderRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("🛍️ [PRODUCTS] Usuario {} comprando: {:?}", user.id, order_req.items);
+
+    if let Err(errors) = order_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [PRODUCTS] Error procesando compra: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al procesar la compra",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let order = match products::purchase_products(&mut tx, user.id, &order_req).await {
+        Ok(order) => order,
+        Err(ProductError::Database(e)) => return Ok(internal_error(e)),
+        Err(e @ ProductError::InsufficientStock { .. }) => {
+            println!("❌ [PRODUCTS] Sin inventario: {}", e);
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            })));
+        }
+        Err(e) => {
+            println!("❌ [PRODUCTS] Compra inválida: {}", e);
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [PRODUCTS] Compra {} registrada ({})", order.id, order.reference);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Compra realizada exitosamente",
+        "order": order
+    })))
+}
+
+pub async fn get_my_product_orders(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("📋 [PRODUCTS] Obteniendo compras para usuario {}", user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [PRODUCTS] Error obteniendo compras: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al obtener compras",
+            "details": e.to_string()
+        }))
+    };
+
+    let rows = match sqlx::query!(
+        r#"
+        SELECT id, order_reference, status, total_price, currency, shipping_address, created_at
+        FROM product_orders
+        WHERE user_id = $1
+        ORDER BY created_at DESC
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let order_ids: Vec<i32> = rows.iter().map(|o| o.id).collect();
+    let mut items = match load_order_items(pool.get_ref(), &order_ids).await {
+        Ok(items) => items,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let order_list: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|o| json!({
+            "id": o.id,
+            "reference": o.order_reference,
+            "status": o.status,
+            "items": items.remove(&o.id).unwrap_or_default(),
+            "total_price": Money::from_db(o.total_price, &o.currency),
+            "shipping_address": o.shipping_address,
+            "created_at": o.created_at
+        }))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "orders": order_list,
+        "total": order_list.len()
+    })))
+}
+
+async fn load_order_items(
+    pool: &PgPool,
+    order_ids: &[i32],
+) -> Result<HashMap<i32, Vec<serde_json::Value>>, sqlx::Error> {
+    let mut grouped: HashMap<i32, Vec<serde_json::Value>> = HashMap::new();
+    if order_ids.is_empty() {
+        return Ok(grouped);
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT i.order_id, i.product_id, i.name, i.unit_price, i.quantity, i.subtotal, o.currency
+        FROM product_order_items i
+        JOIN product_orders o ON i.order_id = o.id
+        WHERE i.order_id = ANY($1)
+        ORDER BY i.id
+        "#,
+        order_ids
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for item in rows {
+        grouped.entry(item.order_id).or_default().push(json!({
+            "product_id": item.product_id,
+            "name": item.name,
+            "unit_price": Money::from_db(item.unit_price, &item.currency),
+            "quantity": item.quantity,
+            "subtotal": Money::from_db(item.subtotal, &item.currency)
+        }));
+    }
+
+    Ok(grouped)
+}
@@ -0,0 +1,93 @@
+// backend/src/handlers/quote.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::models::QuoteRequest;
+use crate::money::Money;
+use crate::services::pricing::{self, PricingError, StayRequest};
+
+// Cotización de prueba: mismo cálculo que create_booking, sin apartar habitaciones
+pub async fn create_quote(
+    pool: web::Data<PgPool>,
+    quote_req: web::Json<QuoteRequest>,
+) -> Result<HttpResponse> {
+    println!("🧮 [QUOTE] Cotizando: {:?}", quote_req);
+
+    if let Err(errors) = quote_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    if quote_req.check_out <= quote_req.check_in {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "La fecha de check-out debe ser posterior a la de check-in"
+        })));
+    }
+
+    let hotel = match sqlx::query!(
+        "SELECT name, price, currency FROM hotels WHERE id = $1 AND status = 'approved'",
+        quote_req.hotel_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(hotel)) => hotel,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Hotel no encontrado o no disponible"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [QUOTE] Error consultando hotel: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error del servidor al cotizar",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let nightly_rate = Money::from_db(hotel.price, &hotel.currency);
+    let stay = StayRequest {
+        hotel_id: quote_req.hotel_id,
+        check_in: quote_req.check_in,
+        check_out: quote_req.check_out,
+        guests: quote_req.guests,
+        rooms: quote_req.rooms,
+        addons: quote_req.addon_services.as_deref().unwrap_or(&[]),
+    };
+
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            println!("💥 [QUOTE] Error obteniendo conexión: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error del servidor al cotizar",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    match pricing::quote_stay(&mut conn, &nightly_rate, &stay).await {
+        Ok(quote) => {
+            println!("✅ [QUOTE] Total: {} {}", quote.total.amount(), quote.total.currency().as_str());
+            Ok(HttpResponse::Ok().json(json!({
+                "hotel_name": hotel.name,
+                "quote": quote
+            })))
+        }
+        Err(PricingError::Database(e)) => {
+            println!("💥 [QUOTE] Error calculando precio: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error del servidor al cotizar",
+                "details": e.to_string()
+            })))
+        }
+        Err(e) => Ok(HttpResponse::BadRequest().json(json!({
+            "error": e.to_string()
+        }))),
+    }
+}
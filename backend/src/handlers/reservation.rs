@@ -0,0 +1,375 @@
+// backend/src/handlers/reservation.rs
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::roles::{RequireOwnerOf, Restaurant};
+use crate::models::*;
+use crate::services::tables::{self, ReservationError};
+
+// Horarios del día con lugares disponibles
+pub async fn get_restaurant_availability(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    query: web::Query<SlotAvailabilityQuery>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    if let Err(errors) = query.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Parámetros inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RESERVATIONS] Error consultando disponibilidad del restaurante {}: {}", restaurant_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "No se pudo consultar la disponibilidad",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let restaurant = match sqlx::query!(
+        r#"
+        SELECT slot_minutes, covers_per_slot, min_party_size, max_party_size
+        FROM restaurants WHERE id = $1 AND status = 'approved'
+        "#,
+        restaurant_id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Restaurante no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let ranges = match tables::opening_ranges(&mut conn, restaurant_id, query.date).await {
+        Ok(ranges) => ranges,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let booked: HashMap<chrono::NaiveTime, i32> = match sqlx::query!(
+        r#"
+        SELECT slot_time, covers_booked FROM restaurant_slot_inventory
+        WHERE restaurant_id = $1 AND slot_date = $2
+        "#,
+        restaurant_id,
+        query.date
+    )
+    .fetch_all(&mut *conn)
+    .await
+    {
+        Ok(rows) => rows.into_iter().map(|r| (r.slot_time, r.covers_booked)).collect(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let party_size = query.party_size.unwrap_or(restaurant.min_party_size);
+    let slots: Vec<serde_json::Value> = tables::slots_for_day(&ranges, restaurant.slot_minutes)
+        .into_iter()
+        .map(|slot| {
+            let covers_left = (restaurant.covers_per_slot - booked.get(&slot).copied().unwrap_or(0)).max(0);
+            json!({
+                "time": slot,
+                "covers_left": covers_left,
+                "available": covers_left >= party_size
+            })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "restaurant_id": restaurant_id,
+        "date": query.date,
+        "party_size": party_size,
+        "min_party_size": restaurant.min_party_size,
+        "max_party_size": restaurant.max_party_size,
+        "slot_minutes": restaurant.slot_minutes,
+        "slots": slots
+    })))
+}
+
+pub async fn create_reservation(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    reservation_req: web::Json<CreateReservationRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+    println!("🍽️ [RESERVATIONS] Usuario {} reservando en restaurante {}: {:?}", user.id, restaurant_id, reservation_req);
+
+    if let Err(errors) = reservation_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RESERVATIONS] Error creando reservación: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al crear la reservación",
+            "details": e.to_string()
+        }))
+    };
+
+    // Apartar lugares y crear la reservación en una sola transacción
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let reservation = match tables::book_table(&mut tx, user.id, restaurant_id, &reservation_req).await {
+        Ok(reservation) => reservation,
+        Err(ReservationError::Database(e)) => return Ok(internal_error(e)),
+        Err(ReservationError::RestaurantUnavailable) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": ReservationError::RestaurantUnavailable.to_string()
+            })));
+        }
+        Err(e @ ReservationError::Full { covers_left }) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string(),
+                "covers_left": covers_left
+            })));
+        }
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [RESERVATIONS] Reservación {} creada ({})", reservation.id, reservation.reference);
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Reservación creada exitosamente",
+        "reservation": reservation
+    })))
+}
+
+pub async fn get_my_reservations(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("📋 [RESERVATIONS] Obteniendo reservaciones para usuario {}", user.id);
+
+    let reservations = sqlx::query!(
+        r#"
+        SELECT rr.id, rr.reservation_date, rr.slot_time, rr.party_size, rr.special_requests,
+               rr.status, rr.reservation_reference, rr.cancelled_at, rr.created_at,
+               r.id as restaurant_id, r.name as restaurant_name, r.location as restaurant_location,
+               r.address as restaurant_address
+        FROM restaurant_reservations rr
+        JOIN restaurants r ON rr.restaurant_id = r.id
+        WHERE rr.user_id = $1
+        ORDER BY rr.reservation_date DESC, rr.slot_time DESC
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match reservations {
+        Ok(rows) => {
+            let reservation_list: Vec<serde_json::Value> = rows
+                .into_iter()
+                .map(|r| json!({
+                    "id": r.id,
+                    "reference": r.reservation_reference,
+                    "restaurant_id": r.restaurant_id,
+                    "restaurant_name": r.restaurant_name,
+                    "restaurant_location": r.restaurant_location,
+                    "restaurant_address": r.restaurant_address,
+                    "reservation_date": r.reservation_date,
+                    "slot_time": r.slot_time,
+                    "party_size": r.party_size,
+                    "special_requests": r.special_requests,
+                    "status": r.status,
+                    "cancelled_at": r.cancelled_at,
+                    "created_at": r.created_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "reservations": reservation_list,
+                "total": reservation_list.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [RESERVATIONS] Error obteniendo reservaciones: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener reservaciones",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn cancel_reservation(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    cancel_req: web::Json<UpdateBookingStatusRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let reservation_id = path.into_inner();
+    println!("🚫 [RESERVATIONS] Cancelando reservación {} para usuario {}", reservation_id, user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RESERVATIONS] Error cancelando reservación {}: {}", reservation_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cancelar la reservación",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Las partes de un viaje sin pagar solo se cancelan con el viaje completo
+    match sqlx::query!(
+        r#"
+        SELECT t.trip_reference FROM restaurant_reservations r
+        JOIN trips t ON r.trip_id = t.id
+        WHERE r.id = $1 AND r.user_id = $2 AND t.payment_status <> 'paid'
+        "#,
+        reservation_id,
+        user.id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(trip)) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": format!(
+                    "Esta reservación es parte del viaje {}, pendiente de pago; cancela el viaje completo",
+                    trip.trip_reference
+                )
+            })));
+        }
+        Ok(None) => {}
+        Err(e) => return Ok(internal_error(e)),
+    }
+
+    let cancelled = match sqlx::query!(
+        r#"
+        UPDATE restaurant_reservations SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $3, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND user_id = $2 AND status = 'confirmed'
+        RETURNING restaurant_id, reservation_date, slot_time, party_size
+        "#,
+        reservation_id,
+        user.id,
+        cancel_req.cancellation_reason
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(cancelled)) => cancelled,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Reservación no encontrada o ya cancelada"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    // Devolver los lugares al horario
+    if let Err(e) = tables::release_covers(
+        &mut tx,
+        cancelled.restaurant_id,
+        cancelled.reservation_date,
+        cancelled.slot_time,
+        cancelled.party_size,
+    )
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [RESERVATIONS] Reservación {} cancelada", reservation_id);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Reservación cancelada exitosamente"
+    })))
+}
+
+pub async fn get_restaurant_reservations(
+    pool: web::Data<PgPool>,
+    query: web::Query<RestaurantReservationsQuery>,
+    owner: RequireOwnerOf<Restaurant>,
+) -> Result<HttpResponse> {
+    let restaurant_id = owner.resource_id;
+
+    let reservations = sqlx::query!(
+        r#"
+        SELECT rr.id, rr.reservation_date, rr.slot_time, rr.party_size, rr.special_requests,
+               rr.status, rr.reservation_reference, rr.created_at,
+               u.first_name || ' ' || u.last_name as "customer_name!",
+               u.email as customer_email
+        FROM restaurant_reservations rr
+        JOIN users u ON rr.user_id = u.id
+        WHERE rr.restaurant_id = $1
+          AND ($2::date IS NULL OR rr.reservation_date = $2)
+        ORDER BY rr.reservation_date, rr.slot_time
+        "#,
+        restaurant_id,
+        query.date
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match reservations {
+        Ok(rows) => {
+            let reservation_list: Vec<serde_json::Value> = rows
+                .into_iter()
+                .map(|r| json!({
+                    "id": r.id,
+                    "reference": r.reservation_reference,
+                    "reservation_date": r.reservation_date,
+                    "slot_time": r.slot_time,
+                    "party_size": r.party_size,
+                    "special_requests": r.special_requests,
+                    "status": r.status,
+                    "created_at": r.created_at,
+                    "customer": {
+                        "name": r.customer_name,
+                        "email": r.customer_email
+                    }
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "reservations": reservation_list,
+                "total": reservation_list.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [RESTAURANT_RESERVATIONS] Error: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener las reservaciones del restaurante",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// backend/src/handlers/restaurant.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::money::Money;
+
+pub async fn get_restaurants(pool: web::Data<PgPool>) -> Result<HttpResponse> {
+    let restaurants = sqlx::query!(
+        r#"
+        SELECT r.id, r.name, r.location, r.specialty, r.description, r.image_url,
+               COUNT(m.id) FILTER (WHERE m.is_active) as "menu_items!"
+        FROM restaurants r
+        LEFT JOIN menu_items m ON m.restaurant_id = r.id
+        WHERE r.status = 'approved'
+        GROUP BY r.id
+        ORDER BY r.name
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match restaurants {
+        Ok(list) => {
+            let restaurants_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|r| json!({
+                    "id": r.id,
+                    "name": r.name,
+                    "location": r.location,
+                    "specialty": r.specialty,
+                    "description": r.description,
+                    "image": r.image_url,
+                    "menu_items": r.menu_items
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(restaurants_json))
+        }
+        Err(e) => {
+            println!("💥 [RESTAURANTS] Error: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudieron obtener los restaurantes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Detalle del restaurante con su menú agrupado por categoría
+pub async fn get_restaurant(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    let restaurant_id = path.into_inner();
+
+    let restaurant = match sqlx::query!(
+        r#"
+        SELECT id, name, location, address, specialty, description, image_url
+        FROM restaurants
+        WHERE id = $1 AND status = 'approved'
+        "#,
+        restaurant_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Restaurante no encontrado"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [RESTAURANTS] Error consultando restaurante {}: {}", restaurant_id, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener el restaurante",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let items = sqlx::query!(
+        r#"
+        SELECT id, name, description, price, currency, category, is_available
+        FROM menu_items
+        WHERE restaurant_id = $1 AND is_active
+        ORDER BY category, sort_order, name
+        "#,
+        restaurant_id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match items {
+        Ok(items) => {
+            // Las filas vienen ordenadas por categoría: basta con agrupar las consecutivas
+            let mut menu: Vec<serde_json::Value> = Vec::new();
+            let mut current_category: Option<String> = None;
+            let mut current_items: Vec<serde_json::Value> = Vec::new();
+
+            for item in items {
+                if current_category.as_deref() != Some(item.category.as_str()) {
+                    if let Some(category) = current_category.take() {
+                        menu.push(json!({ "category": category, "items": current_items }));
+                        current_items = Vec::new();
+                    }
+                    current_category = Some(item.category.clone());
+                }
+
+                current_items.push(json!({
+                    "id": item.id,
+                    "name": item.name,
+                    "description": item.description,
+                    "price": Money::from_db(item.price, &item.currency),
+                    "is_available": item.is_available
+                }));
+            }
+            if let Some(category) = current_category {
+                menu.push(json!({ "category": category, "items": current_items }));
+            }
+
+            Ok(HttpResponse::Ok().json(json!({
+                "restaurant": {
+                    "id": restaurant.id,
+                    "name": restaurant.name,
+                    "location": restaurant.location,
+                    "address": restaurant.address,
+                    "specialty": restaurant.specialty,
+                    "description": restaurant.description,
+                    "image": restaurant.image_url
+                },
+                "menu": menu
+            })))
+        }
+        Err(e) => {
+            println!("💥 [RESTAURANTS] Error consultando menú del restaurante {}: {}", restaurant_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "No se pudo obtener el menú",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
@@ -0,0 +1,557 @@
+// backend/src/handlers/ride.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::middleware::roles::{AssignedRide, RequireOwnerOf};
+use crate::models::*;
+use crate::money::Money;
+use crate::services::dispatch::{self, DispatchHub};
+use crate::services::notifications;
+use crate::services::rides::{self, RideError};
+
+// Cotización de prueba: mismo cálculo que create_ride, sin solicitar conductor
+pub async fn estimate_ride_fare(
+    pool: web::Data<PgPool>,
+    estimate_req: web::Json<FareEstimateRequest>,
+) -> Result<HttpResponse> {
+    if let Err(errors) = estimate_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let mut conn = match pool.acquire().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            println!("💥 [RIDES] Error obteniendo conexión: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error del servidor al cotizar el viaje",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    match rides::estimate_fare(&mut conn, &estimate_req.origin, &estimate_req.destination).await {
+        Ok(fare) => Ok(HttpResponse::Ok().json(json!({
+            "origin": estimate_req.origin,
+            "destination": estimate_req.destination,
+            "fare": fare
+        }))),
+        Err(e) => Ok(ride_error_response(e)),
+    }
+}
+
+pub async fn create_ride(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    ride_req: web::Json<CreateRideRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!(
+        "🚐 [RIDES] Usuario {} solicita viaje: {} → {}",
+        user.id, ride_req.origin.address, ride_req.destination.address
+    );
+
+    if let Err(errors) = ride_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RIDES] Error solicitando viaje: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al solicitar el viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let ride = match rides::request_ride(&mut tx, user.id, &ride_req).await {
+        Ok(ride) => ride,
+        Err(RideError::Database(e)) => return Ok(internal_error(e)),
+        Err(e) => return Ok(ride_error_response(e)),
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [RIDES] Viaje {} ({}) solicitado", ride.id, ride.reference);
+
+    // La oferta sale después del commit para que el conductor pueda aceptarla
+    let offered_to = match dispatch::offer_ride(pool.get_ref(), &hub, ride.id).await {
+        Ok(offered_to) => offered_to,
+        Err(e) => {
+            println!("💥 [RIDES] Error ofreciendo viaje {}: {}", ride.id, e);
+            0
+        }
+    };
+
+    Ok(HttpResponse::Created().json(json!({
+        "message": offer_message(offered_to),
+        "offered_to": offered_to,
+        "ride": ride
+    })))
+}
+
+pub async fn get_my_rides(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    match load_rides(pool.get_ref(), Some(user.id), None, None).await {
+        Ok(rides) => Ok(HttpResponse::Ok().json(json!({
+            "total": rides.len(),
+            "rides": rides
+        }))),
+        Err(e) => {
+            println!("💥 [RIDES] Error obteniendo viajes de usuario {}: {}", user.id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener viajes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn get_ride(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let ride_id = path.into_inner();
+
+    match load_rides(pool.get_ref(), Some(user.id), None, Some(ride_id)).await {
+        Ok(mut rides) if !rides.is_empty() => Ok(HttpResponse::Ok().json(json!({
+            "ride": rides.remove(0)
+        }))),
+        Ok(_) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Viaje no encontrado"
+        }))),
+        Err(e) => {
+            println!("💥 [RIDES] Error obteniendo viaje {}: {}", ride_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener el viaje",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Vuelve a ofrecer a los conductores conectados un viaje que sigue en 'requested'
+pub async fn retry_ride_assignment(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let ride_id = path.into_inner();
+    println!("🔁 [RIDES] Usuario {} busca conductor de nuevo para viaje {}", user.id, ride_id);
+
+    let status = match sqlx::query_scalar!(
+        "SELECT status FROM rides WHERE id = $1 AND user_id = $2",
+        ride_id,
+        user.id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(status)) => status,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Viaje no encontrado"
+            })));
+        }
+        Err(e) => {
+            println!("💥 [RIDES] Error consultando viaje {}: {}", ride_id, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al buscar conductor",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    if RideStatus::from_db(&status) != Some(RideStatus::Requested) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!("El viaje ya no está esperando conductor (estado '{}')", status)
+        })));
+    }
+
+    match dispatch::offer_ride(pool.get_ref(), &hub, ride_id).await {
+        Ok(offered_to) => Ok(HttpResponse::Ok().json(json!({
+            "message": offer_message(offered_to),
+            "offered_to": offered_to
+        }))),
+        Err(e) => {
+            println!("💥 [RIDES] Error ofreciendo viaje {}: {}", ride_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al buscar conductor",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// El pasajero solo puede cancelar antes de subir al vehículo
+pub async fn cancel_ride(
+    pool: web::Data<PgPool>,
+    hub: web::Data<DispatchHub>,
+    path: web::Path<i32>,
+    cancel_req: web::Json<CancelRideRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let ride_id = path.into_inner();
+    println!("🚫 [RIDES] Cancelando viaje {} para usuario {}", ride_id, user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RIDES] Error cancelando viaje {}: {}", ride_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cancelar el viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let ride = match sqlx::query!(
+        r#"
+        SELECT r.status, r.ride_reference, r.driver_id, d.user_id as "driver_user_id?"
+        FROM rides r
+        LEFT JOIN drivers d ON r.driver_id = d.id
+        WHERE r.id = $1 AND r.user_id = $2
+        FOR UPDATE OF r
+        "#,
+        ride_id,
+        user.id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(ride)) => ride,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Viaje no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = RideStatus::from_db(&ride.status).unwrap_or(RideStatus::Requested);
+    if !current.can_transition_to(RideStatus::Cancelled) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!("Un viaje en estado '{}' ya no se puede cancelar", current.as_str())
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE rides SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        ride_id,
+        cancel_req.reason
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = rides::record_status_change(&mut tx, ride_id, Some(current), RideStatus::Cancelled, Some(user.id)).await {
+        return Ok(internal_error(e));
+    }
+
+    if let Some(driver_id) = ride.driver_id {
+        if let Err(e) = rides::release_driver(&mut tx, driver_id, None).await {
+            return Ok(internal_error(e));
+        }
+    }
+
+    if let Some(driver_user_id) = ride.driver_user_id {
+        if let Err(e) = notifications::notify(
+            &mut tx,
+            driver_user_id,
+            "ride_cancelled",
+            "Viaje cancelado",
+            &format!("El pasajero canceló el viaje {}.", ride.ride_reference),
+            Some(json!({ "ride_id": ride_id })),
+        )
+        .await
+        {
+            return Ok(internal_error(e));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    // Si nadie lo había aceptado, se retira la oferta a los conductores conectados
+    if current == RideStatus::Requested {
+        hub.broadcast(&json!({ "type": "offer_withdrawn", "ride_id": ride_id }), None).await;
+    }
+
+    println!("✅ [RIDES] Viaje {} cancelado", ride_id);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Viaje cancelado exitosamente"
+    })))
+}
+
+// Viajes asignados a la cuenta del conductor
+pub async fn get_driver_rides(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    match load_rides(pool.get_ref(), None, Some(user.id), None).await {
+        Ok(rides) => Ok(HttpResponse::Ok().json(json!({
+            "total": rides.len(),
+            "rides": rides
+        }))),
+        Err(e) => {
+            println!("💥 [RIDES] Error obteniendo viajes del conductor {}: {}", user.id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener viajes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// El conductor asignado avanza el viaje: recoge al pasajero, lo completa o lo cancela
+pub async fn update_ride_status(
+    pool: web::Data<PgPool>,
+    status_req: web::Json<UpdateRideStatusRequest>,
+    driver: RequireOwnerOf<AssignedRide>,
+) -> Result<HttpResponse> {
+    let ride_id = driver.resource_id;
+    let target = status_req.status;
+    println!("🚐 [RIDES] Usuario {} cambiando viaje {} a '{}'", driver.user.id, ride_id, target.as_str());
+
+    // 'requested' y 'assigned' solo los pone el sistema al solicitar y asignar
+    if matches!(target, RideStatus::Requested | RideStatus::Assigned) {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": format!("El estado '{}' lo asigna el sistema", target.as_str())
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [RIDES] Error actualizando viaje {}: {}", ride_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al actualizar el estado del viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let ride = match sqlx::query!(
+        r#"
+        SELECT user_id, status, ride_reference, driver_id, destination_lat, destination_lng
+        FROM rides WHERE id = $1
+        FOR UPDATE
+        "#,
+        ride_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    {
+        Ok(Some(ride)) => ride,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Viaje no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let current = RideStatus::from_db(&ride.status).unwrap_or(RideStatus::Requested);
+    if !current.can_transition_to(target) {
+        return Ok(HttpResponse::Conflict().json(json!({
+            "error": format!(
+                "No se puede cambiar un viaje de '{}' a '{}'",
+                current.as_str(),
+                target.as_str()
+            )
+        })));
+    }
+
+    if let Err(e) = sqlx::query!(
+        r#"
+        UPDATE rides SET status = $2::varchar,
+            started_at = CASE WHEN $2::varchar = 'en_route' THEN CURRENT_TIMESTAMP ELSE started_at END,
+            completed_at = CASE WHEN $2::varchar = 'completed' THEN CURRENT_TIMESTAMP ELSE completed_at END,
+            cancelled_at = CASE WHEN $2::varchar = 'cancelled' THEN CURRENT_TIMESTAMP ELSE cancelled_at END,
+            cancellation_reason = CASE WHEN $2::varchar = 'cancelled' THEN $3 ELSE cancellation_reason END,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        ride_id,
+        target.as_str(),
+        status_req.cancellation_reason
+    )
+    .execute(&mut *tx)
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = rides::record_status_change(&mut tx, ride_id, Some(current), target, Some(driver.user.id)).await {
+        return Ok(internal_error(e));
+    }
+
+    // Al terminar el viaje el conductor queda libre donde dejó al pasajero
+    if let Some(driver_id) = ride.driver_id {
+        let released = match target {
+            RideStatus::Completed => {
+                rides::release_driver(&mut tx, driver_id, Some((ride.destination_lat, ride.destination_lng))).await
+            }
+            RideStatus::Cancelled => rides::release_driver(&mut tx, driver_id, None).await,
+            _ => Ok(()),
+        };
+        if let Err(e) = released {
+            return Ok(internal_error(e));
+        }
+    }
+
+    let (title, message) = match target {
+        RideStatus::EnRoute => ("Viaje en curso", format!("Tu viaje {} comenzó. ¡Buen viaje!", ride.ride_reference)),
+        RideStatus::Completed => ("Viaje completado", format!("Llegaste a tu destino. Viaje {} completado.", ride.ride_reference)),
+        RideStatus::Cancelled => ("Tu viaje fue cancelado", format!(
+            "El conductor canceló tu viaje {}. {}",
+            ride.ride_reference,
+            status_req.cancellation_reason.as_deref().unwrap_or("")
+        )),
+        RideStatus::Requested | RideStatus::Assigned => ("Viaje actualizado", format!("Tu viaje {} fue actualizado.", ride.ride_reference)),
+    };
+
+    if let Err(e) = notifications::notify(
+        &mut tx,
+        ride.user_id,
+        "ride_status",
+        title,
+        message.trim(),
+        Some(json!({ "ride_id": ride_id, "status": target.as_str() })),
+    )
+    .await
+    {
+        return Ok(internal_error(e));
+    }
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [RIDES] Viaje {} ahora está '{}'", ride_id, target.as_str());
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Estado del viaje actualizado",
+        "ride_id": ride_id,
+        "status": target
+    })))
+}
+
+fn ride_error_response(e: RideError) -> HttpResponse {
+    match e {
+        RideError::Database(e) => {
+            println!("💥 [RIDES] Error de base de datos: {}", e);
+            HttpResponse::InternalServerError().json(json!({
+                "error": "Error del servidor al procesar el viaje",
+                "details": e.to_string()
+            }))
+        }
+        e @ RideError::FareUnavailable(_) => {
+            println!("💥 [RIDES] {}", e);
+            HttpResponse::ServiceUnavailable().json(json!({ "error": e.to_string() }))
+        }
+        e @ (RideError::AlreadyTaken | RideError::DriverUnavailable) => {
+            HttpResponse::Conflict().json(json!({ "error": e.to_string() }))
+        }
+        e => HttpResponse::BadRequest().json(json!({ "error": e.to_string() })),
+    }
+}
+
+fn offer_message(offered_to: usize) -> &'static str {
+    if offered_to > 0 {
+        "Buscando conductor: tu solicitud se envió a los conductores cercanos"
+    } else {
+        "Solicitud registrada; aún no hay conductores disponibles cerca"
+    }
+}
+
+// Viajes del pasajero (`rider_id`) o del conductor con cuenta `driver_user_id`,
+// opcionalmente uno solo
+async fn load_rides(
+    pool: &PgPool,
+    rider_id: Option<i32>,
+    driver_user_id: Option<i32>,
+    ride_id: Option<i32>,
+) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT r.id, r.ride_reference, r.status, r.origin_address, r.origin_lat, r.origin_lng,
+               r.destination_address, r.destination_lat, r.destination_lng, r.distance_km,
+               r.duration_minutes, r.fare, r.currency, r.fare_breakdown, r.notes,
+               r.assigned_at, r.started_at, r.completed_at, r.cancelled_at, r.cancellation_reason,
+               r.created_at,
+               u.first_name || ' ' || u.last_name as "rider_name!",
+               d.id as "driver_id?", d.name as "driver_name?", d.phone as "driver_phone?",
+               d.vehicle as "driver_vehicle?", d.plate as "driver_plate?"
+        FROM rides r
+        JOIN users u ON r.user_id = u.id
+        LEFT JOIN drivers d ON r.driver_id = d.id
+        WHERE ($1::int4 IS NULL OR r.user_id = $1)
+          AND ($2::int4 IS NULL OR d.user_id = $2)
+          AND ($3::int4 IS NULL OR r.id = $3)
+        ORDER BY r.created_at DESC
+        "#,
+        rider_id,
+        driver_user_id,
+        ride_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| json!({
+            "id": r.id,
+            "reference": r.ride_reference,
+            "status": r.status,
+            "origin": { "address": r.origin_address, "lat": r.origin_lat, "lng": r.origin_lng },
+            "destination": { "address": r.destination_address, "lat": r.destination_lat, "lng": r.destination_lng },
+            "distance_km": r.distance_km,
+            "duration_minutes": r.duration_minutes,
+            "fare": Money::from_db(r.fare, &r.currency),
+            "fare_breakdown": r.fare_breakdown,
+            "notes": r.notes,
+            "rider_name": r.rider_name,
+            "driver": r.driver_id.map(|id| json!({
+                "id": id,
+                "name": r.driver_name,
+                "phone": r.driver_phone,
+                "vehicle": r.driver_vehicle,
+                "plate": r.driver_plate
+            })),
+            "assigned_at": r.assigned_at,
+            "started_at": r.started_at,
+            "completed_at": r.completed_at,
+            "cancelled_at": r.cancelled_at,
+            "cancellation_reason": r.cancellation_reason,
+            "created_at": r.created_at
+        }))
+        .collect())
+}
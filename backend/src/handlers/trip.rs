@@ -0,0 +1,349 @@
+// backend/src/handlers/trip.rs
+use actix_web::{web, HttpResponse, Result};
+use serde_json::json;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::models::*;
+use crate::money::Money;
+use crate::services::trips::{self, TripError};
+
+pub async fn create_trip(
+    pool: web::Data<PgPool>,
+    trip_req: web::Json<CreateTripRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!(
+        "🧳 [TRIPS] Usuario {} creando viaje: {} hoteles, {} experiencias, {} mesas, artesanías: {}",
+        user.id,
+        trip_req.hotels.len(),
+        trip_req.experiences.len(),
+        trip_req.restaurant_reservations.len(),
+        trip_req.products.is_some()
+    );
+
+    if let Err(errors) = trip_req.validate() {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Datos inválidos",
+            "details": errors
+        })));
+    }
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [TRIPS] Error creando viaje: {}", e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error del servidor al crear el viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let trip = match trips::book_trip(&mut tx, user.id, &trip_req).await {
+        Ok(trip) => trip,
+        Err(TripError::Database(e)) => return Ok(internal_error(e)),
+        Err(e) => {
+            // Nada queda apartado: la transacción se descarta completa
+            let _ = tx.rollback().await;
+            println!("❌ [TRIPS] Viaje rechazado: {}", e);
+            let response = if e.is_conflict() {
+                HttpResponse::Conflict().json(json!({ "error": e.to_string() }))
+            } else {
+                HttpResponse::BadRequest().json(json!({ "error": e.to_string() }))
+            };
+            return Ok(response);
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!(
+        "✅ [TRIPS] Viaje {} creado ({}) por {} {}",
+        trip.id,
+        trip.reference,
+        trip.total_price.amount(),
+        trip.total_price.currency().as_str()
+    );
+    Ok(HttpResponse::Created().json(json!({
+        "message": "Viaje creado. Completa el pago para confirmarlo.",
+        "trip": trip
+    })))
+}
+
+// Cancela un viaje sin pagar completo y libera todo lo que tenía apartado
+pub async fn cancel_trip(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    cancel_req: web::Json<UpdateBookingStatusRequest>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let trip_id = path.into_inner();
+    println!("🚫 [TRIPS] Cancelando viaje {} para usuario {}", trip_id, user.id);
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [TRIPS] Error cancelando viaje {}: {}", trip_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al cancelar el viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let reference = match trips::cancel_trip(&mut tx, user.id, trip_id, cancel_req.cancellation_reason.as_deref()).await {
+        Ok(reference) => reference,
+        Err(TripError::Database(e)) => return Ok(internal_error(e)),
+        Err(TripError::NotFound) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": TripError::NotFound.to_string()
+            })));
+        }
+        Err(e) => {
+            return Ok(HttpResponse::Conflict().json(json!({
+                "error": e.to_string()
+            })));
+        }
+    };
+
+    if let Err(e) = tx.commit().await {
+        return Ok(internal_error(e));
+    }
+
+    println!("✅ [TRIPS] Viaje {} ({}) cancelado", trip_id, reference);
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Viaje cancelado exitosamente",
+        "reference": reference
+    })))
+}
+
+pub async fn get_my_trips(
+    pool: web::Data<PgPool>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    println!("📋 [TRIPS] Obteniendo viajes para usuario {}", user.id);
+
+    let trips = sqlx::query!(
+        r#"
+        SELECT t.id, t.trip_reference, t.name, t.status, t.payment_status, t.total_price, t.currency,
+               t.created_at,
+               (SELECT COUNT(*) FROM bookings b WHERE b.trip_id = t.id) as "hotels!",
+               (SELECT COUNT(*) FROM experience_bookings e WHERE e.trip_id = t.id) as "experiences!",
+               (SELECT COUNT(*) FROM restaurant_reservations r WHERE r.trip_id = t.id) as "restaurant_reservations!",
+               EXISTS (SELECT 1 FROM product_orders o WHERE o.trip_id = t.id) as "has_products!"
+        FROM trips t
+        WHERE t.user_id = $1
+        ORDER BY t.created_at DESC
+        "#,
+        user.id
+    )
+    .fetch_all(pool.get_ref())
+    .await;
+
+    match trips {
+        Ok(list) => {
+            let trips_json: Vec<serde_json::Value> = list
+                .into_iter()
+                .map(|t| json!({
+                    "id": t.id,
+                    "reference": t.trip_reference,
+                    "name": t.name,
+                    "status": t.status,
+                    "payment_status": t.payment_status,
+                    "total_price": Money::from_db(t.total_price, &t.currency),
+                    "hotels": t.hotels,
+                    "experiences": t.experiences,
+                    "restaurant_reservations": t.restaurant_reservations,
+                    "has_products": t.has_products,
+                    "created_at": t.created_at
+                }))
+                .collect();
+
+            Ok(HttpResponse::Ok().json(json!({
+                "trips": trips_json,
+                "total": trips_json.len()
+            })))
+        }
+        Err(e) => {
+            println!("💥 [TRIPS] Error obteniendo viajes: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Error al obtener viajes",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+// Detalle del viaje con cada una de sus partes
+pub async fn get_trip(
+    pool: web::Data<PgPool>,
+    path: web::Path<i32>,
+    user: UserInfo,
+) -> Result<HttpResponse> {
+    let trip_id = path.into_inner();
+
+    let internal_error = |e: sqlx::Error| {
+        println!("💥 [TRIPS] Error obteniendo viaje {}: {}", trip_id, e);
+        HttpResponse::InternalServerError().json(json!({
+            "error": "Error al obtener el viaje",
+            "details": e.to_string()
+        }))
+    };
+
+    let trip = match sqlx::query!(
+        r#"
+        SELECT id, trip_reference, name, status, payment_status, total_price, currency, created_at
+        FROM trips WHERE id = $1 AND user_id = $2
+        "#,
+        trip_id,
+        user.id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(Some(trip)) => trip,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(json!({
+                "error": "Viaje no encontrado"
+            })));
+        }
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let hotels = match sqlx::query!(
+        r#"
+        SELECT b.id, b.booking_reference, b.check_in, b.check_out, b.guests, b.rooms,
+               b.total_price, b.currency, b.status,
+               h.name as hotel_name, h.location as hotel_location
+        FROM bookings b
+        JOIN hotels h ON b.hotel_id = h.id
+        WHERE b.trip_id = $1
+        ORDER BY b.check_in
+        "#,
+        trip_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|b| json!({
+                "id": b.id,
+                "reference": b.booking_reference,
+                "hotel_name": b.hotel_name,
+                "hotel_location": b.hotel_location,
+                "check_in": b.check_in,
+                "check_out": b.check_out,
+                "guests": b.guests,
+                "rooms": b.rooms,
+                "total_price": Money::from_db(b.total_price, &b.currency),
+                "status": b.status
+            }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let experiences = match sqlx::query!(
+        r#"
+        SELECT eb.id, eb.booking_reference, eb.participants, eb.total_price, eb.currency, eb.status,
+               e.name as experience_name, d.departure_date, d.departure_time
+        FROM experience_bookings eb
+        JOIN experiences e ON eb.experience_id = e.id
+        JOIN experience_departures d ON eb.departure_id = d.id
+        WHERE eb.trip_id = $1
+        ORDER BY d.departure_date, d.departure_time
+        "#,
+        trip_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|e| json!({
+                "id": e.id,
+                "reference": e.booking_reference,
+                "experience_name": e.experience_name,
+                "departure_date": e.departure_date,
+                "departure_time": e.departure_time,
+                "participants": e.participants,
+                "total_price": Money::from_db(e.total_price, &e.currency),
+                "status": e.status
+            }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let reservations = match sqlx::query!(
+        r#"
+        SELECT r.id, r.reservation_reference, r.reservation_date, r.slot_time, r.party_size, r.status,
+               rs.name as restaurant_name, rs.location as restaurant_location
+        FROM restaurant_reservations r
+        JOIN restaurants rs ON r.restaurant_id = rs.id
+        WHERE r.trip_id = $1
+        ORDER BY r.reservation_date, r.slot_time
+        "#,
+        trip_id
+    )
+    .fetch_all(pool.get_ref())
+    .await
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .map(|r| json!({
+                "id": r.id,
+                "reference": r.reservation_reference,
+                "restaurant_name": r.restaurant_name,
+                "restaurant_location": r.restaurant_location,
+                "reservation_date": r.reservation_date,
+                "slot_time": r.slot_time,
+                "party_size": r.party_size,
+                "status": r.status
+            }))
+            .collect::<Vec<_>>(),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    let product_order = match sqlx::query!(
+        r#"
+        SELECT id, order_reference, status, total_price, currency, shipping_address
+        FROM product_orders WHERE trip_id = $1
+        "#,
+        trip_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    {
+        Ok(order) => order.map(|o| json!({
+            "id": o.id,
+            "reference": o.order_reference,
+            "status": o.status,
+            "total_price": Money::from_db(o.total_price, &o.currency),
+            "shipping_address": o.shipping_address
+        })),
+        Err(e) => return Ok(internal_error(e)),
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "trip": {
+            "id": trip.id,
+            "reference": trip.trip_reference,
+            "name": trip.name,
+            "status": trip.status,
+            "payment_status": trip.payment_status,
+            "total_price": Money::from_db(trip.total_price, &trip.currency),
+            "created_at": trip.created_at,
+            "hotels": hotels,
+            "experiences": experiences,
+            "restaurant_reservations": reservations,
+            "product_order": product_order
+        }
+    })))
+}
@@ -1,24 +1,62 @@
 // backend/src/main.rs
 use actix_web::{web, App, HttpServer, HttpResponse};
 use actix_cors::Cors;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::postgres::PgPoolOptions;
 use dotenv::dotenv;
 use std::env;
 
 // Módulos
 mod models;
+mod money;
 mod handlers {
+    pub mod admin;
+    pub mod artisan;
     pub mod auth;
     pub mod booking;
+    pub mod cart;
+    pub mod driver;
+    pub mod experience;
+    pub mod guest;
     pub mod hotel;
+    pub mod notification;
+    pub mod order;
+    pub mod owner;
+    pub mod payment;
+    pub mod product;
+    pub mod quote;
+    pub mod reservation;
+    pub mod restaurant;
+    pub mod ride;
+    pub mod trip;
 }
 mod middleware {
     pub mod auth;
+    pub mod roles;
+}
+mod services {
+    pub mod addons;
+    pub mod bookings;
+    pub mod dispatch;
+    pub mod experiences;
+    pub mod guests;
+    pub mod inventory;
+    pub mod mailer;
+    pub mod notifications;
+    pub mod orders;
+    pub mod password;
+    pub mod payments;
+    pub mod pricing;
+    pub mod products;
+    pub mod references;
+    pub mod refunds;
+    pub mod rides;
+    pub mod session;
+    pub mod tables;
+    pub mod trips;
 }
 
 // Imports
-use models::*;
-use handlers::{auth, booking};
+use handlers::{admin, artisan, auth, booking, cart, driver, experience, guest, hotel, notification, order, owner, payment, product, quote, reservation, restaurant, ride, trip};
 
 // Ruta de salud
 async fn health() -> HttpResponse {
@@ -57,125 +95,6 @@ async fn get_hoteles() -> HttpResponse {
     HttpResponse::Ok().json(hoteles)
 }
 
-async fn get_restaurantes() -> HttpResponse {
-    let restaurantes = vec![
-        serde_json::json!({
-            "id": 1,
-            "name": "Corazón de Jade",
-            "specialty": "Cocina de Autor",
-            "location": "Campeche",
-            "image": "https://images.unsplash.com/photo-1552566626-52f8b828add9?q=80&w=2070&auto=format&fit=crop"
-        }),
-        serde_json::json!({
-            "id": 2,
-            "name": "La Ceiba",
-            "specialty": "Mariscos Frescos",
-            "location": "Chetumal",
-            "image": "https://images.unsplash.com/photo-1517248135467-4c7edcad34c4?q=80&w=2070&auto=format&fit=crop"
-        }),
-        serde_json::json!({
-            "id": 3,
-            "name": "El Fogón del Jaguar",
-            "specialty": "Carnes y Tradición",
-            "location": "Valladolid",
-            "image": "https://images.unsplash.com/photo-1414235077428-338989a2e8c0?q=80&w=2070&auto=format&fit=crop"
-        })
-    ];
-    HttpResponse::Ok().json(restaurantes)
-}
-
-async fn get_experiencias() -> HttpResponse {
-    let experiencias = vec![
-        serde_json::json!({
-            "id": 1,
-            "type": "tour",
-            "name": "Tour a Chichén Itzá",
-            "price": 1200,
-            "image": "https://images.unsplash.com/photo-1528181304800-259b08848526?q=80&w=2070&auto=format&fit=crop"
-        }),
-        serde_json::json!({
-            "id": 2,
-            "type": "caballos",
-            "name": "Paseo a Caballo",
-            "price": 850,
-            "image": "https://images.unsplash.com/photo-1599059813005-3603a5603703?q=80&w=1974&auto=format&fit=crop"
-        }),
-        serde_json::json!({
-            "id": 3,
-            "type": "cenote",
-            "name": "Nado en Cenote Sagrado",
-            "price": 450,
-            "image": "https://images.unsplash.com/photo-1627907222543-4111d6946196?q=80&w=1965&auto=format&fit=crop"
-        })
-    ];
-    HttpResponse::Ok().json(experiencias)
-}
-
-async fn get_productos() -> HttpResponse {
-    let productos = vec![
-        serde_json::json!({
-            "id": 1,
-            "name": "Huipil Ceremonial",
-            "artisan": "Elena Poot",
-            "price": 1800,
-            "category": "textil",
-            "img": "https://images.unsplash.com/photo-1620921207299-b37993505b12?q=80&w=1964&auto=format&fit=crop",
-            "desc": "Tejido a mano con técnicas ancestrales, este huipil representa la cosmovisión maya en cada uno de sus hilos."
-        }),
-        serde_json::json!({
-            "id": 2,
-            "name": "Vasija de Sac-bé",
-            "artisan": "Mateo Cruz",
-            "price": 950,
-            "category": "ceramica",
-            "img": "https://images.unsplash.com/photo-1578899223131-a7isea110323?q=80&w=1887&auto=format&fit=crop",
-            "desc": "Cerámica de alta temperatura pintada a mano con pigmentos naturales, ideal para decoración."
-        })
-    ];
-    HttpResponse::Ok().json(productos)
-}
-
-async fn get_producto_by_id(path: web::Path<u32>) -> HttpResponse {
-    let product_id = path.into_inner();
-    let productos = vec![
-        serde_json::json!({
-            "id": 1,
-            "name": "Huipil Ceremonial",
-            "artisan": "Elena Poot",
-            "price": 1800,
-            "category": "textil",
-            "img": "https://images.unsplash.com/photo-1620921207299-b37993505b12?q=80&w=1964&auto=format&fit=crop",
-            "desc": "Tejido a mano con técnicas ancestrales, este huipil representa la cosmovisión maya en cada uno de sus hilos."
-        }),
-        serde_json::json!({
-            "id": 2,
-            "name": "Vasija de Sac-bé",
-            "artisan": "Mateo Cruz",
-            "price": 950,
-            "category": "ceramica",
-            "img": "https://images.unsplash.com/photo-1578899223131-a7isea110323?q=80&w=1887&auto=format&fit=crop",
-            "desc": "Cerámica de alta temperatura pintada a mano con pigmentos naturales, ideal para decoración."
-        }),
-        serde_json::json!({
-            "id": 3,
-            "name": "Aretes de Filigrana",
-            "artisan": "Isabel Chi",
-            "price": 1200,
-            "category": "joyeria",
-            "img": "https://images.unsplash.com/photo-1611652032935-a6ce59b4c03d?q=80&w=1887&auto=format&fit=crop",
-            "desc": "Elegantes aretes de plata trabajados con la delicada técnica de filigrana."
-        })
-    ];
-
-    if let Some(producto) = productos.into_iter().find(|p| p["id"] == product_id) {
-        HttpResponse::Ok().json(producto)
-    } else {
-        HttpResponse::NotFound().json(serde_json::json!({
-            "error": "Producto no encontrado"
-        }))
-    }
-}
-
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Cargar variables de entorno
@@ -211,13 +130,106 @@ async fn main() -> std::io::Result<()> {
     println!("   - GET  /health");
     println!("   - POST /api/auth/register");
     println!("   - POST /api/auth/login");
+    println!("   - POST /api/auth/refresh");
+    println!("   - POST /api/auth/logout (protegido)");
+    println!("   - POST /api/auth/logout-all (protegido)");
     println!("   - GET  /api/auth/me (protegido)");
     println!("   - POST /api/bookings (protegido)");
     println!("   - GET  /api/bookings (protegido)");
+    println!("   - GET  /api/bookings/ref/{{reference}} (protegido)");
+    println!("   - POST /api/bookings/lookup (referencia + email)");
+    println!("   - PUT  /api/bookings/{{id}} (protegido)");
+    println!("   - GET  /api/bookings/{{id}}/versions (protegido)");
+    println!("   - POST /api/bookings/{{id}}/payment-intent (protegido)");
+    println!("   - GET  /api/bookings/{{id}}/refund-preview (protegido)");
+    println!("   - GET  /api/hotels");
+    println!("   - POST /api/restaurants/{{id}}/reservations (protegido)");
+    println!("   - GET  /api/reservations (protegido)");
+    println!("   - PATCH /api/reservations/{{id}}/cancel (protegido)");
+    println!("   - POST /api/restaurants/{{id}}/orders (protegido)");
+    println!("   - GET  /api/orders (protegido)");
+    println!("   - PATCH /api/orders/{{id}}/cancel (protegido)");
+    println!("   - POST /api/experiences/{{id}}/bookings (protegido)");
+    println!("   - GET  /api/experience-bookings (protegido)");
+    println!("   - PATCH /api/experience-bookings/{{id}}/cancel (protegido)");
+    println!("   - POST /api/product-orders (protegido)");
+    println!("   - GET  /api/product-orders (protegido)");
+    println!("   - POST /api/trips (protegido)");
+    println!("   - GET  /api/trips (protegido)");
+    println!("   - GET  /api/trips/{{id}} (protegido)");
+    println!("   - POST /api/trips/{{id}}/payment-intent (protegido)");
+    println!("   - PATCH /api/trips/{{id}}/cancel (protegido)");
+    println!("   - POST /api/rides (protegido)");
+    println!("   - GET  /api/rides (protegido)");
+    println!("   - GET  /api/rides/{{id}} (protegido)");
+    println!("   - POST /api/rides/{{id}}/assign (protegido)");
+    println!("   - PATCH /api/rides/{{id}}/cancel (protegido)");
+    println!("   - GET|DELETE /api/cart (protegido)");
+    println!("   - POST /api/cart/items (protegido)");
+    println!("   - PUT|DELETE /api/cart/items/{{product_id}} (protegido)");
+    println!("   - POST /api/cart/checkout (protegido)");
+    println!("   - GET  /api/owner/hotels (socio)");
+    println!("   - POST /api/owner/hotels (socio)");
+    println!("   - PUT  /api/owner/hotels/{{id}} (dueño)");
+    println!("   - GET  /api/owner/hotels/{{id}}/bookings (dueño)");
+    println!("   - PATCH /api/owner/hotels/{{id}}/bookings/{{booking_id}}/status (dueño)");
+    println!("   - GET  /api/owner/hotels/{{id}}/bookings/{{booking_id}}/history (dueño)");
+    println!("   - GET|POST /api/owner/hotels/{{id}}/addons (dueño)");
+    println!("   - PUT  /api/owner/hotels/{{id}}/cancellation-policy (dueño)");
+    println!("   - PUT|DELETE /api/owner/hotels/{{id}}/addons/{{addon_id}} (dueño)");
+    println!("   - GET|POST /api/owner/restaurants (socio)");
+    println!("   - PUT  /api/owner/restaurants/{{id}} (dueño)");
+    println!("   - GET|POST /api/owner/restaurants/{{id}}/menu (dueño)");
+    println!("   - PUT|DELETE /api/owner/restaurants/{{id}}/menu/{{item_id}} (dueño)");
+    println!("   - PUT  /api/owner/restaurants/{{id}}/reservation-settings (dueño)");
+    println!("   - GET  /api/owner/restaurants/{{id}}/reservations (dueño)");
+    println!("   - GET  /api/owner/restaurants/{{id}}/orders (dueño)");
+    println!("   - PATCH /api/owner/restaurants/{{id}}/orders/{{order_id}}/status (dueño)");
+    println!("   - POST /api/artisan/profile (protegido)");
+    println!("   - GET|POST /api/artisan/products (artesano)");
+    println!("   - PUT|DELETE /api/artisan/products/{{id}} (dueño)");
+    println!("   - POST /api/driver/profile (protegido)");
+    println!("   - GET  /api/driver/profile (conductor)");
+    println!("   - PATCH /api/driver/status (conductor)");
+    println!("   - GET  /api/driver/ws?token= (conductor, WebSocket)");
+    println!("   - POST /api/driver/rides/{{id}}/accept (conductor)");
+    println!("   - GET  /api/driver/rides (protegido)");
+    println!("   - PATCH /api/driver/rides/{{id}}/status (conductor asignado)");
+    println!("   - GET  /api/admin/hotels/pending (admin)");
+    println!("   - POST /api/admin/hotels/{{id}}/approve|reject|suspend (admin)");
+    println!("   - GET  /api/admin/restaurants/pending (admin)");
+    println!("   - POST /api/admin/restaurants/{{id}}/approve|reject|suspend (admin)");
+    println!("   - GET  /api/admin/drivers/pending (admin)");
+    println!("   - POST /api/admin/drivers/{{id}}/approve|reject|suspend (admin)");
+    println!("   - GET  /api/notifications (protegido)");
     println!("   - GET  /api/hoteles");
+    println!("   - GET  /api/hotels/availability");
+    println!("   - GET  /api/hotels/{{id}}/addons");
+    println!("   - GET  /api/hotels/{{id}}/cancellation-policy");
+    println!("   - POST /api/quotes");
     println!("   - GET  /api/restaurantes");
+    println!("   - GET  /api/restaurantes/{{id}}");
+    println!("   - GET  /api/restaurants/{{id}}/availability");
     println!("   - GET  /api/experiencias");
+    println!("   - GET  /api/experiencias/{{id}}");
     println!("   - GET  /api/productos");
+    println!("   - GET  /api/productos/{{id}}");
+    println!("   - POST /api/rides/estimate");
+    println!("   - POST /api/payments/webhooks/{{provider}} (firma del proveedor)");
+    println!("   - POST /api/guest/bookings (sin cuenta)");
+    println!("   - POST /api/guest/bookings/resend-link (referencia + email)");
+    println!("   - GET  /api/guest/bookings/{{token}} (enlace mágico)");
+    println!("   - POST /api/guest/bookings/{{token}}/payment-intent (enlace mágico)");
+    println!("   - GET  /api/guest/bookings/{{token}}/refund-preview (enlace mágico)");
+    println!("   - PATCH /api/guest/bookings/{{token}}/cancel (enlace mágico)");
+    println!("   - POST /api/guest/bookings/claim (protegido)");
+
+    // Conexiones WebSocket de conductores, compartidas por todos los workers
+    let dispatch_hub = web::Data::new(services::dispatch::DispatchHub::default());
+    // Proveedores de pagos configurados (PAYMENT_PROVIDER elige el de los cobros nuevos)
+    let payment_providers = web::Data::new(services::payments::PaymentProviders::from_env());
+    // Entrega en segundo plano de los correos encolados (enlaces de huéspedes, avisos)
+    services::mailer::spawn_outbox_worker(pool.clone(), std::sync::Arc::new(services::mailer::LogMailer));
 
     HttpServer::new(move || {
         let cors = Cors::default()
@@ -229,24 +241,142 @@ async fn main() -> std::io::Result<()> {
 
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(dispatch_hub.clone())
+            .app_data(payment_providers.clone())
             .wrap(cors)
-            .wrap(middleware::Logger::default())
             .wrap(actix_web::middleware::Logger::default())
             // Rutas públicas
             .route("/health", web::get().to(health))
             .route("/api/hoteles", web::get().to(get_hoteles))
-            .route("/api/restaurantes", web::get().to(get_restaurantes))
-            .route("/api/experiencias", web::get().to(get_experiencias))
-            .route("/api/productos", web::get().to(get_productos))
-            .route("/api/productos/{id}", web::get().to(get_producto_by_id))
+            .route("/api/hotels/availability", web::get().to(hotel::search_availability))
+            .route("/api/hotels/{id}/addons", web::get().to(hotel::get_hotel_addons))
+            .route("/api/hotels/{id}/cancellation-policy", web::get().to(hotel::get_cancellation_policy))
+            .route("/api/quotes", web::post().to(quote::create_quote))
+            .route("/api/restaurantes", web::get().to(restaurant::get_restaurants))
+            .route("/api/restaurantes/{id}", web::get().to(restaurant::get_restaurant))
+            .route("/api/restaurants/{id}/availability", web::get().to(reservation::get_restaurant_availability))
+            .route("/api/experiencias", web::get().to(experience::get_experiences))
+            .route("/api/experiencias/{id}", web::get().to(experience::get_experience))
+            .route("/api/productos", web::get().to(product::get_products))
+            .route("/api/productos/{id}", web::get().to(product::get_product))
+            .route("/api/rides/estimate", web::post().to(ride::estimate_ride_fare))
+            // Webhooks de pagos (autenticados con la firma del proveedor)
+            .route("/api/payments/webhooks/{provider}", web::post().to(payment::payment_webhook))
+            // Reservas sin cuenta (autenticadas con el enlace mágico enviado por email)
+            .route("/api/guest/bookings", web::post().to(guest::create_guest_booking))
+            .route("/api/guest/bookings/resend-link", web::post().to(guest::resend_guest_link))
+            .route("/api/guest/bookings/claim", web::post().to(guest::claim_guest_bookings))
+            .route("/api/guest/bookings/{token}", web::get().to(guest::get_guest_booking))
+            .route("/api/guest/bookings/{token}/payment-intent", web::post().to(guest::create_guest_payment_intent))
+            .route("/api/guest/bookings/{token}/refund-preview", web::get().to(guest::preview_guest_refund))
+            .route("/api/guest/bookings/{token}/cancel", web::patch().to(guest::cancel_guest_booking))
             // Rutas de autenticación
             .route("/api/auth/register", web::post().to(auth::register))
             .route("/api/auth/login", web::post().to(auth::login))
+            .route("/api/auth/refresh", web::post().to(auth::refresh))
+            .route("/api/auth/logout", web::post().to(auth::logout))
+            .route("/api/auth/logout-all", web::post().to(auth::logout_all))
             .route("/api/auth/me", web::get().to(auth::me))
             // Rutas protegidas de reservas
             .route("/api/bookings", web::post().to(booking::create_booking))
             .route("/api/bookings", web::get().to(booking::get_my_bookings))
+            .route("/api/bookings/ref/{reference}", web::get().to(booking::get_booking_by_reference))
+            .route("/api/bookings/lookup", web::post().to(booking::lookup_booking))
+            .route("/api/bookings/{id}", web::put().to(booking::modify_booking))
+            .route("/api/bookings/{id}/versions", web::get().to(booking::get_booking_versions))
             .route("/api/bookings/{id}/cancel", web::patch().to(booking::cancel_booking))
+            .route("/api/bookings/{id}/payment-intent", web::post().to(payment::create_payment_intent))
+            .route("/api/bookings/{id}/refund-preview", web::get().to(booking::preview_refund))
+            // Hoteles
+            .route("/api/hotels", web::get().to(hotel::get_hotels))
+            // Reservaciones de mesa
+            .route("/api/restaurants/{id}/reservations", web::post().to(reservation::create_reservation))
+            .route("/api/reservations", web::get().to(reservation::get_my_reservations))
+            .route("/api/reservations/{id}/cancel", web::patch().to(reservation::cancel_reservation))
+            // Pedidos de restaurante
+            .route("/api/restaurants/{id}/orders", web::post().to(order::create_order))
+            .route("/api/orders", web::get().to(order::get_my_orders))
+            .route("/api/orders/{id}/cancel", web::patch().to(order::cancel_order))
+            // Reservas de experiencias
+            .route("/api/experiences/{id}/bookings", web::post().to(experience::create_experience_booking))
+            .route("/api/experience-bookings", web::get().to(experience::get_my_experience_bookings))
+            .route("/api/experience-bookings/{id}/cancel", web::patch().to(experience::cancel_experience_booking))
+            // Compras de artesanías
+            .route("/api/product-orders", web::post().to(product::create_product_order))
+            .route("/api/product-orders", web::get().to(product::get_my_product_orders))
+            // Viajes (checkout unificado)
+            .route("/api/trips", web::post().to(trip::create_trip))
+            .route("/api/trips", web::get().to(trip::get_my_trips))
+            .route("/api/trips/{id}", web::get().to(trip::get_trip))
+            .route("/api/trips/{id}/payment-intent", web::post().to(payment::create_trip_payment_intent))
+            .route("/api/trips/{id}/cancel", web::patch().to(trip::cancel_trip))
+            // Transporte certificado
+            .route("/api/rides", web::post().to(ride::create_ride))
+            .route("/api/rides", web::get().to(ride::get_my_rides))
+            .route("/api/rides/{id}", web::get().to(ride::get_ride))
+            .route("/api/rides/{id}/assign", web::post().to(ride::retry_ride_assignment))
+            .route("/api/rides/{id}/cancel", web::patch().to(ride::cancel_ride))
+            // Carrito de compras
+            .route("/api/cart", web::get().to(cart::get_cart))
+            .route("/api/cart", web::delete().to(cart::clear_cart))
+            .route("/api/cart/items", web::post().to(cart::add_cart_item))
+            .route("/api/cart/items/{product_id}", web::put().to(cart::update_cart_item))
+            .route("/api/cart/items/{product_id}", web::delete().to(cart::remove_cart_item))
+            .route("/api/cart/checkout", web::post().to(cart::checkout_cart))
+            // Panel de socios (hoteleros)
+            .route("/api/owner/hotels", web::get().to(owner::get_my_hotels))
+            .route("/api/owner/hotels", web::post().to(owner::create_hotel))
+            .route("/api/owner/hotels/{id}", web::put().to(owner::update_hotel))
+            .route("/api/owner/hotels/{id}/bookings", web::get().to(booking::get_hotel_bookings))
+            .route("/api/owner/hotels/{id}/bookings/{booking_id}/status", web::patch().to(booking::update_booking_status))
+            .route("/api/owner/hotels/{id}/bookings/{booking_id}/history", web::get().to(booking::get_booking_history))
+            .route("/api/owner/hotels/{id}/addons", web::get().to(owner::get_hotel_addons))
+            .route("/api/owner/hotels/{id}/addons", web::post().to(owner::create_addon))
+            .route("/api/owner/hotels/{id}/addons/{addon_id}", web::put().to(owner::update_addon))
+            .route("/api/owner/hotels/{id}/addons/{addon_id}", web::delete().to(owner::delete_addon))
+            .route("/api/owner/hotels/{id}/cancellation-policy", web::put().to(owner::update_cancellation_policy))
+            .route("/api/owner/restaurants", web::get().to(owner::get_my_restaurants))
+            .route("/api/owner/restaurants", web::post().to(owner::create_restaurant))
+            .route("/api/owner/restaurants/{id}", web::put().to(owner::update_restaurant))
+            .route("/api/owner/restaurants/{id}/menu", web::get().to(owner::get_restaurant_menu))
+            .route("/api/owner/restaurants/{id}/menu", web::post().to(owner::create_menu_item))
+            .route("/api/owner/restaurants/{id}/menu/{item_id}", web::put().to(owner::update_menu_item))
+            .route("/api/owner/restaurants/{id}/menu/{item_id}", web::delete().to(owner::delete_menu_item))
+            .route("/api/owner/restaurants/{id}/reservation-settings", web::put().to(owner::update_reservation_settings))
+            .route("/api/owner/restaurants/{id}/reservations", web::get().to(reservation::get_restaurant_reservations))
+            .route("/api/owner/restaurants/{id}/orders", web::get().to(order::get_restaurant_orders))
+            .route("/api/owner/restaurants/{id}/orders/{order_id}/status", web::patch().to(order::update_order_status))
+            // Panel de artesanos
+            .route("/api/artisan/profile", web::post().to(artisan::create_artisan_profile))
+            .route("/api/artisan/products", web::get().to(artisan::get_my_products))
+            .route("/api/artisan/products", web::post().to(artisan::create_product))
+            .route("/api/artisan/products/{id}", web::put().to(artisan::update_product))
+            .route("/api/artisan/products/{id}", web::delete().to(artisan::delete_product))
+            // Panel de conductores
+            .route("/api/driver/profile", web::post().to(driver::register_driver))
+            .route("/api/driver/profile", web::get().to(driver::get_driver_profile))
+            .route("/api/driver/status", web::patch().to(driver::update_driver_status))
+            .route("/api/driver/ws", web::get().to(driver::driver_socket))
+            .route("/api/driver/rides/{id}/accept", web::post().to(driver::accept_ride))
+            .route("/api/driver/rides", web::get().to(ride::get_driver_rides))
+            .route("/api/driver/rides/{id}/status", web::patch().to(ride::update_ride_status))
+            // Moderación (administradores)
+            .route("/api/admin/hotels/pending", web::get().to(admin::get_pending_hotels))
+            .route("/api/admin/hotels/{id}/approve", web::post().to(admin::approve_hotel))
+            .route("/api/admin/hotels/{id}/reject", web::post().to(admin::reject_hotel))
+            .route("/api/admin/hotels/{id}/suspend", web::post().to(admin::suspend_hotel))
+            .route("/api/admin/hotels/{id}/audit", web::get().to(admin::get_hotel_audit))
+            .route("/api/admin/restaurants/pending", web::get().to(admin::get_pending_restaurants))
+            .route("/api/admin/restaurants/{id}/approve", web::post().to(admin::approve_restaurant))
+            .route("/api/admin/restaurants/{id}/reject", web::post().to(admin::reject_restaurant))
+            .route("/api/admin/restaurants/{id}/suspend", web::post().to(admin::suspend_restaurant))
+            .route("/api/admin/drivers/pending", web::get().to(admin::get_pending_drivers))
+            .route("/api/admin/drivers/{id}/approve", web::post().to(admin::approve_driver))
+            .route("/api/admin/drivers/{id}/reject", web::post().to(admin::reject_driver))
+            .route("/api/admin/drivers/{id}/suspend", web::post().to(admin::suspend_driver))
+            // Notificaciones
+            .route("/api/notifications", web::get().to(notification::get_my_notifications))
+            .route("/api/notifications/{id}/read", web::patch().to(notification::mark_notification_read))
     })
     .bind(format!("{}:{}", server_host, server_port))?
     .run()
@@ -1,15 +1,24 @@
 // backend/src/middleware/auth.rs
-use actix_web::{dev, Error, FromRequest, HttpRequest};
-use actix_web::error::ErrorUnauthorized;
-use futures_util::future::{err, ok, Ready};
+use actix_web::{dev, web, Error, FromRequest, HttpRequest};
+use actix_web::error::{ErrorInternalServerError, ErrorUnauthorized};
+use futures_util::future::{err, LocalBoxFuture};
+use futures_util::FutureExt;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
 use serde_json::json;
+use sqlx::PgPool;
 
 use crate::models::{JwtClaims, UserInfo};
+use crate::services::session;
 
-impl FromRequest for UserInfo {
+// Usuario autenticado junto con la sesión (dispositivo) a la que pertenece su token
+pub struct AuthSession {
+    pub user: UserInfo,
+    pub session_id: uuid::Uuid,
+}
+
+impl FromRequest for AuthSession {
     type Error = Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
         println!("🔐 [MIDDLEWARE] Verificando autenticación...");
@@ -25,37 +34,31 @@ impl FromRequest for UserInfo {
                     println!("❌ [MIDDLEWARE] Header Authorization con formato inválido");
                     return err(ErrorUnauthorized(json!({
                         "error": "Header Authorization inválido"
-                    })));
+                    }))).boxed_local();
                 }
             },
             None => {
                 println!("❌ [MIDDLEWARE] Header Authorization no encontrado");
                 return err(ErrorUnauthorized(json!({
                     "error": "Token de autenticación requerido"
-                })));
+                }))).boxed_local();
             }
         };
 
         // Extraer el token (formato: "Bearer TOKEN")
-        let token = if auth_header.starts_with("Bearer ") {
-            &auth_header[7..] // Remover "Bearer "
+        let token = if let Some(token) = auth_header.strip_prefix("Bearer ") {
+            token
         } else {
             println!("❌ [MIDDLEWARE] Formato de token inválido (debe ser 'Bearer TOKEN')");
             return err(ErrorUnauthorized(json!({
                 "error": "Formato de token inválido. Use: Bearer <token>"
-            })));
+            }))).boxed_local();
         };
 
         println!("🎫 [MIDDLEWARE] Token extraído: {}...", &token[..std::cmp::min(20, token.len())]);
 
         // Obtener la clave secreta del JWT
-        let secret = match std::env::var("JWT_SECRET_KEY") {
-            Ok(secret) => secret,
-            Err(_) => {
-                println!("⚠️ [MIDDLEWARE] JWT_SECRET_KEY no encontrado en .env, usando valor por defecto");
-                "esta_es_una_clave_diferente_para_la_copia".to_string()
-            }
-        };
+        let secret = session::jwt_secret();
 
         // Configurar validación del JWT
         let mut validation = Validation::new(Algorithm::HS256);
@@ -69,23 +72,45 @@ impl FromRequest for UserInfo {
         ) {
             Ok(token_data) => {
                 println!("✅ [MIDDLEWARE] Token válido para usuario ID: {}", token_data.claims.user_id);
-                
-                let user_info = UserInfo {
-                    id: token_data.claims.user_id,
-                    first_name: "".to_string(), // Estos campos se pueden obtener de la BD si es necesario
-                    last_name: "".to_string(),
-                    email: token_data.claims.email,
-                    user_type: token_data.claims.user_type,
+
+                let pool = match req.app_data::<web::Data<PgPool>>() {
+                    Some(pool) => pool.clone(),
+                    None => {
+                        return err(ErrorInternalServerError(json!({
+                            "error": "Base de datos no configurada"
+                        }))).boxed_local();
+                    }
                 };
-                
-                ok(user_info)
+                let session_id = token_data.claims.session_id;
+                let user_id = token_data.claims.user_id;
+
+                // La sesión debe seguir activa; los datos del usuario (y su rol) se leen
+                // de la BD para que un cambio de rol aplique sin esperar a que expire el token
+                async move {
+                    match session::active_session_user(pool.get_ref(), session_id, user_id).await {
+                        Ok(Some(user)) => Ok(AuthSession { user, session_id }),
+                        Ok(None) => {
+                            println!("❌ [MIDDLEWARE] Sesión {} revocada o expirada", session_id);
+                            Err(ErrorUnauthorized(json!({
+                                "error": "La sesión fue cerrada. Por favor, inicia sesión nuevamente."
+                            })))
+                        }
+                        Err(e) => {
+                            println!("💥 [MIDDLEWARE] Error verificando sesión: {}", e);
+                            Err(ErrorInternalServerError(json!({
+                                "error": "Error verificando la sesión"
+                            })))
+                        }
+                    }
+                }
+                .boxed_local()
             }
             Err(e) => {
                 println!("❌ [MIDDLEWARE] Token inválido: {:?}", e);
                 
                 let error_message = match e.kind() {
                     jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
-                        "Token expirado. Por favor, inicia sesión nuevamente."
+                        "Token expirado. Renueva la sesión en /api/auth/refresh o inicia sesión nuevamente."
                     }
                     jsonwebtoken::errors::ErrorKind::InvalidToken => {
                         "Token inválido"
@@ -99,8 +124,56 @@ impl FromRequest for UserInfo {
                 err(ErrorUnauthorized(json!({
                     "error": error_message,
                     "details": e.to_string()
-                })))
+                }))).boxed_local()
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl FromRequest for UserInfo {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        AuthSession::from_request(req, payload)
+            .map(|auth| auth.map(|auth| auth.user))
+            .boxed_local()
+    }
+}
+
+// Autentica un token recibido fuera del header Authorization. Se usa al abrir
+// el WebSocket de despacho, porque el navegador no permite enviar headers ahí.
+pub async fn authenticate_token(pool: &PgPool, token: &str) -> Result<UserInfo, Error> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.validate_exp = true;
+
+    let claims = decode::<JwtClaims>(
+        token,
+        &DecodingKey::from_secret(session::jwt_secret().as_ref()),
+        &validation,
+    )
+    .map_err(|e| {
+        println!("❌ [MIDDLEWARE] Token inválido: {:?}", e);
+        ErrorUnauthorized(json!({
+            "error": "Token de autenticación inválido",
+            "details": e.to_string()
+        }))
+    })?
+    .claims;
+
+    match session::active_session_user(pool, claims.session_id, claims.user_id).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => {
+            println!("❌ [MIDDLEWARE] Sesión {} revocada o expirada", claims.session_id);
+            Err(ErrorUnauthorized(json!({
+                "error": "La sesión fue cerrada. Por favor, inicia sesión nuevamente."
+            })))
+        }
+        Err(e) => {
+            println!("💥 [MIDDLEWARE] Error verificando sesión: {}", e);
+            Err(ErrorInternalServerError(json!({
+                "error": "Error verificando la sesión"
+            })))
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// backend/src/middleware/roles.rs
+use std::marker::PhantomData;
+
+use actix_web::error::InternalError;
+use actix_web::{dev, web, Error, FromRequest, HttpRequest, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::models::{Role, UserInfo};
+
+// Respuesta 403 común para todas las verificaciones de permisos
+pub fn forbidden(message: &str) -> Error {
+    InternalError::from_response(
+        message.to_string(),
+        HttpResponse::Forbidden().json(json!({ "error": message })),
+    )
+    .into()
+}
+
+fn not_found(message: &str) -> Error {
+    InternalError::from_response(
+        message.to_string(),
+        HttpResponse::NotFound().json(json!({ "error": message })),
+    )
+    .into()
+}
+
+fn server_error(message: &str) -> Error {
+    InternalError::from_response(
+        message.to_string(),
+        HttpResponse::InternalServerError().json(json!({ "error": message })),
+    )
+    .into()
+}
+
+// === GUARDIAS POR ROL ===
+
+// Conjunto de roles que cumple un requisito
+pub trait RoleRequirement {
+    const ALLOWED: &'static [Role];
+    const DENIED_MESSAGE: &'static str;
+}
+
+pub struct Admin;
+
+impl RoleRequirement for Admin {
+    const ALLOWED: &'static [Role] = &[Role::Admin];
+    const DENIED_MESSAGE: &'static str = "Esta acción requiere permisos de administrador";
+}
+
+// Dueños de negocios; los administradores también cumplen este requisito
+pub struct Owner;
+
+impl RoleRequirement for Owner {
+    const ALLOWED: &'static [Role] = &[Role::Owner, Role::Admin];
+    const DENIED_MESSAGE: &'static str = "Esta acción requiere una cuenta de socio";
+}
+
+// Vendedores de artesanías; los administradores también cumplen este requisito
+pub struct Artisan;
+
+impl RoleRequirement for Artisan {
+    const ALLOWED: &'static [Role] = &[Role::Artisan, Role::Admin];
+    const DENIED_MESSAGE: &'static str = "Esta acción requiere una cuenta de artesano";
+}
+
+// Conductores de transporte; los administradores también cumplen este requisito
+pub struct Driver;
+
+impl RoleRequirement for Driver {
+    const ALLOWED: &'static [Role] = &[Role::Driver, Role::Admin];
+    const DENIED_MESSAGE: &'static str = "Esta acción requiere una cuenta de conductor";
+}
+
+// Usuario autenticado con alguno de los roles de `R`. Uso: `user: RequireRole<Admin>`
+pub struct RequireRole<R: RoleRequirement> {
+    pub user: UserInfo,
+    _role: PhantomData<R>,
+}
+
+impl<R: RoleRequirement + 'static> FromRequest for RequireRole<R> {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let user_future = UserInfo::from_request(req, payload);
+
+        Box::pin(async move {
+            let user = user_future.await?;
+
+            if !R::ALLOWED.contains(&user.role()) {
+                println!("⛔ [ROLES] Usuario {} ({}) sin permiso", user.id, user.role().as_str());
+                return Err(forbidden(R::DENIED_MESSAGE));
+            }
+
+            Ok(RequireRole { user, _role: PhantomData })
+        })
+    }
+}
+
+// === GUARDIAS POR PROPIEDAD ===
+
+// Recurso con dueño identificado por el parámetro `{id}` de la ruta
+pub trait OwnedResource {
+    // Nombre con artículo para los mensajes de error ("el hotel")
+    const NAME: &'static str;
+    // Consulta que recibe el id del recurso y devuelve su owner_id
+    const OWNER_QUERY: &'static str;
+    const PATH_PARAM: &'static str = "id";
+}
+
+pub struct Hotel;
+
+impl OwnedResource for Hotel {
+    const NAME: &'static str = "el hotel";
+    const OWNER_QUERY: &'static str = "SELECT owner_id FROM hotels WHERE id = $1";
+}
+
+pub struct Restaurant;
+
+impl OwnedResource for Restaurant {
+    const NAME: &'static str = "el restaurante";
+    const OWNER_QUERY: &'static str = "SELECT owner_id FROM restaurants WHERE id = $1";
+}
+
+// El dueño de un producto es la cuenta ligada al perfil del artesano
+pub struct Product;
+
+impl OwnedResource for Product {
+    const NAME: &'static str = "el producto";
+    const OWNER_QUERY: &'static str =
+        "SELECT a.user_id FROM products p JOIN artisans a ON p.artisan_id = a.id WHERE p.id = $1";
+}
+
+// Un viaje lo administra la cuenta del conductor asignado; sin conductor nadie
+// más que un administrador puede cambiarlo
+pub struct AssignedRide;
+
+impl OwnedResource for AssignedRide {
+    const NAME: &'static str = "el viaje";
+    const OWNER_QUERY: &'static str =
+        "SELECT d.user_id FROM rides r LEFT JOIN drivers d ON r.driver_id = d.id WHERE r.id = $1";
+}
+
+// Usuario dueño del recurso `T` de la ruta (o administrador). Uso: `owner: RequireOwnerOf<Hotel>`
+pub struct RequireOwnerOf<T: OwnedResource> {
+    pub user: UserInfo,
+    pub resource_id: i32,
+    _resource: PhantomData<T>,
+}
+
+impl<T: OwnedResource + 'static> FromRequest for RequireOwnerOf<T> {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut dev::Payload) -> Self::Future {
+        let user_future = UserInfo::from_request(req, payload);
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+        let resource_id = req
+            .match_info()
+            .get(T::PATH_PARAM)
+            .and_then(|id| id.parse::<i32>().ok());
+
+        Box::pin(async move {
+            let user = user_future.await?;
+
+            let resource_id = resource_id
+                .ok_or_else(|| not_found(&format!("Identificador inválido para {}", T::NAME)))?;
+            let pool = pool.ok_or_else(|| server_error("Base de datos no configurada"))?;
+
+            let owner_id = sqlx::query_scalar::<_, Option<i32>>(T::OWNER_QUERY)
+                .bind(resource_id)
+                .fetch_optional(pool.get_ref())
+                .await
+                .map_err(|e| {
+                    println!("💥 [ROLES] Error verificando dueño ({} {}): {}", T::NAME, resource_id, e);
+                    server_error("Error al verificar permisos")
+                })?
+                .ok_or_else(|| not_found(&format!("No se encontró {}", T::NAME)))?;
+
+            // Los administradores pueden acceder a todo
+            if user.role() != Role::Admin && owner_id != Some(user.id) {
+                println!("⛔ [ROLES] Usuario {} no es dueño ({} {})", user.id, T::NAME, resource_id);
+                return Err(forbidden(&format!("No tienes permiso para administrar {}", T::NAME)));
+            }
+
+            Ok(RequireOwnerOf { user, resource_id, _resource: PhantomData })
+        })
+    }
+}
@@ -27,6 +27,47 @@ pub struct UserInfo {
     pub user_type: String,
 }
 
+impl UserInfo {
+    // Rol tipado del usuario; un valor desconocido se trata como cliente
+    pub fn role(&self) -> Role {
+        Role::from_db(&self.user_type).unwrap_or(Role::Customer)
+    }
+}
+
+// Roles válidos para users.user_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Customer,
+    Owner,
+    Admin,
+    Artisan,
+    Driver,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Customer => "customer",
+            Role::Owner => "owner",
+            Role::Admin => "admin",
+            Role::Artisan => "artisan",
+            Role::Driver => "driver",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Role> {
+        match value {
+            "customer" => Some(Role::Customer),
+            "owner" => Some(Role::Owner),
+            "admin" => Some(Role::Admin),
+            "artisan" => Some(Role::Artisan),
+            "driver" => Some(Role::Driver),
+            _ => None,
+        }
+    }
+}
+
 // Request para registro
 #[derive(Debug, Deserialize, Validate)]
 pub struct RegisterRequest {
@@ -41,6 +82,10 @@ pub struct RegisterRequest {
     
     #[validate(length(min = 6, message = "La contraseña debe tener al menos 6 caracteres"))]
     pub password: String,
+
+    // Token del enlace mágico de una reserva sin cuenta; prueba que el email es
+    // del huésped y permite pasar sus reservas a la cuenta nueva
+    pub guest_token: Option<String>,
 }
 
 // Request para login
@@ -53,11 +98,19 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+// Request para renovar el access token
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
 // Respuesta de autenticación
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub message: String,
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // Segundos de vida del access token
     pub user: UserInfo,
 }
 
@@ -67,11 +120,729 @@ pub struct JwtClaims {
     pub user_id: i32,
     pub email: String,
     pub user_type: String,
+    pub session_id: uuid::Uuid, // Sesión en user_sessions; permite revocar el token
     pub exp: usize, // Tiempo de expiración
 }
 
+// === MODELOS PARA HOTELES (PANEL DE SOCIOS) ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateHotelRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    #[validate(length(min = 2, max = 255, message = "La ubicación debe tener entre 2 y 255 caracteres"))]
+    pub location: String,
+
+    pub address: Option<String>,
+
+    // Precio por noche; se valida en el handler porque BigDecimal no soporta `range`
+    pub price: bigdecimal::BigDecimal,
+
+    #[validate(range(min = 1, max = 1000, message = "El número de habitaciones debe estar entre 1 y 1000"))]
+    pub total_rooms: i32,
+
+    #[validate(range(min = 1, max = 20, message = "La capacidad por habitación debe estar entre 1 y 20"))]
+    pub max_guests_per_room: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateHotelRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 2, max = 255, message = "La ubicación debe tener entre 2 y 255 caracteres"))]
+    pub location: Option<String>,
+
+    pub address: Option<String>,
+    pub price: Option<bigdecimal::BigDecimal>,
+
+    #[validate(range(min = 0, max = 1000, message = "El número de habitaciones debe estar entre 0 y 1000"))]
+    pub total_rooms: Option<i32>,
+
+    #[validate(range(min = 1, max = 20, message = "La capacidad por habitación debe estar entre 1 y 20"))]
+    pub max_guests_per_room: Option<i32>,
+}
+
+// Estados de moderación de un hotel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HotelStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Suspended,
+}
+
+impl HotelStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HotelStatus::Pending => "pending",
+            HotelStatus::Approved => "approved",
+            HotelStatus::Rejected => "rejected",
+            HotelStatus::Suspended => "suspended",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<HotelStatus> {
+        match value {
+            "pending" => Some(HotelStatus::Pending),
+            "approved" => Some(HotelStatus::Approved),
+            "rejected" => Some(HotelStatus::Rejected),
+            "suspended" => Some(HotelStatus::Suspended),
+            _ => None,
+        }
+    }
+
+    // Transiciones que puede hacer un administrador
+    pub fn can_transition_to(&self, next: HotelStatus) -> bool {
+        matches!(
+            (self, next),
+            (HotelStatus::Pending, HotelStatus::Approved)
+                | (HotelStatus::Pending, HotelStatus::Rejected)
+                | (HotelStatus::Rejected, HotelStatus::Approved)
+                | (HotelStatus::Approved, HotelStatus::Suspended)
+                | (HotelStatus::Suspended, HotelStatus::Approved)
+        )
+    }
+}
+
+// Los restaurantes pasan por la misma moderación que los hoteles
+pub type RestaurantStatus = HotelStatus;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ModerationRequest {
+    #[validate(length(min = 3, max = 1000, message = "El motivo debe tener entre 3 y 1000 caracteres"))]
+    pub reason: String,
+}
+
+// === MODELOS PARA RESTAURANTES ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRestaurantRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    #[validate(length(min = 2, max = 255, message = "La ubicación debe tener entre 2 y 255 caracteres"))]
+    pub location: String,
+
+    pub address: Option<String>,
+
+    #[validate(length(max = 255, message = "La especialidad es demasiado larga"))]
+    pub specialty: Option<String>,
+
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateRestaurantRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    #[validate(length(min = 2, max = 255, message = "La ubicación debe tener entre 2 y 255 caracteres"))]
+    pub location: Option<String>,
+
+    pub address: Option<String>,
+
+    #[validate(length(max = 255, message = "La especialidad es demasiado larga"))]
+    pub specialty: Option<String>,
+
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateMenuItemRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    pub description: Option<String>,
+
+    // Se valida en el handler porque BigDecimal no soporta `range`
+    pub price: bigdecimal::BigDecimal,
+
+    #[validate(length(min = 2, max = 100, message = "La categoría debe tener entre 2 y 100 caracteres"))]
+    pub category: Option<String>,
+
+    pub is_available: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateMenuItemRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    pub description: Option<String>,
+    pub price: Option<bigdecimal::BigDecimal>,
+
+    #[validate(length(min = 2, max = 100, message = "La categoría debe tener entre 2 y 100 caracteres"))]
+    pub category: Option<String>,
+
+    pub is_available: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+// === MODELOS PARA RESERVACIONES DE MESA ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateReservationRequest {
+    // Se puede reservar para hoy; el horario se valida contra la hora actual al reservar
+    #[validate(custom = "validate_not_past_date")]
+    pub reservation_date: chrono::NaiveDate,
+
+    // Inicio del turno, p. ej. "19:30:00"
+    pub slot_time: chrono::NaiveTime,
+
+    #[validate(range(min = 1, max = 50, message = "El número de comensales debe estar entre 1 y 50"))]
+    pub party_size: i32,
+
+    #[validate(length(max = 1000, message = "Las peticiones especiales son demasiado largas"))]
+    pub special_requests: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SlotAvailabilityQuery {
+    pub date: chrono::NaiveDate,
+
+    #[validate(range(min = 1, max = 50, message = "El número de comensales debe estar entre 1 y 50"))]
+    pub party_size: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestaurantReservationsQuery {
+    pub date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpeningHoursInput {
+    // ISO: 1 = lunes ... 7 = domingo
+    pub weekday: i16,
+    pub opens_at: chrono::NaiveTime,
+    pub closes_at: chrono::NaiveTime,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReservationSettingsRequest {
+    #[validate(range(min = 15, max = 240, message = "La duración del turno debe estar entre 15 y 240 minutos"))]
+    pub slot_minutes: Option<i32>,
+
+    #[validate(range(min = 1, max = 500, message = "Los lugares por turno deben estar entre 1 y 500"))]
+    pub covers_per_slot: Option<i32>,
+
+    #[validate(range(min = 1, max = 50, message = "El mínimo de comensales debe estar entre 1 y 50"))]
+    pub min_party_size: Option<i32>,
+
+    #[validate(range(min = 1, max = 50, message = "El máximo de comensales debe estar entre 1 y 50"))]
+    pub max_party_size: Option<i32>,
+
+    // Si se envía, reemplaza todo el horario semanal
+    pub opening_hours: Option<Vec<OpeningHoursInput>>,
+}
+
+// === MODELOS PARA PEDIDOS DE RESTAURANTE ===
+
+// Ciclo de vida de un pedido
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Placed,
+    Accepted,
+    Preparing,
+    Ready,
+    Delivered,
+    Cancelled,
+}
+
+impl OrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::Placed => "placed",
+            OrderStatus::Accepted => "accepted",
+            OrderStatus::Preparing => "preparing",
+            OrderStatus::Ready => "ready",
+            OrderStatus::Delivered => "delivered",
+            OrderStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<OrderStatus> {
+        match value {
+            "placed" => Some(OrderStatus::Placed),
+            "accepted" => Some(OrderStatus::Accepted),
+            "preparing" => Some(OrderStatus::Preparing),
+            "ready" => Some(OrderStatus::Ready),
+            "delivered" => Some(OrderStatus::Delivered),
+            "cancelled" => Some(OrderStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    // Transiciones que puede hacer el restaurante; el cliente solo cancela pedidos 'placed'
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        matches!(
+            (self, next),
+            (OrderStatus::Placed, OrderStatus::Accepted)
+                | (OrderStatus::Placed, OrderStatus::Cancelled)
+                | (OrderStatus::Accepted, OrderStatus::Preparing)
+                | (OrderStatus::Accepted, OrderStatus::Cancelled)
+                | (OrderStatus::Preparing, OrderStatus::Ready)
+                | (OrderStatus::Ready, OrderStatus::Delivered)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItemSelection {
+    pub menu_item_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrderRequest {
+    // Solo IDs y cantidades; el precio se calcula con el menú del restaurante
+    #[validate(length(min = 1, max = 50, message = "El pedido debe tener entre 1 y 50 platillos"))]
+    pub items: Vec<OrderItemSelection>,
+
+    #[validate(length(max = 1000, message = "Las notas son demasiado largas"))]
+    pub notes: Option<String>,
+
+    // Pedido para la mesa de una reservación propia
+    pub reservation_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateOrderStatusRequest {
+    pub status: OrderStatus,
+    pub cancellation_reason: Option<String>,
+}
+
+// === MODELOS PARA EXPERIENCIAS ===
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FareSelection {
+    pub fare_type: String,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateExperienceBookingRequest {
+    pub departure_id: i32,
+
+    // Solo tipos de tarifa y cantidades; el precio se calcula en el servidor
+    #[validate(length(min = 1, max = 10, message = "Debes indicar entre 1 y 10 tipos de tarifa"))]
+    pub fares: Vec<FareSelection>,
+
+    #[validate(length(max = 1000, message = "Las peticiones especiales son demasiado largas"))]
+    pub special_requests: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExperienceDeparturesQuery {
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+}
+
+// === MODELOS PARA ARTESANÍAS ===
+
+// Categorías del catálogo de artesanías
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductCategory {
+    Textil,
+    Ceramica,
+    Joyeria,
+}
+
+impl ProductCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductCategory::Textil => "textil",
+            ProductCategory::Ceramica => "ceramica",
+            ProductCategory::Joyeria => "joyeria",
+        }
+    }
+}
+
+// Filtros y paginación del catálogo (GET /api/productos)
+#[derive(Debug, Deserialize, Validate)]
+pub struct ProductsQuery {
+    pub category: Option<ProductCategory>,
+    // Busca en el nombre de la pieza y del artesano
+    pub q: Option<String>,
+    pub artisan_id: Option<i32>,
+
+    #[validate(range(min = 1, message = "La página debe ser mayor a cero"))]
+    pub page: Option<i64>,
+
+    #[validate(range(min = 1, max = 100, message = "Se permiten entre 1 y 100 productos por página"))]
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ArtisanProfileRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    #[validate(length(max = 255, message = "La comunidad es demasiado larga"))]
+    pub community: Option<String>,
+
+    pub bio: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateProductRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    pub description: Option<String>,
+    pub category: ProductCategory,
+
+    // Se valida en el handler porque BigDecimal no soporta `range`
+    pub price: bigdecimal::BigDecimal,
+
+    #[validate(range(min = 0, max = 10000, message = "El inventario debe estar entre 0 y 10000 piezas"))]
+    pub stock: i32,
+
+    #[validate(length(max = 10, message = "Se permiten hasta 10 imágenes"))]
+    pub images: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateProductRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    pub description: Option<String>,
+    pub category: Option<ProductCategory>,
+    pub price: Option<bigdecimal::BigDecimal>,
+
+    #[validate(range(min = 0, max = 10000, message = "El inventario debe estar entre 0 y 10000 piezas"))]
+    pub stock: Option<i32>,
+
+    #[validate(length(max = 10, message = "Se permiten hasta 10 imágenes"))]
+    pub images: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductSelection {
+    pub product_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateProductOrderRequest {
+    // Solo IDs y cantidades; el precio y el inventario se verifican en el servidor
+    #[validate(length(min = 1, max = 50, message = "La compra debe tener entre 1 y 50 productos"))]
+    pub items: Vec<ProductSelection>,
+
+    #[validate(length(max = 1000, message = "La dirección de envío es demasiado larga"))]
+    pub shipping_address: Option<String>,
+}
+
+// === MODELOS PARA EL CARRITO ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddCartItemRequest {
+    pub product_id: i32,
+
+    // Si no se indica se agrega una pieza
+    #[validate(range(min = 1, max = 20, message = "La cantidad debe estar entre 1 y 20"))]
+    pub quantity: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateCartItemRequest {
+    #[validate(range(min = 1, max = 20, message = "La cantidad debe estar entre 1 y 20"))]
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CartCheckoutRequest {
+    #[validate(length(min = 10, max = 1000, message = "La dirección de envío debe tener entre 10 y 1000 caracteres"))]
+    pub shipping_address: String,
+}
+
+// === MODELOS PARA VIAJES (CHECKOUT UNIFICADO) ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TripExperienceItem {
+    pub experience_id: i32,
+
+    #[serde(flatten)]
+    #[validate]
+    pub booking: CreateExperienceBookingRequest,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TripReservationItem {
+    pub restaurant_id: i32,
+
+    #[serde(flatten)]
+    #[validate]
+    pub reservation: CreateReservationRequest,
+}
+
+// Todo lo que se reserva junto; se confirma completo o no se confirma nada
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateTripRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre del viaje debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    #[validate]
+    pub hotels: Vec<CreateBookingRequest>,
+
+    #[serde(default)]
+    #[validate]
+    pub experiences: Vec<TripExperienceItem>,
+
+    #[serde(default)]
+    #[validate]
+    pub restaurant_reservations: Vec<TripReservationItem>,
+
+    #[validate]
+    pub products: Option<CreateProductOrderRequest>,
+}
+
+// === MODELOS PARA TRANSPORTE ===
+
+// Ciclo de vida de un viaje. 'en_route' significa que el pasajero ya va a bordo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RideStatus {
+    Requested,
+    Assigned,
+    EnRoute,
+    Completed,
+    Cancelled,
+}
+
+impl RideStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RideStatus::Requested => "requested",
+            RideStatus::Assigned => "assigned",
+            RideStatus::EnRoute => "en_route",
+            RideStatus::Completed => "completed",
+            RideStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<RideStatus> {
+        match value {
+            "requested" => Some(RideStatus::Requested),
+            "assigned" => Some(RideStatus::Assigned),
+            "en_route" => Some(RideStatus::EnRoute),
+            "completed" => Some(RideStatus::Completed),
+            "cancelled" => Some(RideStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    // Un viaje solo se cancela antes de que el pasajero suba
+    pub fn can_transition_to(&self, next: RideStatus) -> bool {
+        matches!(
+            (self, next),
+            (RideStatus::Requested, RideStatus::Assigned)
+                | (RideStatus::Requested, RideStatus::Cancelled)
+                | (RideStatus::Assigned, RideStatus::EnRoute)
+                | (RideStatus::Assigned, RideStatus::Cancelled)
+                | (RideStatus::EnRoute, RideStatus::Completed)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct RidePlace {
+    #[validate(length(min = 2, max = 255, message = "La dirección debe tener entre 2 y 255 caracteres"))]
+    pub address: String,
+
+    #[validate(range(min = -90.0, max = 90.0, message = "La latitud debe estar entre -90 y 90"))]
+    pub lat: f64,
+
+    #[validate(range(min = -180.0, max = 180.0, message = "La longitud debe estar entre -180 y 180"))]
+    pub lng: f64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct FareEstimateRequest {
+    #[validate]
+    pub origin: RidePlace,
+
+    #[validate]
+    pub destination: RidePlace,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRideRequest {
+    // Solo el trayecto; la tarifa se calcula en el servidor
+    #[validate]
+    pub origin: RidePlace,
+
+    #[validate]
+    pub destination: RidePlace,
+
+    #[validate(length(max = 500, message = "Las notas son demasiado largas"))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRideStatusRequest {
+    pub status: RideStatus,
+    pub cancellation_reason: Option<String>,
+}
+
+// Cancelación del viaje por parte del pasajero
+#[derive(Debug, Deserialize)]
+pub struct CancelRideRequest {
+    pub reason: Option<String>,
+}
+
+// Estado de la certificación de un conductor; mismas transiciones que la moderación de hoteles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificationStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Suspended,
+}
+
+impl CertificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CertificationStatus::Pending => "pending",
+            CertificationStatus::Approved => "approved",
+            CertificationStatus::Rejected => "rejected",
+            CertificationStatus::Suspended => "suspended",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<CertificationStatus> {
+        match value {
+            "pending" => Some(CertificationStatus::Pending),
+            "approved" => Some(CertificationStatus::Approved),
+            "rejected" => Some(CertificationStatus::Rejected),
+            "suspended" => Some(CertificationStatus::Suspended),
+            _ => None,
+        }
+    }
+
+    // Transiciones que puede hacer un administrador
+    pub fn can_transition_to(&self, next: CertificationStatus) -> bool {
+        matches!(
+            (self, next),
+            (CertificationStatus::Pending, CertificationStatus::Approved)
+                | (CertificationStatus::Pending, CertificationStatus::Rejected)
+                | (CertificationStatus::Rejected, CertificationStatus::Approved)
+                | (CertificationStatus::Approved, CertificationStatus::Suspended)
+                | (CertificationStatus::Suspended, CertificationStatus::Approved)
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DriverProfileRequest {
+    #[validate(length(min = 8, max = 20, message = "El teléfono debe tener entre 8 y 20 caracteres"))]
+    pub phone: String,
+
+    #[validate(length(min = 3, max = 255, message = "Describe el vehículo (marca, modelo, año y color)"))]
+    pub vehicle: String,
+
+    #[validate(length(min = 5, max = 20, message = "La placa debe tener entre 5 y 20 caracteres"))]
+    pub plate: String,
+
+    #[validate(length(min = 5, max = 50, message = "El número de licencia debe tener entre 5 y 50 caracteres"))]
+    pub license_number: String,
+
+    #[validate(custom = "validate_future_date")]
+    pub license_expires_on: chrono::NaiveDate,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct DriverStatusRequest {
+    pub online: bool,
+
+    #[validate(range(min = -90.0, max = 90.0, message = "La latitud debe estar entre -90 y 90"))]
+    pub lat: Option<f64>,
+
+    #[validate(range(min = -180.0, max = 180.0, message = "La longitud debe estar entre -180 y 180"))]
+    pub lng: Option<f64>,
+}
+
+// El navegador no permite enviar el header Authorization al abrir un WebSocket
+#[derive(Debug, Deserialize)]
+pub struct SocketAuthQuery {
+    pub token: String,
+}
+
+// Mensajes que el conductor envía por el WebSocket de despacho
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DriverSocketMessage {
+    Location { lat: f64, lng: f64 },
+    Accept { ride_id: i32 },
+}
+
 // === MODELOS PARA BOOKING ===
 
+// Ciclo de vida de una reserva de hotel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    Pending,
+    Confirmed,
+    CheckedIn,
+    CheckedOut,
+    Cancelled,
+    NoShow,
+    Rejected,
+}
+
+impl BookingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingStatus::Pending => "pending",
+            BookingStatus::Confirmed => "confirmed",
+            BookingStatus::CheckedIn => "checked_in",
+            BookingStatus::CheckedOut => "checked_out",
+            BookingStatus::Cancelled => "cancelled",
+            BookingStatus::NoShow => "no_show",
+            BookingStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<BookingStatus> {
+        match value {
+            "pending" => Some(BookingStatus::Pending),
+            "confirmed" => Some(BookingStatus::Confirmed),
+            "checked_in" => Some(BookingStatus::CheckedIn),
+            "checked_out" => Some(BookingStatus::CheckedOut),
+            "cancelled" => Some(BookingStatus::Cancelled),
+            "no_show" => Some(BookingStatus::NoShow),
+            "rejected" => Some(BookingStatus::Rejected),
+            _ => None,
+        }
+    }
+
+    // El huésped solo cancela; el resto de las transiciones las hace el hotel
+    pub fn can_transition_to(&self, next: BookingStatus) -> bool {
+        matches!(
+            (self, next),
+            (BookingStatus::Pending, BookingStatus::Confirmed)
+                | (BookingStatus::Pending, BookingStatus::Rejected)
+                | (BookingStatus::Pending, BookingStatus::Cancelled)
+                | (BookingStatus::Confirmed, BookingStatus::CheckedIn)
+                | (BookingStatus::Confirmed, BookingStatus::NoShow)
+                | (BookingStatus::Confirmed, BookingStatus::Cancelled)
+                | (BookingStatus::CheckedIn, BookingStatus::CheckedOut)
+        )
+    }
+}
+
 #[derive(Debug, Deserialize, Validate)]
 pub struct CreateBookingRequest {
     pub hotel_id: i32,
@@ -89,12 +860,198 @@ pub struct CreateBookingRequest {
     pub rooms: i32,
     
     pub special_requests: Option<String>,
-    pub addon_services: Option<serde_json::Value>,
+
+    // Solo IDs y cantidades; el precio se calcula con el catálogo del hotel
+    pub addon_services: Option<Vec<AddonSelection>>,
+
+    // Si no hay cupo, anotar al usuario en la lista de espera en lugar de rechazar
+    pub join_waitlist: Option<bool>,
+}
+
+// Reserva sin cuenta: contacto del huésped más los datos de la reserva
+#[derive(Debug, Deserialize, Validate)]
+pub struct GuestBookingRequest {
+    #[validate(length(min = 2, max = 50, message = "El nombre debe tener entre 2 y 50 caracteres"))]
+    pub first_name: String,
+
+    #[validate(length(min = 2, max = 50, message = "El apellido debe tener entre 2 y 50 caracteres"))]
+    pub last_name: String,
+
+    #[validate(email(message = "Debe ser un email válido"))]
+    pub email: String,
+
+    #[validate(length(min = 7, max = 30, message = "El teléfono debe tener entre 7 y 30 caracteres"))]
+    pub phone: String,
+
+    #[serde(flatten)]
+    #[validate]
+    pub booking: CreateBookingRequest,
+}
+
+// Pasa a la cuenta del usuario las reservas hechas como huésped
+#[derive(Debug, Deserialize)]
+pub struct ClaimGuestBookingsRequest {
+    pub token: String,
+}
+
+// Cambio de fechas, habitaciones o huéspedes de una reserva (PUT /api/bookings/{id})
+#[derive(Debug, Deserialize, Validate)]
+pub struct ModifyBookingRequest {
+    #[validate(custom = "validate_future_date")]
+    pub check_in: chrono::NaiveDate,
+
+    pub check_out: chrono::NaiveDate,
+
+    #[validate(range(min = 1, max = 10, message = "El número de huéspedes debe estar entre 1 y 10"))]
+    pub guests: i32,
+
+    #[validate(range(min = 1, max = 5, message = "El número de habitaciones debe estar entre 1 y 5"))]
+    pub rooms: i32,
+
+    // Si no se envía, se conservan los servicios adicionales actuales
+    pub addon_services: Option<Vec<AddonSelection>>,
+
+    pub special_requests: Option<String>,
+}
+
+// Consulta de una reserva sin cuenta: referencia más el email del titular
+#[derive(Debug, Deserialize, Validate)]
+pub struct BookingLookupRequest {
+    #[validate(length(min = 4, max = 50, message = "La referencia no es válida"))]
+    pub reference: String,
+
+    #[validate(email(message = "Debe ser un email válido"))]
+    pub email: String,
+}
+
+// Parámetros de búsqueda de disponibilidad de hoteles
+#[derive(Debug, Deserialize, Validate)]
+pub struct AvailabilityQuery {
+    // Misma regla que al reservar, para no cotizar estancias que no se pueden reservar
+    #[validate(custom = "validate_future_date")]
+    pub check_in: chrono::NaiveDate,
+    pub check_out: chrono::NaiveDate,
+
+    #[validate(range(min = 1, max = 10, message = "El número de huéspedes debe estar entre 1 y 10"))]
+    pub guests: Option<i32>,
+
+    #[validate(range(min = 1, max = 5, message = "El número de habitaciones debe estar entre 1 y 5"))]
+    pub rooms: Option<i32>,
+
+    pub location: Option<String>,
+}
+
+// Cotización sin reservar (POST /api/quotes)
+#[derive(Debug, Deserialize, Validate)]
+pub struct QuoteRequest {
+    pub hotel_id: i32,
+    pub check_in: chrono::NaiveDate,
+    pub check_out: chrono::NaiveDate,
+
+    #[validate(range(min = 1, max = 10, message = "El número de huéspedes debe estar entre 1 y 10"))]
+    pub guests: i32,
+
+    #[validate(range(min = 1, max = 5, message = "El número de habitaciones debe estar entre 1 y 5"))]
+    pub rooms: i32,
+
+    pub addon_services: Option<Vec<AddonSelection>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddonSelection {
+    pub addon_id: i32,
+    pub quantity: i32,
+}
+
+// Política de cancelación de un hotel. Cancelar con al menos
+// free_cancellation_days de anticipación siempre es gratis; después:
+// 'flexible' retiene todo, 'partial' retiene el porcentaje de penalización.
+// 'non_refundable' nunca reembolsa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancellationPolicyKind {
+    Flexible,
+    Partial,
+    NonRefundable,
+}
+
+impl CancellationPolicyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CancellationPolicyKind::Flexible => "flexible",
+            CancellationPolicyKind::Partial => "partial",
+            CancellationPolicyKind::NonRefundable => "non_refundable",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<CancellationPolicyKind> {
+        match value {
+            "flexible" => Some(CancellationPolicyKind::Flexible),
+            "partial" => Some(CancellationPolicyKind::Partial),
+            "non_refundable" => Some(CancellationPolicyKind::NonRefundable),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CancellationPolicyRequest {
+    pub policy: CancellationPolicyKind,
+
+    #[validate(range(min = 0, max = 365, message = "Los días de cancelación gratuita deben estar entre 0 y 365"))]
+    pub free_cancellation_days: i32,
+
+    // Solo aplica a 'partial'
+    #[validate(range(min = 0, max = 100, message = "La penalización debe estar entre 0 y 100%"))]
+    pub penalty_percent: Option<i32>,
+}
+
+// === MODELOS PARA SERVICIOS ADICIONALES ===
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAddonRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: String,
+
+    pub description: Option<String>,
+
+    #[validate(length(max = 16, message = "El ícono es demasiado largo"))]
+    pub icon: Option<String>,
+
+    pub price: bigdecimal::BigDecimal,
+
+    // per_stay (por defecto), per_night o per_guest
+    pub pricing_unit: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "La cantidad máxima debe estar entre 1 y 100"))]
+    pub max_quantity: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateAddonRequest {
+    #[validate(length(min = 2, max = 255, message = "El nombre debe tener entre 2 y 255 caracteres"))]
+    pub name: Option<String>,
+
+    pub description: Option<String>,
+
+    #[validate(length(max = 16, message = "El ícono es demasiado largo"))]
+    pub icon: Option<String>,
+
+    pub price: Option<bigdecimal::BigDecimal>,
+    pub pricing_unit: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "La cantidad máxima debe estar entre 1 y 100"))]
+    pub max_quantity: Option<i32>,
+
+    pub is_active: Option<bool>,
 }
 
+pub const ADDON_PRICING_UNITS: [&str; 3] = ["per_stay", "per_night", "per_guest"];
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateBookingStatusRequest {
-    pub status: Option<String>,
+    // Lo usa el hotel para mover la reserva; al cancelar se ignora
+    pub status: Option<BookingStatus>,
     pub cancellation_reason: Option<String>,
 }
 
@@ -106,7 +1063,100 @@ fn validate_future_date(date: &chrono::NaiveDate) -> Result<(), validator::Valid
     Ok(())
 }
 
-fn validate_checkout_after_checkin(checkout: &chrono::NaiveDate) -> Result<(), validator::ValidationError> {
+// Los horarios de los restaurantes están en la hora local del servidor
+fn validate_not_past_date(date: &chrono::NaiveDate) -> Result<(), validator::ValidationError> {
+    if *date < chrono::Local::now().date_naive() {
+        return Err(validator::ValidationError::new("La fecha no puede estar en el pasado"));
+    }
+    Ok(())
+}
+
+fn validate_checkout_after_checkin(_checkout: &chrono::NaiveDate) -> Result<(), validator::ValidationError> {
     // Esta validación se hará en el handler ya que necesitamos comparar con check_in
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_BOOKING_STATUSES: [BookingStatus; 7] = [
+        BookingStatus::Pending,
+        BookingStatus::Confirmed,
+        BookingStatus::CheckedIn,
+        BookingStatus::CheckedOut,
+        BookingStatus::Cancelled,
+        BookingStatus::NoShow,
+        BookingStatus::Rejected,
+    ];
+
+    #[test]
+    fn reserva_pendiente_se_confirma_rechaza_o_cancela() {
+        let pending = BookingStatus::Pending;
+
+        assert!(pending.can_transition_to(BookingStatus::Confirmed));
+        assert!(pending.can_transition_to(BookingStatus::Rejected));
+        assert!(pending.can_transition_to(BookingStatus::Cancelled));
+        assert!(!pending.can_transition_to(BookingStatus::CheckedIn));
+        assert!(!pending.can_transition_to(BookingStatus::NoShow));
+    }
+
+    #[test]
+    fn reserva_confirmada_llega_a_check_in_no_show_o_cancelacion() {
+        let confirmed = BookingStatus::Confirmed;
+
+        assert!(confirmed.can_transition_to(BookingStatus::CheckedIn));
+        assert!(confirmed.can_transition_to(BookingStatus::NoShow));
+        assert!(confirmed.can_transition_to(BookingStatus::Cancelled));
+        assert!(!confirmed.can_transition_to(BookingStatus::Rejected));
+        assert!(!confirmed.can_transition_to(BookingStatus::CheckedOut));
+        assert!(!confirmed.can_transition_to(BookingStatus::Pending));
+    }
+
+    #[test]
+    fn check_in_solo_avanza_a_check_out() {
+        for next in ALL_BOOKING_STATUSES {
+            assert_eq!(
+                BookingStatus::CheckedIn.can_transition_to(next),
+                next == BookingStatus::CheckedOut,
+                "checked_in -> {}",
+                next.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn estados_finales_no_cambian() {
+        let finals = [
+            BookingStatus::CheckedOut,
+            BookingStatus::Cancelled,
+            BookingStatus::NoShow,
+            BookingStatus::Rejected,
+        ];
+        for current in finals {
+            for next in ALL_BOOKING_STATUSES {
+                assert!(
+                    !current.can_transition_to(next),
+                    "{} -> {}",
+                    current.as_str(),
+                    next.as_str()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ningun_estado_transiciona_a_si_mismo() {
+        for status in ALL_BOOKING_STATUSES {
+            assert!(!status.can_transition_to(status), "{}", status.as_str());
+        }
+    }
+
+    #[test]
+    fn estados_de_reserva_ida_y_vuelta_con_la_bd() {
+        for status in ALL_BOOKING_STATUSES {
+            assert_eq!(BookingStatus::from_db(status.as_str()), Some(status));
+        }
+        assert_eq!(BookingStatus::from_db("desconocido"), None);
+    }
+}
@@ -0,0 +1,122 @@
+// backend/src/money.rs
+use std::ops::{Add, Sub};
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+// Monedas aceptadas por la plataforma (código ISO 4217)
+// Los nombres son los códigos ISO tal cual, así se serializan sin renombrar
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Currency {
+    MXN,
+    USD,
+}
+
+impl Currency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Currency::MXN => "MXN",
+            Currency::USD => "USD",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Currency> {
+        match code.trim().to_uppercase().as_str() {
+            "MXN" => Some(Currency::MXN),
+            "USD" => Some(Currency::USD),
+            _ => None,
+        }
+    }
+}
+
+// Cantidad de dinero exacta (decimal, redondeada a centavos) con su moneda.
+// Se serializa como {"amount": "2500.00", "currency": "MXN"}: el monto va
+// como texto para que ningún cliente lo interprete como float binario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Money {
+    amount: BigDecimal,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: BigDecimal, currency: Currency) -> Money {
+        Money {
+            amount: amount.round(2),
+            currency,
+        }
+    }
+
+    pub fn mxn(amount: BigDecimal) -> Money {
+        Money::new(amount, Currency::MXN)
+    }
+
+    // Construye a partir de columnas DECIMAL + VARCHAR(3) de la BD
+    pub fn from_db(amount: BigDecimal, currency_code: &str) -> Money {
+        let currency = Currency::from_code(currency_code).unwrap_or_else(|| {
+            println!("⚠️ [MONEY] Moneda desconocida '{}', usando MXN", currency_code);
+            Currency::MXN
+        });
+        Money::new(amount, currency)
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::new(BigDecimal::zero(), currency)
+    }
+
+    pub fn amount(&self) -> &BigDecimal {
+        &self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount < BigDecimal::zero()
+    }
+
+    // Multiplica por una cantidad entera (noches, habitaciones, unidades)
+    pub fn times(&self, quantity: i64) -> Money {
+        Money::new(&self.amount * BigDecimal::from(quantity), self.currency)
+    }
+
+    // Porcentaje del monto (15 => 15%), redondeado a centavos
+    pub fn percent(&self, percent: &BigDecimal) -> Money {
+        Money::new(&self.amount * percent / BigDecimal::from(100), self.currency)
+    }
+
+    // Multiplica por una tasa decimal (0.20 => 20%), redondeado a centavos
+    pub fn rate(&self, rate: &BigDecimal) -> Money {
+        Money::new(&self.amount * rate, self.currency)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    // Sumar monedas distintas es un error de programación, no un caso de negocio
+    fn add(self, other: Money) -> Money {
+        assert_eq!(self.currency, other.currency, "No se pueden sumar montos en monedas distintas");
+        Money::new(self.amount + other.amount, self.currency)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        assert_eq!(self.currency, other.currency, "No se pueden restar montos en monedas distintas");
+        Money::new(self.amount - other.amount, self.currency)
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Money", 2)?;
+        state.serialize_field("amount", &self.amount.with_scale(2).to_string())?;
+        state.serialize_field("currency", self.currency.as_str())?;
+        state.end()
+    }
+}
@@ -0,0 +1,127 @@
+// backend/src/services/addons.rs
+use serde::Serialize;
+
+use crate::models::AddonSelection;
+use crate::money::{Currency, Money};
+
+// Addon con precio calculado en el servidor; se guarda como snapshot en la reserva
+#[derive(Debug, Clone, Serialize)]
+pub struct PricedAddon {
+    pub addon_id: i32,
+    pub name: String,
+    pub pricing_unit: String,
+    pub unit_price: Money,
+    pub quantity: i32,
+    pub subtotal: Money,
+}
+
+#[derive(Debug)]
+pub enum AddonError {
+    NotFound(i32),
+    InvalidQuantity { addon_id: i32, max_quantity: i32 },
+    Duplicated(i32),
+    CurrencyMismatch(i32),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for AddonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddonError::NotFound(id) => write!(f, "El servicio adicional {} no existe o no está disponible en este hotel", id),
+            AddonError::InvalidQuantity { addon_id, max_quantity } => write!(
+                f,
+                "La cantidad del servicio adicional {} debe estar entre 1 y {}",
+                addon_id, max_quantity
+            ),
+            AddonError::Duplicated(id) => write!(f, "El servicio adicional {} aparece más de una vez", id),
+            AddonError::CurrencyMismatch(id) => write!(f, "El servicio adicional {} tiene una moneda distinta a la del hotel", id),
+            AddonError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AddonError {
+    fn from(e: sqlx::Error) -> Self {
+        AddonError::Database(e)
+    }
+}
+
+// Valida las selecciones contra el catálogo del hotel y calcula cada subtotal.
+// Los precios del cliente nunca se usan: solo se aceptan IDs y cantidades.
+pub async fn price_addons<'c, E>(
+    executor: E,
+    hotel_id: i32,
+    selections: &[AddonSelection],
+    nights: i64,
+    guests: i32,
+    currency: Currency,
+) -> Result<Vec<PricedAddon>, AddonError>
+where
+    E: sqlx::PgExecutor<'c>,
+{
+    if selections.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<i32> = selections.iter().map(|s| s.addon_id).collect();
+    let catalog = sqlx::query!(
+        r#"
+        SELECT id, name, price, currency, pricing_unit, max_quantity
+        FROM addon_services
+        WHERE hotel_id = $1 AND id = ANY($2) AND is_active
+        "#,
+        hotel_id,
+        &ids[..]
+    )
+    .fetch_all(executor)
+    .await?;
+
+    let mut priced: Vec<PricedAddon> = Vec::with_capacity(selections.len());
+
+    for selection in selections {
+        if priced.iter().any(|p| p.addon_id == selection.addon_id) {
+            return Err(AddonError::Duplicated(selection.addon_id));
+        }
+
+        let addon = catalog
+            .iter()
+            .find(|a| a.id == selection.addon_id)
+            .ok_or(AddonError::NotFound(selection.addon_id))?;
+
+        if selection.quantity < 1 || selection.quantity > addon.max_quantity {
+            return Err(AddonError::InvalidQuantity {
+                addon_id: addon.id,
+                max_quantity: addon.max_quantity,
+            });
+        }
+
+        let unit_price = Money::from_db(addon.price.clone(), &addon.currency);
+        if unit_price.currency() != currency {
+            return Err(AddonError::CurrencyMismatch(addon.id));
+        }
+
+        // Unidades cobradas según la forma de tarificar el addon
+        let units = match addon.pricing_unit.as_str() {
+            "per_night" => nights,
+            "per_guest" => guests as i64,
+            _ => 1,
+        };
+
+        priced.push(PricedAddon {
+            addon_id: addon.id,
+            name: addon.name.clone(),
+            pricing_unit: addon.pricing_unit.clone(),
+            subtotal: unit_price.times(units * selection.quantity as i64),
+            unit_price,
+            quantity: selection.quantity,
+        });
+    }
+
+    Ok(priced)
+}
+
+pub fn addons_total(addons: &[PricedAddon], currency: Currency) -> Money {
+    addons
+        .iter()
+        .fold(Money::zero(currency), |total, addon| total + addon.subtotal.clone())
+}
@@ -0,0 +1,483 @@
+// backend/src/services/bookings.rs
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::{AddonSelection, BookingStatus, CreateBookingRequest, ModifyBookingRequest};
+use crate::money::Money;
+use crate::services::inventory::{self, RoomAllocation};
+use crate::services::pricing::{self, PricingError, StayQuote, StayRequest};
+use crate::services::references;
+
+// Titular de una reserva: un usuario registrado o un huésped sin cuenta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookingHolder {
+    User(i32),
+    Guest(i32),
+}
+
+impl BookingHolder {
+    pub fn user_id(&self) -> Option<i32> {
+        match self {
+            BookingHolder::User(id) => Some(*id),
+            BookingHolder::Guest(_) => None,
+        }
+    }
+
+    pub fn guest_id(&self) -> Option<i32> {
+        match self {
+            BookingHolder::User(_) => None,
+            BookingHolder::Guest(id) => Some(*id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StayBooking {
+    pub id: i32,
+    pub reference: String,
+    pub hotel_id: i32,
+    pub hotel_name: String,
+    pub hotel_location: String,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+    pub guests: i32,
+    pub rooms: i32,
+    pub total_price: Money,
+    pub price_breakdown: StayQuote,
+    pub status: BookingStatus,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// Resultado de modificar una reserva
+#[derive(Debug, Clone, Serialize)]
+pub struct ModifiedStay {
+    pub booking: StayBooking,
+    pub version: i32,
+    pub previous_total: Money,
+    // Positivo: el nuevo total es mayor; negativo: es menor (aún no hay cobro)
+    pub price_difference: Money,
+}
+
+#[derive(Debug)]
+pub enum BookingError {
+    InvalidDates,
+    HotelUnavailable,
+    NotFound,
+    NotModifiable(BookingStatus),
+    StayStarted,
+    PartOfTrip,
+    PaymentStarted,
+    CurrencyChanged,
+    Pricing(PricingError),
+    SoldOut { night: NaiveDate, rooms_left: i32 },
+    TooManyGuests { max_guests: i32 },
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for BookingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookingError::InvalidDates => write!(f, "La fecha de check-out debe ser posterior a la de check-in"),
+            BookingError::HotelUnavailable => write!(f, "Hotel no encontrado o no disponible"),
+            BookingError::NotFound => write!(f, "Reserva no encontrada"),
+            BookingError::NotModifiable(status) => {
+                write!(f, "No se puede modificar una reserva en estado '{}'", status.as_str())
+            }
+            BookingError::StayStarted => write!(f, "La estancia ya comenzó; contacta al hotel para cambiarla"),
+            BookingError::PartOfTrip => {
+                write!(f, "La reserva es parte de un viaje; cancela el viaje y vuelve a reservar")
+            }
+            BookingError::PaymentStarted => {
+                write!(f, "La reserva ya tiene un pago registrado; cancélala y vuelve a reservar")
+            }
+            BookingError::CurrencyChanged => {
+                write!(f, "El hotel cambió la moneda de sus tarifas; cancela y vuelve a reservar")
+            }
+            BookingError::Pricing(e) => write!(f, "{}", e),
+            BookingError::SoldOut { night, .. } => write!(f, "No hay habitaciones disponibles para la noche del {}", night),
+            BookingError::TooManyGuests { max_guests } => {
+                write!(f, "Las habitaciones elegidas admiten máximo {} huéspedes", max_guests)
+            }
+            BookingError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for BookingError {
+    fn from(e: sqlx::Error) -> Self {
+        BookingError::Database(e)
+    }
+}
+
+impl From<PricingError> for BookingError {
+    fn from(e: PricingError) -> Self {
+        match e {
+            PricingError::Database(e) => BookingError::Database(e),
+            other => BookingError::Pricing(other),
+        }
+    }
+}
+
+// Cotiza la estancia, aparta las habitaciones y registra la reserva.
+// Debe llamarse dentro de una transacción: si algo falla, el llamador
+// descarta la transacción y el inventario queda como estaba.
+pub async fn book_stay(
+    conn: &mut PgConnection,
+    holder: BookingHolder,
+    booking: &CreateBookingRequest,
+) -> Result<StayBooking, BookingError> {
+    if booking.check_out <= booking.check_in {
+        return Err(BookingError::InvalidDates);
+    }
+
+    let (hotel, quote) = quote_and_reserve(
+        &mut *conn,
+        &StayRequest {
+            hotel_id: booking.hotel_id,
+            check_in: booking.check_in,
+            check_out: booking.check_out,
+            guests: booking.guests,
+            rooms: booking.rooms,
+            addons: booking.addon_services.as_deref().unwrap_or(&[]),
+        },
+    )
+    .await?;
+
+    let reference = references::issue_reference(
+        &mut *conn,
+        references::HOTEL_BOOKING_PREFIX,
+        "hotel_booking",
+    )
+    .await?;
+
+    let addon_snapshot = if quote.addons.is_empty() {
+        None
+    } else {
+        serde_json::to_value(&quote.addons).ok()
+    };
+    let price_breakdown = serde_json::to_value(&quote).ok();
+    let total_price = quote.total.clone();
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO bookings
+        (user_id, guest_id, hotel_id, check_in, check_out, guests, rooms, total_price, currency,
+         special_requests, addon_services, commission_amount, price_breakdown,
+         status, payment_status, booking_reference)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, 'pending', $15)
+        RETURNING id, created_at
+        "#,
+        holder.user_id(),
+        holder.guest_id(),
+        booking.hotel_id,
+        booking.check_in,
+        booking.check_out,
+        booking.guests,
+        booking.rooms,
+        total_price.amount(),
+        total_price.currency().as_str(),
+        booking.special_requests,
+        addon_snapshot,
+        quote.commission.amount(),
+        price_breakdown,
+        BookingStatus::Pending.as_str(),
+        reference
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_status_change(&mut *conn, created.id, None, BookingStatus::Pending, holder.user_id(), None).await?;
+
+    Ok(StayBooking {
+        id: created.id,
+        reference,
+        hotel_id: booking.hotel_id,
+        hotel_name: hotel.name,
+        hotel_location: hotel.location,
+        check_in: booking.check_in,
+        check_out: booking.check_out,
+        guests: booking.guests,
+        rooms: booking.rooms,
+        total_price,
+        price_breakdown: quote,
+        status: BookingStatus::Pending,
+        created_at: created.created_at,
+    })
+}
+
+// Modifica fechas, habitaciones o huéspedes de una reserva pendiente o
+// confirmada. Guarda la versión anterior, devuelve sus habitaciones y vuelve a
+// cotizar y apartar como en book_stay; si no hay cupo, el llamador descarta la
+// transacción y la reserva queda intacta.
+pub async fn modify_stay(
+    conn: &mut PgConnection,
+    booking_id: i32,
+    user_id: i32,
+    changes: &ModifyBookingRequest,
+) -> Result<ModifiedStay, BookingError> {
+    if changes.check_out <= changes.check_in {
+        return Err(BookingError::InvalidDates);
+    }
+
+    let current = sqlx::query!(
+        r#"
+        SELECT hotel_id, check_in, check_out, guests, rooms, total_price, currency, addon_services,
+               special_requests, price_breakdown, status, payment_status, trip_id,
+               booking_reference, created_at, check_in <= CURRENT_DATE as "stay_started!"
+        FROM bookings
+        WHERE id = $1 AND user_id = $2
+        FOR UPDATE
+        "#,
+        booking_id,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(BookingError::NotFound)?;
+
+    let status = BookingStatus::from_db(&current.status).unwrap_or(BookingStatus::Pending);
+    check_modifiable(status, current.stay_started, current.trip_id, &current.payment_status)?;
+
+    // Sin addons en la solicitud se conservan los de la versión actual
+    let addons: Vec<AddonSelection> = match &changes.addon_services {
+        Some(addons) => addons.clone(),
+        None => current
+            .addon_services
+            .clone()
+            .and_then(|snapshot| serde_json::from_value(snapshot).ok())
+            .unwrap_or_default(),
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO booking_versions
+        (booking_id, version, check_in, check_out, guests, rooms, total_price, currency,
+         addon_services, special_requests, price_breakdown, replaced_by)
+        SELECT id, version, check_in, check_out, guests, rooms, total_price, currency,
+               addon_services, special_requests, price_breakdown, $2
+        FROM bookings WHERE id = $1
+        "#,
+        booking_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    inventory::release_rooms(
+        &mut *conn,
+        current.hotel_id,
+        current.check_in,
+        current.check_out,
+        current.rooms,
+    )
+    .await?;
+
+    let (hotel, quote) = quote_and_reserve(
+        &mut *conn,
+        &StayRequest {
+            hotel_id: current.hotel_id,
+            check_in: changes.check_in,
+            check_out: changes.check_out,
+            guests: changes.guests,
+            rooms: changes.rooms,
+            addons: &addons,
+        },
+    )
+    .await?;
+
+    let previous_total = Money::from_db(current.total_price, &current.currency);
+    if previous_total.currency() != quote.total.currency() {
+        return Err(BookingError::CurrencyChanged);
+    }
+    let total_price = quote.total.clone();
+    let price_difference = total_price.clone() - previous_total.clone();
+
+    let addon_snapshot = if quote.addons.is_empty() {
+        None
+    } else {
+        serde_json::to_value(&quote.addons).ok()
+    };
+    let special_requests = changes.special_requests.clone().or(current.special_requests);
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE bookings SET check_in = $2, check_out = $3, guests = $4, rooms = $5,
+            total_price = $6, currency = $7, addon_services = $8, commission_amount = $9,
+            price_breakdown = $10, special_requests = $11, version = version + 1,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING version
+        "#,
+        booking_id,
+        changes.check_in,
+        changes.check_out,
+        changes.guests,
+        changes.rooms,
+        total_price.amount(),
+        total_price.currency().as_str(),
+        addon_snapshot,
+        quote.commission.amount(),
+        serde_json::to_value(&quote).ok(),
+        special_requests
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(ModifiedStay {
+        booking: StayBooking {
+            id: booking_id,
+            reference: current.booking_reference,
+            hotel_id: current.hotel_id,
+            hotel_name: hotel.name,
+            hotel_location: hotel.location,
+            check_in: changes.check_in,
+            check_out: changes.check_out,
+            guests: changes.guests,
+            rooms: changes.rooms,
+            total_price,
+            price_breakdown: quote,
+            status,
+            created_at: current.created_at,
+        },
+        version: updated.version,
+        previous_total,
+        price_difference,
+    })
+}
+
+struct BookedHotel {
+    name: String,
+    location: String,
+}
+
+// Cotiza con el motor de precios (temporada, fin de semana, estancia larga,
+// addons) y aparta las habitaciones del rango
+// Solo se modifican reservas sueltas sin cobro: el total de un viaje y los
+// pagos ya registrados se calcularon con la versión actual
+fn check_modifiable(
+    status: BookingStatus,
+    stay_started: bool,
+    trip_id: Option<i32>,
+    payment_status: &str,
+) -> Result<(), BookingError> {
+    if !matches!(status, BookingStatus::Pending | BookingStatus::Confirmed) {
+        return Err(BookingError::NotModifiable(status));
+    }
+    if stay_started {
+        return Err(BookingError::StayStarted);
+    }
+    if trip_id.is_some() {
+        return Err(BookingError::PartOfTrip);
+    }
+    if payment_status != "pending" {
+        return Err(BookingError::PaymentStarted);
+    }
+    Ok(())
+}
+
+async fn quote_and_reserve(
+    conn: &mut PgConnection,
+    stay: &StayRequest<'_>,
+) -> Result<(BookedHotel, StayQuote), BookingError> {
+    let hotel = sqlx::query!(
+        "SELECT name, location, price, currency, max_guests_per_room FROM hotels WHERE id = $1 AND status = 'approved'",
+        stay.hotel_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(BookingError::HotelUnavailable)?;
+
+    // Misma regla que la búsqueda de disponibilidad
+    let max_guests = hotel.max_guests_per_room * stay.rooms;
+    if stay.guests > max_guests {
+        return Err(BookingError::TooManyGuests { max_guests });
+    }
+
+    let nightly_rate = Money::from_db(hotel.price, &hotel.currency);
+    let quote = pricing::quote_stay(&mut *conn, &nightly_rate, stay).await?;
+
+    match inventory::reserve_rooms(&mut *conn, stay.hotel_id, stay.check_in, stay.check_out, stay.rooms).await? {
+        RoomAllocation::Reserved => {}
+        RoomAllocation::SoldOut { night, rooms_left } => {
+            return Err(BookingError::SoldOut { night, rooms_left });
+        }
+    }
+
+    Ok((
+        BookedHotel {
+            name: hotel.name,
+            location: hotel.location,
+        },
+        quote,
+    ))
+}
+
+// changed_by es None cuando el cambio lo hace un huésped sin cuenta
+pub async fn record_status_change(
+    conn: &mut PgConnection,
+    booking_id: i32,
+    from: Option<BookingStatus>,
+    to: BookingStatus,
+    changed_by: Option<i32>,
+    reason: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO booking_status_history (booking_id, from_status, to_status, reason, changed_by)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        booking_id,
+        from.map(|s| s.as_str()),
+        to.as_str(),
+        reason,
+        changed_by
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permite_modificar_una_reserva_suelta_sin_cobro() {
+        assert!(check_modifiable(BookingStatus::Pending, false, None, "pending").is_ok());
+        assert!(check_modifiable(BookingStatus::Confirmed, false, None, "pending").is_ok());
+    }
+
+    #[test]
+    fn rechaza_reservas_que_son_parte_de_un_viaje() {
+        assert!(matches!(
+            check_modifiable(BookingStatus::Confirmed, false, Some(7), "pending"),
+            Err(BookingError::PartOfTrip)
+        ));
+    }
+
+    #[test]
+    fn rechaza_reservas_con_pago_registrado() {
+        assert!(matches!(
+            check_modifiable(BookingStatus::Confirmed, false, None, "paid"),
+            Err(BookingError::PaymentStarted)
+        ));
+        assert!(matches!(
+            check_modifiable(BookingStatus::Pending, false, None, "failed"),
+            Err(BookingError::PaymentStarted)
+        ));
+    }
+
+    #[test]
+    fn rechaza_estados_cerrados_y_estancias_iniciadas() {
+        assert!(matches!(
+            check_modifiable(BookingStatus::Cancelled, false, None, "pending"),
+            Err(BookingError::NotModifiable(BookingStatus::Cancelled))
+        ));
+        assert!(matches!(
+            check_modifiable(BookingStatus::Confirmed, true, None, "pending"),
+            Err(BookingError::StayStarted)
+        ));
+    }
+}
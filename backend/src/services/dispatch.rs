@@ -0,0 +1,257 @@
+// backend/src/services/dispatch.rs
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use actix_ws::Session;
+use bigdecimal::BigDecimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::money::Money;
+use crate::services::notifications;
+use crate::services::rides::{self, AcceptedRide, RideError, MAX_PICKUP_KM};
+
+// Solicitudes más viejas que esto ya no se ofrecen al conectarse un conductor
+const PENDING_OFFER_MINUTES: i32 = 30;
+
+// Conexión WebSocket de un conductor. El id distingue una reconexión de la
+// conexión anterior para que el cierre de la vieja no borre la nueva.
+struct DriverConnection {
+    connection_id: Uuid,
+    session: Session,
+}
+
+// Conductores conectados al WebSocket de despacho, por id de conductor. Se crea
+// una sola vez fuera de HttpServer::new para que todos los workers la compartan.
+#[derive(Default)]
+pub struct DispatchHub {
+    connections: Mutex<HashMap<i32, DriverConnection>>,
+}
+
+impl DispatchHub {
+    pub fn connect(&self, driver_id: i32, session: Session) -> Uuid {
+        let connection_id = Uuid::new_v4();
+        self.connections
+            .lock()
+            .expect("dispatch hub lock")
+            .insert(driver_id, DriverConnection { connection_id, session });
+        connection_id
+    }
+
+    // Devuelve false si el conductor ya se había reconectado con otra conexión
+    pub fn disconnect(&self, driver_id: i32, connection_id: Uuid) -> bool {
+        let mut connections = self.connections.lock().expect("dispatch hub lock");
+        if connections.get(&driver_id).map(|c| c.connection_id) == Some(connection_id) {
+            connections.remove(&driver_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_connected(&self, driver_id: i32) -> bool {
+        self.connections.lock().expect("dispatch hub lock").contains_key(&driver_id)
+    }
+
+    // Envía un mensaje JSON a un conductor; devuelve false si no está conectado
+    pub async fn send(&self, driver_id: i32, message: &serde_json::Value) -> bool {
+        // La sesión se clona para no mantener el candado mientras se envía
+        let session = self
+            .connections
+            .lock()
+            .expect("dispatch hub lock")
+            .get(&driver_id)
+            .map(|c| c.session.clone());
+
+        match session {
+            Some(mut session) => session.text(message.to_string()).await.is_ok(),
+            None => false,
+        }
+    }
+
+    // Envía un mensaje a todos los conductores conectados, salvo `except`
+    pub async fn broadcast(&self, message: &serde_json::Value, except: Option<i32>) {
+        let sessions: Vec<Session> = self
+            .connections
+            .lock()
+            .expect("dispatch hub lock")
+            .iter()
+            .filter(|(driver_id, _)| Some(**driver_id) != except)
+            .map(|(_, c)| c.session.clone())
+            .collect();
+
+        let text = message.to_string();
+        for mut session in sessions {
+            let _ = session.text(text.clone()).await;
+        }
+    }
+}
+
+// Viaje en 'requested' tal como se ofrece a los conductores
+struct OpenRide {
+    id: i32,
+    ride_reference: String,
+    origin_address: String,
+    origin_lat: f64,
+    origin_lng: f64,
+    destination_address: String,
+    destination_lat: f64,
+    destination_lng: f64,
+    distance_km: BigDecimal,
+    duration_minutes: i32,
+    fare: BigDecimal,
+    currency: String,
+}
+
+impl OpenRide {
+    fn offer(&self, pickup_distance_km: f64) -> serde_json::Value {
+        json!({
+            "type": "ride_offer",
+            "ride": {
+                "id": self.id,
+                "reference": self.ride_reference,
+                "origin": { "address": self.origin_address, "lat": self.origin_lat, "lng": self.origin_lng },
+                "destination": {
+                    "address": self.destination_address,
+                    "lat": self.destination_lat,
+                    "lng": self.destination_lng
+                },
+                "distance_km": self.distance_km,
+                "duration_minutes": self.duration_minutes,
+                "fare": Money::from_db(self.fare.clone(), &self.currency),
+                "pickup_distance_km": (pickup_distance_km * 100.0).round() / 100.0
+            }
+        })
+    }
+}
+
+// Ofrece un viaje recién solicitado a los conductores certificados, en línea,
+// libres y conectados cerca del punto de partida. Devuelve a cuántos llegó.
+pub async fn offer_ride(pool: &PgPool, hub: &DispatchHub, ride_id: i32) -> Result<usize, sqlx::Error> {
+    let ride = sqlx::query_as!(
+        OpenRide,
+        r#"
+        SELECT id, ride_reference, origin_address, origin_lat, origin_lng,
+               destination_address, destination_lat, destination_lng,
+               distance_km, duration_minutes, fare, currency
+        FROM rides
+        WHERE id = $1 AND status = 'requested'
+        "#,
+        ride_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let ride = match ride {
+        Some(ride) => ride,
+        None => return Ok(0),
+    };
+
+    let drivers = sqlx::query!(
+        r#"
+        SELECT id, latitude as "latitude!", longitude as "longitude!"
+        FROM drivers
+        WHERE is_online AND is_available AND certification_status = 'approved'
+          AND latitude IS NOT NULL AND longitude IS NOT NULL
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut offered = 0;
+    for driver in drivers {
+        let pickup_km = rides::haversine_km((driver.latitude, driver.longitude), (ride.origin_lat, ride.origin_lng));
+        if pickup_km > MAX_PICKUP_KM || !hub.is_connected(driver.id) {
+            continue;
+        }
+        if hub.send(driver.id, &ride.offer(pickup_km)).await {
+            offered += 1;
+        }
+    }
+
+    println!("📡 [DISPATCH] Viaje {} ofrecido a {} conductores", ride_id, offered);
+    Ok(offered)
+}
+
+// Al conectarse o ponerse en línea, el conductor recibe las solicitudes
+// recientes que siguen sin conductor cerca de su ubicación
+pub async fn send_pending_offers(pool: &PgPool, hub: &DispatchHub, driver_id: i32) -> Result<usize, sqlx::Error> {
+    let driver = sqlx::query!(
+        r#"
+        SELECT latitude, longitude FROM drivers
+        WHERE id = $1 AND is_online AND is_available AND certification_status = 'approved'
+        "#,
+        driver_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let location = match driver {
+        Some(driver) => match (driver.latitude, driver.longitude) {
+            (Some(lat), Some(lng)) => (lat, lng),
+            _ => return Ok(0),
+        },
+        None => return Ok(0),
+    };
+
+    let open_rides = sqlx::query_as!(
+        OpenRide,
+        r#"
+        SELECT id, ride_reference, origin_address, origin_lat, origin_lng,
+               destination_address, destination_lat, destination_lng,
+               distance_km, duration_minutes, fare, currency
+        FROM rides
+        WHERE status = 'requested'
+          AND created_at > CURRENT_TIMESTAMP - make_interval(mins => $1)
+        ORDER BY created_at
+        "#,
+        PENDING_OFFER_MINUTES
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut offered = 0;
+    for ride in open_rides {
+        let pickup_km = rides::haversine_km(location, (ride.origin_lat, ride.origin_lng));
+        if pickup_km <= MAX_PICKUP_KM && hub.send(driver_id, &ride.offer(pickup_km)).await {
+            offered += 1;
+        }
+    }
+
+    Ok(offered)
+}
+
+// Aceptación de una oferta: asigna el viaje, avisa al pasajero y retira la
+// oferta a los demás conductores
+pub async fn accept_offer(
+    pool: &PgPool,
+    hub: &DispatchHub,
+    driver_id: i32,
+    driver_user_id: i32,
+    ride_id: i32,
+) -> Result<AcceptedRide, RideError> {
+    let mut tx = pool.begin().await?;
+
+    let accepted = rides::accept_ride(&mut tx, ride_id, driver_id, driver_user_id).await?;
+
+    notifications::notify(
+        &mut tx,
+        accepted.rider_id,
+        "ride_assigned",
+        "Tu conductor va en camino",
+        &format!(
+            "{} llegará en {} ({}) para tu viaje {}.",
+            accepted.driver.name, accepted.driver.vehicle, accepted.driver.plate, accepted.reference
+        ),
+        Some(json!({ "ride_id": ride_id, "driver_id": driver_id })),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    println!("✅ [DISPATCH] Conductor {} aceptó el viaje {} ({})", driver_id, ride_id, accepted.reference);
+    hub.broadcast(&json!({ "type": "offer_taken", "ride_id": ride_id }), Some(driver_id)).await;
+
+    Ok(accepted)
+}
@@ -0,0 +1,249 @@
+// backend/src/services/experiences.rs
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::CreateExperienceBookingRequest;
+use crate::money::{Currency, Money};
+use crate::services::references;
+
+pub const EXPERIENCE_BOOKING_PREFIX: &str = "MX";
+
+// Máximo de participantes por reserva
+const MAX_PARTICIPANTS: i32 = 20;
+
+// Tarifa con precio calculado en el servidor; se guarda como snapshot en la reserva
+#[derive(Debug, Clone, Serialize)]
+pub struct PricedFare {
+    pub fare_type: String,
+    pub label: String,
+    pub unit_price: Money,
+    pub quantity: i32,
+    pub subtotal: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExperienceBooking {
+    pub id: i32,
+    pub reference: String,
+    pub experience_id: i32,
+    pub departure_id: i32,
+    pub departure_date: NaiveDate,
+    pub departure_time: NaiveTime,
+    pub participants: i32,
+    pub fares: Vec<PricedFare>,
+    pub total_price: Money,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum ExperienceError {
+    ExperienceUnavailable,
+    DepartureNotFound,
+    DepartureClosed,
+    FareNotFound(String),
+    Duplicated(String),
+    InvalidQuantity(String),
+    TooManyParticipants,
+    AdultRequired,
+    SoldOut { seats_left: i32 },
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ExperienceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExperienceError::ExperienceUnavailable => write!(f, "Experiencia no encontrada o no disponible"),
+            ExperienceError::DepartureNotFound => write!(f, "La salida no existe para esta experiencia"),
+            ExperienceError::DepartureClosed => write!(f, "La salida ya no acepta reservas"),
+            ExperienceError::FareNotFound(fare) => write!(f, "La tarifa '{}' no existe para esta experiencia", fare),
+            ExperienceError::Duplicated(fare) => write!(f, "La tarifa '{}' aparece más de una vez", fare),
+            ExperienceError::InvalidQuantity(fare) => write!(f, "La cantidad de la tarifa '{}' debe ser mayor a cero", fare),
+            ExperienceError::TooManyParticipants => write!(f, "Una reserva admite máximo {} participantes", MAX_PARTICIPANTS),
+            ExperienceError::AdultRequired => write!(f, "Los menores deben ir acompañados de al menos un adulto"),
+            ExperienceError::SoldOut { seats_left } => write!(f, "No hay lugares suficientes en esta salida (quedan {})", seats_left),
+            ExperienceError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ExperienceError {
+    fn from(e: sqlx::Error) -> Self {
+        ExperienceError::Database(e)
+    }
+}
+
+// Aparta lugares en una salida y registra la reserva con su precio.
+// Debe llamarse dentro de una transacción: el bloqueo de la salida serializa
+// las reservas concurrentes hasta el COMMIT.
+pub async fn book_departure(
+    conn: &mut PgConnection,
+    user_id: i32,
+    experience_id: i32,
+    booking: &CreateExperienceBookingRequest,
+) -> Result<ExperienceBooking, ExperienceError> {
+    let experience = sqlx::query!(
+        "SELECT currency FROM experiences WHERE id = $1 AND status = 'approved'",
+        experience_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(ExperienceError::ExperienceUnavailable)?;
+    let currency = Currency::from_code(&experience.currency).unwrap_or(Currency::MXN);
+
+    let departure = sqlx::query!(
+        r#"
+        SELECT departure_date, departure_time, capacity, seats_booked, status
+        FROM experience_departures
+        WHERE id = $1 AND experience_id = $2
+        FOR UPDATE
+        "#,
+        booking.departure_id,
+        experience_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(ExperienceError::DepartureNotFound)?;
+
+    if departure.status != "scheduled" || departure.departure_date <= Utc::now().naive_utc().date() {
+        return Err(ExperienceError::DepartureClosed);
+    }
+
+    let catalog = sqlx::query!(
+        r#"
+        SELECT fare_type, label, price, requires_adult
+        FROM experience_fares
+        WHERE experience_id = $1 AND is_active
+        "#,
+        experience_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut fares: Vec<PricedFare> = Vec::with_capacity(booking.fares.len());
+    let mut participants = 0;
+    let mut adults = 0;
+    let mut minors = 0;
+
+    for selection in &booking.fares {
+        if fares.iter().any(|f| f.fare_type == selection.fare_type) {
+            return Err(ExperienceError::Duplicated(selection.fare_type.clone()));
+        }
+
+        let fare = catalog
+            .iter()
+            .find(|f| f.fare_type == selection.fare_type)
+            .ok_or_else(|| ExperienceError::FareNotFound(selection.fare_type.clone()))?;
+
+        if selection.quantity < 1 {
+            return Err(ExperienceError::InvalidQuantity(selection.fare_type.clone()));
+        }
+        // Tope por tarifa antes de sumar, para que la suma no pueda desbordarse
+        if selection.quantity > MAX_PARTICIPANTS {
+            return Err(ExperienceError::TooManyParticipants);
+        }
+
+        participants += selection.quantity;
+        if fare.requires_adult {
+            minors += selection.quantity;
+        } else {
+            adults += selection.quantity;
+        }
+
+        let unit_price = Money::new(fare.price.clone(), currency);
+        fares.push(PricedFare {
+            fare_type: fare.fare_type.clone(),
+            label: fare.label.clone(),
+            subtotal: unit_price.times(selection.quantity as i64),
+            unit_price,
+            quantity: selection.quantity,
+        });
+    }
+
+    if participants > MAX_PARTICIPANTS {
+        return Err(ExperienceError::TooManyParticipants);
+    }
+
+    if minors > 0 && adults == 0 {
+        return Err(ExperienceError::AdultRequired);
+    }
+
+    let seats_left = departure.capacity - departure.seats_booked;
+    if seats_left < participants {
+        println!("⛔ [EXPERIENCES] Salida {} sin cupo (quedan {})", booking.departure_id, seats_left);
+        return Err(ExperienceError::SoldOut { seats_left: seats_left.max(0) });
+    }
+
+    sqlx::query!(
+        "UPDATE experience_departures SET seats_booked = seats_booked + $2 WHERE id = $1",
+        booking.departure_id,
+        participants
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let total_price = fares
+        .iter()
+        .fold(Money::zero(currency), |total, fare| total + fare.subtotal.clone());
+
+    let reference = references::issue_reference(
+        &mut *conn,
+        EXPERIENCE_BOOKING_PREFIX,
+        "experience_booking",
+    )
+    .await?;
+
+    let fares_snapshot = serde_json::to_value(&fares).unwrap_or_default();
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO experience_bookings
+        (user_id, experience_id, departure_id, booking_reference, participants, fares,
+         total_price, currency, special_requests)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id, status, created_at
+        "#,
+        user_id,
+        experience_id,
+        booking.departure_id,
+        reference,
+        participants,
+        fares_snapshot,
+        total_price.amount(),
+        total_price.currency().as_str(),
+        booking.special_requests
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(ExperienceBooking {
+        id: created.id,
+        reference,
+        experience_id,
+        departure_id: booking.departure_id,
+        departure_date: departure.departure_date,
+        departure_time: departure.departure_time,
+        participants,
+        fares,
+        total_price,
+        status: created.status,
+        created_at: created.created_at,
+    })
+}
+
+// Devuelve a la salida los lugares de una reserva cancelada
+pub async fn release_seats(
+    conn: &mut PgConnection,
+    departure_id: i32,
+    participants: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE experience_departures SET seats_booked = GREATEST(seats_booked - $2, 0) WHERE id = $1",
+        departure_id,
+        participants
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+// backend/src/services/guests.rs
+use chrono::{Duration, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::models::GuestBookingRequest;
+use crate::services::mailer;
+use crate::services::session::hash_token;
+
+// Vigencia del enlace para administrar una reserva sin cuenta
+pub const MAGIC_LINK_DAYS: i64 = 90;
+
+// Reserva a la que da acceso un enlace mágico vigente
+#[derive(Debug, Clone)]
+pub struct GuestAccess {
+    pub booking_id: i32,
+    pub guest_id: i32,
+    pub reference: String,
+}
+
+#[derive(Debug)]
+pub enum GuestError {
+    AccountExists,
+    InvalidLink,
+    EmailMismatch,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for GuestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GuestError::AccountExists => {
+                write!(f, "Ya existe una cuenta con este email. Inicia sesión para reservar.")
+            }
+            GuestError::InvalidLink => write!(f, "El enlace no es válido o ya expiró"),
+            GuestError::EmailMismatch => write!(f, "El enlace pertenece a reservas de otro email"),
+            GuestError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for GuestError {
+    fn from(e: sqlx::Error) -> Self {
+        GuestError::Database(e)
+    }
+}
+
+// Devuelve el huésped abierto de ese email o crea uno. Los emails con cuenta
+// deben iniciar sesión, así sus reservas no quedan repartidas en dos lugares.
+pub async fn find_or_create_guest(conn: &mut PgConnection, request: &GuestBookingRequest) -> Result<i32, GuestError> {
+    let email = request.email.trim().to_lowercase();
+
+    let registered = sqlx::query_scalar!("SELECT id FROM users WHERE LOWER(email) = $1", email)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if registered.is_some() {
+        return Err(GuestError::AccountExists);
+    }
+
+    // Un huésped existente no se sobrescribe: quien escriba un email ajeno no
+    // cambia el nombre ni el teléfono de las reservas del titular
+    let guest_id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO guests (first_name, last_name, email, phone)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT ((LOWER(email))) WHERE merged_into_user_id IS NULL
+        DO UPDATE SET updated_at = CURRENT_TIMESTAMP
+        RETURNING id
+        "#,
+        request.first_name.trim(),
+        request.last_name.trim(),
+        email,
+        request.phone.trim()
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(guest_id)
+}
+
+// Emite un enlace nuevo para la reserva y lo envía al email del huésped. El
+// token solo viaja por email: es la prueba de que el email es de quien reservó.
+pub async fn send_manage_link(conn: &mut PgConnection, booking_id: i32) -> Result<(), sqlx::Error> {
+    let booking = sqlx::query!(
+        r#"
+        SELECT b.booking_reference, b.check_in, b.check_out, h.name as hotel_name,
+               g.first_name, g.email
+        FROM bookings b
+        JOIN hotels h ON b.hotel_id = h.id
+        JOIN guests g ON b.guest_id = g.id
+        WHERE b.id = $1
+        "#,
+        booking_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    sqlx::query!(
+        "INSERT INTO booking_magic_links (booking_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+        booking_id,
+        hash_token(&token),
+        Utc::now() + Duration::days(MAGIC_LINK_DAYS)
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let frontend_url = std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    let body = format!(
+        "Hola {},\n\n\
+         Tu reserva {} en {} ({} al {}) quedó registrada.\n\n\
+         Desde este enlace puedes pagarla, consultarla o cancelarla:\n\
+         {}/reservas/gestionar?token={}\n\n\
+         Si creas una cuenta desde el enlace, tus reservas se agregarán a ella.\n\
+         El enlace vence en {} días.",
+        booking.first_name,
+        booking.booking_reference,
+        booking.hotel_name,
+        booking.check_in,
+        booking.check_out,
+        frontend_url.trim_end_matches('/'),
+        token,
+        MAGIC_LINK_DAYS
+    );
+
+    mailer::queue_email(
+        &mut *conn,
+        &booking.email,
+        &format!("Tu reserva {} en {}", booking.booking_reference, booking.hotel_name),
+        &body,
+    )
+    .await?;
+
+    Ok(())
+}
+
+// Reenvía el enlace si la referencia es de una reserva sin cuenta con ese email
+pub async fn resend_manage_link(conn: &mut PgConnection, reference: &str, email: &str) -> Result<bool, sqlx::Error> {
+    let booking_id = sqlx::query_scalar!(
+        r#"
+        SELECT b.id FROM bookings b
+        JOIN guests g ON b.guest_id = g.id
+        WHERE b.booking_reference = $1 AND LOWER(g.email) = LOWER($2)
+        "#,
+        reference.trim().to_uppercase(),
+        email.trim()
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match booking_id {
+        Some(booking_id) => {
+            send_manage_link(&mut *conn, booking_id).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+pub async fn find_access(conn: &mut PgConnection, token: &str) -> Result<Option<GuestAccess>, sqlx::Error> {
+    let access = sqlx::query!(
+        r#"
+        SELECT b.id, b.guest_id as "guest_id!", b.booking_reference
+        FROM booking_magic_links l
+        JOIN bookings b ON l.booking_id = b.id
+        WHERE l.token_hash = $1 AND l.expires_at > CURRENT_TIMESTAMP AND b.guest_id IS NOT NULL
+        "#,
+        hash_token(token)
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(access.map(|a| GuestAccess {
+        booking_id: a.id,
+        guest_id: a.guest_id,
+        reference: a.booking_reference,
+    }))
+}
+
+// Pasa a la cuenta del usuario todas las reservas del huésped al que apunta el
+// enlace. Registrarse con el mismo email no basta, porque el registro no
+// verifica el email; el enlace sí, ya que solo se envía a ese correo.
+// Devuelve cuántas reservas se agregaron a la cuenta.
+pub async fn merge_into_account(
+    conn: &mut PgConnection,
+    token: &str,
+    user_id: i32,
+    user_email: &str,
+) -> Result<u64, GuestError> {
+    let access = find_access(&mut *conn, token).await?.ok_or(GuestError::InvalidLink)?;
+
+    let guest = sqlx::query!(
+        "SELECT email, merged_into_user_id FROM guests WHERE id = $1 FOR UPDATE",
+        access.guest_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if !guest.email.eq_ignore_ascii_case(user_email.trim()) {
+        return Err(GuestError::EmailMismatch);
+    }
+    match guest.merged_into_user_id {
+        Some(merged) if merged == user_id => return Ok(0),
+        Some(_) => return Err(GuestError::InvalidLink),
+        None => {}
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE guests SET merged_into_user_id = $2, merged_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        access.guest_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let moved = sqlx::query!(
+        "UPDATE bookings SET user_id = $2, updated_at = CURRENT_TIMESTAMP WHERE guest_id = $1 AND user_id IS NULL",
+        access.guest_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    println!("🔗 [GUESTS] Huésped {} unido al usuario {} ({} reservas)", access.guest_id, user_id, moved.rows_affected());
+    Ok(moved.rows_affected())
+}
@@ -0,0 +1,107 @@
+// backend/src/services/inventory.rs
+use chrono::NaiveDate;
+use sqlx::PgConnection;
+
+// Resultado de intentar apartar habitaciones para un rango de noches
+#[derive(Debug)]
+pub enum RoomAllocation {
+    Reserved,
+    SoldOut { night: NaiveDate, rooms_left: i32 },
+}
+
+// Aparta `rooms` habitaciones en cada noche de [check_in, check_out).
+// Debe llamarse dentro de una transacción: el bloqueo de la fila del hotel
+// serializa las reservas concurrentes del mismo hotel hasta el COMMIT.
+pub async fn reserve_rooms(
+    conn: &mut PgConnection,
+    hotel_id: i32,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    rooms: i32,
+) -> Result<RoomAllocation, sqlx::Error> {
+    let hotel = sqlx::query!(
+        "SELECT total_rooms FROM hotels WHERE id = $1 FOR UPDATE",
+        hotel_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Asegurar que existe una fila por cada noche del rango
+    sqlx::query!(
+        r#"
+        INSERT INTO room_inventory (hotel_id, night, rooms_booked)
+        SELECT $1, night::date, 0
+        FROM generate_series($2::date, $3::date - 1, INTERVAL '1 day') AS night
+        ON CONFLICT (hotel_id, night) DO NOTHING
+        "#,
+        hotel_id,
+        check_in,
+        check_out
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let nights = sqlx::query!(
+        r#"
+        SELECT night, rooms_booked FROM room_inventory
+        WHERE hotel_id = $1 AND night >= $2 AND night < $3
+        ORDER BY night
+        FOR UPDATE
+        "#,
+        hotel_id,
+        check_in,
+        check_out
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for night in &nights {
+        let rooms_left = hotel.total_rooms - night.rooms_booked;
+        if rooms_left < rooms {
+            println!("⛔ [INVENTORY] Hotel {} sin cupo el {} (quedan {})", hotel_id, night.night, rooms_left);
+            return Ok(RoomAllocation::SoldOut {
+                night: night.night,
+                rooms_left: rooms_left.max(0),
+            });
+        }
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE room_inventory SET rooms_booked = rooms_booked + $4, updated_at = CURRENT_TIMESTAMP
+        WHERE hotel_id = $1 AND night >= $2 AND night < $3
+        "#,
+        hotel_id,
+        check_in,
+        check_out,
+        rooms
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(RoomAllocation::Reserved)
+}
+
+// Devuelve al inventario las habitaciones de una reserva cancelada
+pub async fn release_rooms(
+    conn: &mut PgConnection,
+    hotel_id: i32,
+    check_in: NaiveDate,
+    check_out: NaiveDate,
+    rooms: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE room_inventory SET rooms_booked = GREATEST(rooms_booked - $4, 0), updated_at = CURRENT_TIMESTAMP
+        WHERE hotel_id = $1 AND night >= $2 AND night < $3
+        "#,
+        hotel_id,
+        check_in,
+        check_out,
+        rooms
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+// backend/src/services/mailer.rs
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use sqlx::{PgConnection, PgPool};
+
+// Intentos de entrega antes de marcar un correo como fallido
+const MAX_ATTEMPTS: i32 = 5;
+// Cada cuánto revisa el worker la bandeja de salida
+const POLL_INTERVAL_SECS: u64 = 10;
+const BATCH_SIZE: i64 = 20;
+
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub id: i32,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Servicio que entrega los correos (SMTP, API de un proveedor, etc.)
+pub trait Mailer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> BoxFuture<'a, Result<(), String>>;
+}
+
+// Mailer de desarrollo: escribe el correo en la consola en lugar de enviarlo
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, email: &'a OutgoingEmail) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            println!("📧 [MAILER] Para: {} | {}\n{}", email.recipient, email.subject, email.body);
+            Ok(())
+        })
+    }
+}
+
+// Deja el correo en la bandeja de salida. Se recibe la conexión para que el
+// correo solo exista si la transacción que lo origina se confirma.
+pub async fn queue_email(
+    conn: &mut PgConnection,
+    recipient: &str,
+    subject: &str,
+    body: &str,
+) -> Result<i32, sqlx::Error> {
+    let id = sqlx::query_scalar!(
+        "INSERT INTO email_outbox (recipient, subject, body) VALUES ($1, $2, $3) RETURNING id",
+        recipient,
+        subject,
+        body
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    println!("📮 [MAILER] Correo {} en cola para {}", id, recipient);
+    Ok(id)
+}
+
+// Entrega un lote de correos pendientes. SKIP LOCKED permite que varias
+// instancias del servidor procesen la bandeja sin enviar dos veces el mismo.
+pub async fn deliver_pending(pool: &PgPool, mailer: &dyn Mailer) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let pending = sqlx::query_as!(
+        OutgoingEmail,
+        r#"
+        SELECT id, recipient, subject, body FROM email_outbox
+        WHERE status = 'pending'
+        ORDER BY id
+        LIMIT $1
+        FOR UPDATE SKIP LOCKED
+        "#,
+        BATCH_SIZE
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for email in &pending {
+        match mailer.send(email).await {
+            Ok(()) => {
+                sqlx::query!(
+                    r#"
+                    UPDATE email_outbox SET status = 'sent', attempts = attempts + 1,
+                        last_error = NULL, sent_at = CURRENT_TIMESTAMP
+                    WHERE id = $1
+                    "#,
+                    email.id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            Err(error) => {
+                println!("⚠️ [MAILER] No se pudo entregar el correo {} con '{}': {}", email.id, mailer.name(), error);
+                sqlx::query!(
+                    r#"
+                    UPDATE email_outbox SET attempts = attempts + 1, last_error = $2,
+                        status = CASE WHEN attempts + 1 >= $3 THEN 'failed' ELSE 'pending' END
+                    WHERE id = $1
+                    "#,
+                    email.id,
+                    error,
+                    MAX_ATTEMPTS
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+    Ok(pending.len())
+}
+
+// Revisa la bandeja de salida en segundo plano mientras viva el servidor
+pub fn spawn_outbox_worker(pool: PgPool, mailer: Arc<dyn Mailer>) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match deliver_pending(&pool, mailer.as_ref()).await {
+                Ok(0) => {}
+                Ok(sent) => println!("📬 [MAILER] {} correos procesados", sent),
+                Err(e) => println!("💥 [MAILER] Error procesando la bandeja de salida: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,56 @@
+// backend/src/services/notifications.rs
+use sqlx::PgConnection;
+
+use crate::services::mailer;
+
+// Registra una notificación para el usuario. Se recibe la conexión para que
+// la notificación se guarde en la misma transacción que el cambio que la origina.
+pub async fn notify(
+    conn: &mut PgConnection,
+    user_id: i32,
+    kind: &str,
+    title: &str,
+    message: &str,
+    data: Option<serde_json::Value>,
+) -> Result<i32, sqlx::Error> {
+    let notification = sqlx::query!(
+        r#"
+        INSERT INTO notifications (user_id, kind, title, message, data)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+        user_id,
+        kind,
+        title,
+        message,
+        data
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    println!("🔔 [NOTIFY] Notificación '{}' para usuario {}", kind, user_id);
+    Ok(notification.id)
+}
+
+// Notifica al titular de una reserva. Los huéspedes sin cuenta no tienen
+// bandeja de notificaciones, así que a ellos se les escribe por email.
+pub async fn notify_booking_holder(
+    conn: &mut PgConnection,
+    user_id: Option<i32>,
+    guest_id: Option<i32>,
+    kind: &str,
+    title: &str,
+    message: &str,
+    data: Option<serde_json::Value>,
+) -> Result<(), sqlx::Error> {
+    if let Some(user_id) = user_id {
+        notify(&mut *conn, user_id, kind, title, message, data).await?;
+    } else if let Some(guest_id) = guest_id {
+        let email = sqlx::query_scalar!("SELECT email FROM guests WHERE id = $1", guest_id)
+            .fetch_one(&mut *conn)
+            .await?;
+        mailer::queue_email(&mut *conn, &email, title, message).await?;
+    }
+
+    Ok(())
+}
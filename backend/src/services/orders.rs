@@ -0,0 +1,228 @@
+// backend/src/services/orders.rs
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::{CreateOrderRequest, OrderStatus};
+use crate::money::{Currency, Money};
+use crate::services::references;
+
+pub const ORDER_PREFIX: &str = "MO";
+
+// Máximo de unidades de un mismo platillo por pedido
+const MAX_ITEM_QUANTITY: i32 = 20;
+
+// Platillo con precio calculado en el servidor
+#[derive(Debug, Clone, Serialize)]
+pub struct PricedOrderItem {
+    pub menu_item_id: i32,
+    pub name: String,
+    pub unit_price: Money,
+    pub quantity: i32,
+    pub subtotal: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlacedOrder {
+    pub id: i32,
+    pub reference: String,
+    pub restaurant_id: i32,
+    pub reservation_id: Option<i32>,
+    pub status: OrderStatus,
+    pub items: Vec<PricedOrderItem>,
+    pub total_price: Money,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum OrderError {
+    RestaurantUnavailable,
+    InvalidReservation,
+    ItemNotFound(i32),
+    ItemUnavailable(i32),
+    InvalidQuantity(i32),
+    Duplicated(i32),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::RestaurantUnavailable => write!(f, "Restaurante no encontrado o no disponible"),
+            OrderError::InvalidReservation => write!(f, "La reservación no existe, no es tuya o no es de este restaurante"),
+            OrderError::ItemNotFound(id) => write!(f, "El platillo {} no existe en el menú de este restaurante", id),
+            OrderError::ItemUnavailable(id) => write!(f, "El platillo {} está agotado", id),
+            OrderError::InvalidQuantity(id) => write!(
+                f,
+                "La cantidad del platillo {} debe estar entre 1 y {}",
+                id, MAX_ITEM_QUANTITY
+            ),
+            OrderError::Duplicated(id) => write!(f, "El platillo {} aparece más de una vez", id),
+            OrderError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for OrderError {
+    fn from(e: sqlx::Error) -> Self {
+        OrderError::Database(e)
+    }
+}
+
+// Crea un pedido con sus platillos y su primer registro de historial.
+// Recibe una conexión para que el llamador decida la transacción.
+pub async fn place_order(
+    conn: &mut PgConnection,
+    user_id: i32,
+    restaurant_id: i32,
+    order: &CreateOrderRequest,
+) -> Result<PlacedOrder, OrderError> {
+    let restaurant = sqlx::query!(
+        "SELECT currency FROM restaurants WHERE id = $1 AND status = 'approved'",
+        restaurant_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(OrderError::RestaurantUnavailable)?;
+
+    if let Some(reservation_id) = order.reservation_id {
+        let reservation = sqlx::query!(
+            r#"
+            SELECT id FROM restaurant_reservations
+            WHERE id = $1 AND user_id = $2 AND restaurant_id = $3 AND status = 'confirmed'
+            "#,
+            reservation_id,
+            user_id,
+            restaurant_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if reservation.is_none() {
+            return Err(OrderError::InvalidReservation);
+        }
+    }
+
+    let ids: Vec<i32> = order.items.iter().map(|i| i.menu_item_id).collect();
+    let menu = sqlx::query!(
+        r#"
+        SELECT id, name, price, currency, is_available
+        FROM menu_items
+        WHERE restaurant_id = $1 AND id = ANY($2) AND is_active
+        "#,
+        restaurant_id,
+        &ids[..]
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut items: Vec<PricedOrderItem> = Vec::with_capacity(order.items.len());
+    for selection in &order.items {
+        if items.iter().any(|i| i.menu_item_id == selection.menu_item_id) {
+            return Err(OrderError::Duplicated(selection.menu_item_id));
+        }
+
+        let dish = menu
+            .iter()
+            .find(|m| m.id == selection.menu_item_id)
+            .ok_or(OrderError::ItemNotFound(selection.menu_item_id))?;
+
+        if !dish.is_available {
+            return Err(OrderError::ItemUnavailable(dish.id));
+        }
+
+        if selection.quantity < 1 || selection.quantity > MAX_ITEM_QUANTITY {
+            return Err(OrderError::InvalidQuantity(dish.id));
+        }
+
+        let unit_price = Money::from_db(dish.price.clone(), &dish.currency);
+        items.push(PricedOrderItem {
+            menu_item_id: dish.id,
+            name: dish.name.clone(),
+            subtotal: unit_price.times(selection.quantity as i64),
+            unit_price,
+            quantity: selection.quantity,
+        });
+    }
+
+    let currency = Currency::from_code(&restaurant.currency).unwrap_or(Currency::MXN);
+    let total_price = items
+        .iter()
+        .fold(Money::zero(currency), |total, item| total + item.subtotal.clone());
+
+    let reference = references::issue_reference(&mut *conn, ORDER_PREFIX, "restaurant_order").await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO restaurant_orders
+        (user_id, restaurant_id, reservation_id, order_reference, status, total_price, currency, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, created_at
+        "#,
+        user_id,
+        restaurant_id,
+        order.reservation_id,
+        reference,
+        OrderStatus::Placed.as_str(),
+        total_price.amount(),
+        total_price.currency().as_str(),
+        order.notes
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for item in &items {
+        sqlx::query!(
+            r#"
+            INSERT INTO restaurant_order_items (order_id, menu_item_id, name, unit_price, quantity, subtotal)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            created.id,
+            item.menu_item_id,
+            item.name,
+            item.unit_price.amount(),
+            item.quantity,
+            item.subtotal.amount()
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    record_status_change(&mut *conn, created.id, None, OrderStatus::Placed, user_id).await?;
+
+    Ok(PlacedOrder {
+        id: created.id,
+        reference,
+        restaurant_id,
+        reservation_id: order.reservation_id,
+        status: OrderStatus::Placed,
+        items,
+        total_price,
+        notes: order.notes.clone(),
+        created_at: created.created_at,
+    })
+}
+
+pub async fn record_status_change(
+    conn: &mut PgConnection,
+    order_id: i32,
+    from: Option<OrderStatus>,
+    to: OrderStatus,
+    changed_by: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO restaurant_order_status_history (order_id, from_status, to_status, changed_by)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        order_id,
+        from.map(|s| s.as_str()),
+        to.as_str(),
+        changed_by
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// backend/src/services/password.rs
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+
+// Prefijo de los hashes antiguos generados con DefaultHasher
+const LEGACY_PREFIX: &str = "hash_";
+
+// Resultado de verificar una contraseña contra el hash almacenado
+#[derive(Debug, PartialEq)]
+pub enum PasswordCheck {
+    Valid,
+    // La contraseña es correcta pero el hash debe regenerarse (formato antiguo o parámetros viejos)
+    ValidNeedsRehash,
+    Invalid,
+}
+
+fn argon2() -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::default())
+}
+
+// Genera un hash Argon2id con sal aleatoria en formato PHC ($argon2id$v=19$...)
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = argon2().hash_password(password.as_bytes(), &salt)?;
+    Ok(hash.to_string())
+}
+
+pub fn verify_password(password: &str, stored_hash: &str) -> PasswordCheck {
+    if stored_hash.starts_with(LEGACY_PREFIX) {
+        return if legacy_hash(password) == stored_hash {
+            PasswordCheck::ValidNeedsRehash
+        } else {
+            PasswordCheck::Invalid
+        };
+    }
+
+    let parsed = match PasswordHash::new(stored_hash) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            println!("⚠️ [PASSWORD] Hash almacenado con formato desconocido: {}", e);
+            return PasswordCheck::Invalid;
+        }
+    };
+
+    if argon2().verify_password(password.as_bytes(), &parsed).is_err() {
+        return PasswordCheck::Invalid;
+    }
+
+    if is_outdated(&parsed) {
+        PasswordCheck::ValidNeedsRehash
+    } else {
+        PasswordCheck::Valid
+    }
+}
+
+// Un hash está desactualizado si no es Argon2id o usa parámetros distintos a los actuales
+fn is_outdated(parsed: &PasswordHash) -> bool {
+    if parsed.algorithm != argon2::ARGON2ID_IDENT {
+        return true;
+    }
+
+    let current = Params::default();
+    match Params::try_from(parsed) {
+        Ok(params) => {
+            params.m_cost() != current.m_cost()
+                || params.t_cost() != current.t_cost()
+                || params.p_cost() != current.p_cost()
+        }
+        Err(_) => true,
+    }
+}
+
+// Hash antiguo (sin sal) usado antes de Argon2. Solo se conserva para migrar
+// las cuentas existentes en su siguiente login; nunca para generar hashes nuevos.
+fn legacy_hash(password: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    password.hash(&mut hasher);
+    format!("{}{}", LEGACY_PREFIX, hasher.finish())
+}
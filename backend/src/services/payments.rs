@@ -0,0 +1,722 @@
+// backend/src/services/payments.rs
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::models::BookingStatus;
+use crate::money::Money;
+use crate::services::bookings::BookingHolder;
+use crate::services::notifications;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Datos que recibe el proveedor para crear un intento de cobro
+#[derive(Debug, Clone)]
+pub struct IntentRequest {
+    // Referencia de la reserva o del viaje que se cobra
+    pub reference: String,
+    pub amount: Money,
+}
+
+// Respuesta del proveedor al crear el intento
+#[derive(Debug, Clone)]
+pub struct ProviderIntent {
+    pub id: String,
+    // Lo usa el frontend para completar el pago con el SDK del proveedor
+    pub client_secret: Option<String>,
+}
+
+// Devolución (total o parcial) de un cobro ya exitoso
+#[derive(Debug, Clone)]
+pub struct RefundRequest {
+    pub provider_intent_id: String,
+    pub reference: String,
+    pub amount: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProviderRefund {
+    pub id: String,
+}
+
+// Resultado de un cobro que reporta el proveedor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntentOutcome {
+    Succeeded,
+    Failed,
+}
+
+impl IntentOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentOutcome::Succeeded => "succeeded",
+            IntentOutcome::Failed => "failed",
+        }
+    }
+}
+
+// Evento de webhook ya verificado y traducido al formato común
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub id: String,
+    pub event_type: String,
+    pub intent_id: String,
+    // None para eventos que no cambian el estado del pago
+    pub outcome: Option<IntentOutcome>,
+    pub failure_reason: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug)]
+pub enum PaymentError {
+    InvalidSignature,
+    MalformedEvent(String),
+    BookingNotFound,
+    BookingCancelled,
+    BookingInTrip,
+    TripNotFound,
+    TripCancelled,
+    AlreadyPaid,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaymentError::InvalidSignature => write!(f, "Firma del webhook inválida"),
+            PaymentError::MalformedEvent(detail) => write!(f, "Evento de pago inválido: {}", detail),
+            PaymentError::BookingNotFound => write!(f, "Reserva no encontrada"),
+            PaymentError::BookingCancelled => write!(f, "La reserva ya no está activa"),
+            PaymentError::BookingInTrip => {
+                write!(f, "La reserva es parte de un viaje; el pago se hace desde el viaje")
+            }
+            PaymentError::TripNotFound => write!(f, "Viaje no encontrado"),
+            PaymentError::TripCancelled => write!(f, "El viaje ya no está activo"),
+            PaymentError::AlreadyPaid => write!(f, "El pago ya se completó"),
+            PaymentError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for PaymentError {
+    fn from(e: sqlx::Error) -> Self {
+        PaymentError::Database(e)
+    }
+}
+
+// Un proveedor de pagos (pasarela). Cada proveedor real implementa la creación
+// del intento contra su API y la verificación de la firma de sus webhooks.
+pub trait PaymentProvider: Send + Sync {
+    // Identificador usado en la base de datos y en la ruta del webhook
+    fn name(&self) -> &'static str;
+
+    // Encabezado HTTP en el que el proveedor manda la firma del webhook
+    fn signature_header(&self) -> &'static str;
+
+    fn create_intent<'a>(&'a self, request: &'a IntentRequest) -> BoxFuture<'a, Result<ProviderIntent, PaymentError>>;
+
+    fn refund<'a>(&'a self, request: &'a RefundRequest) -> BoxFuture<'a, Result<ProviderRefund, PaymentError>>;
+
+    // Verifica la firma sobre el cuerpo sin modificar y traduce el evento
+    fn parse_webhook(&self, payload: &[u8], signature: Option<&str>) -> Result<WebhookEvent, PaymentError>;
+}
+
+// === PROVEEDOR SIMULADO ===
+
+// Pasarela local para desarrollo sin conexión y pruebas. No cobra nada: los
+// pagos se confirman enviando a /api/payments/webhooks/mock un evento como
+// {"id": "evt_1", "type": "payment_intent.succeeded", "data": {"intent_id": "mock_pi_..."}}
+// firmado en el encabezado X-Mock-Signature con hex(HMAC-SHA256(PAYMENT_MOCK_SECRET, cuerpo)),
+// p. ej. `printf '%s' "$BODY" | openssl dgst -sha256 -hmac "$PAYMENT_MOCK_SECRET"`.
+pub struct MockProvider {
+    secret: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct MockEvent {
+    id: String,
+    #[serde(rename = "type")]
+    event_type: String,
+    data: MockEventData,
+}
+
+#[derive(Deserialize)]
+struct MockEventData {
+    intent_id: String,
+    failure_reason: Option<String>,
+}
+
+impl MockProvider {
+    pub fn new(secret: &str) -> MockProvider {
+        MockProvider { secret: secret.as_bytes().to_vec() }
+    }
+
+    pub fn from_env() -> MockProvider {
+        let secret = std::env::var("PAYMENT_MOCK_SECRET").unwrap_or_else(|_| {
+            println!("⚠️ [PAYMENTS] PAYMENT_MOCK_SECRET no encontrado en .env, usando valor por defecto");
+            "clave_local_de_pagos_simulados".to_string()
+        });
+        MockProvider::new(&secret)
+    }
+}
+
+impl PaymentProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn signature_header(&self) -> &'static str {
+        "X-Mock-Signature"
+    }
+
+    fn create_intent<'a>(&'a self, request: &'a IntentRequest) -> BoxFuture<'a, Result<ProviderIntent, PaymentError>> {
+        Box::pin(async move {
+            let id = format!("mock_pi_{}", Uuid::new_v4().simple());
+            println!(
+                "🧪 [PAYMENTS] Intento simulado {} para {} por {} {}",
+                id,
+                request.reference,
+                request.amount.amount(),
+                request.amount.currency().as_str()
+            );
+            Ok(ProviderIntent {
+                client_secret: Some(format!("{}_secret_{}", id, Uuid::new_v4().simple())),
+                id,
+            })
+        })
+    }
+
+    fn refund<'a>(&'a self, request: &'a RefundRequest) -> BoxFuture<'a, Result<ProviderRefund, PaymentError>> {
+        Box::pin(async move {
+            let id = format!("mock_re_{}", Uuid::new_v4().simple());
+            println!(
+                "🧪 [PAYMENTS] Reembolso simulado {} de {} {} sobre {} ({})",
+                id,
+                request.amount.amount(),
+                request.amount.currency().as_str(),
+                request.provider_intent_id,
+                request.reference
+            );
+            Ok(ProviderRefund { id })
+        })
+    }
+
+    fn parse_webhook(&self, payload: &[u8], signature: Option<&str>) -> Result<WebhookEvent, PaymentError> {
+        let signature = signature
+            .and_then(|s| hex::decode(s.trim()).ok())
+            .ok_or(PaymentError::InvalidSignature)?;
+
+        // Comparación en tiempo constante
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC acepta llaves de cualquier tamaño");
+        mac.update(payload);
+        mac.verify_slice(&signature).map_err(|_| PaymentError::InvalidSignature)?;
+
+        let event: MockEvent =
+            serde_json::from_slice(payload).map_err(|e| PaymentError::MalformedEvent(e.to_string()))?;
+        let outcome = match event.event_type.as_str() {
+            "payment_intent.succeeded" => Some(IntentOutcome::Succeeded),
+            "payment_intent.payment_failed" => Some(IntentOutcome::Failed),
+            _ => None,
+        };
+
+        Ok(WebhookEvent {
+            id: event.id,
+            event_type: event.event_type,
+            intent_id: event.data.intent_id,
+            outcome,
+            failure_reason: event.data.failure_reason,
+            payload: serde_json::from_slice(payload).unwrap_or(serde_json::Value::Null),
+        })
+    }
+}
+
+// === REGISTRO DE PROVEEDORES ===
+
+// Proveedores disponibles. Se crea una sola vez al iniciar el servidor; los
+// intentos nuevos usan el proveedor indicado en PAYMENT_PROVIDER.
+pub struct PaymentProviders {
+    default: &'static str,
+    providers: Vec<Box<dyn PaymentProvider>>,
+}
+
+impl PaymentProviders {
+    pub fn from_env() -> PaymentProviders {
+        let providers: Vec<Box<dyn PaymentProvider>> = vec![Box::new(MockProvider::from_env())];
+
+        let requested = std::env::var("PAYMENT_PROVIDER").unwrap_or_else(|_| "mock".to_string());
+        let default = match providers.iter().find(|p| p.name() == requested) {
+            Some(provider) => provider.name(),
+            None => {
+                println!("⚠️ [PAYMENTS] Proveedor '{}' no disponible, usando 'mock'", requested);
+                "mock"
+            }
+        };
+
+        PaymentProviders { default, providers }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn PaymentProvider> {
+        self.providers.iter().find(|p| p.name() == name).map(|p| p.as_ref())
+    }
+
+    pub fn default_provider(&self) -> &dyn PaymentProvider {
+        self.get(self.default).expect("el proveedor por defecto está registrado")
+    }
+}
+
+// === INTENTOS DE COBRO ===
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaymentIntent {
+    pub id: i32,
+    // Solo uno de los dos: el intento cobra una reserva suelta o un viaje completo
+    pub booking_id: Option<i32>,
+    pub trip_id: Option<i32>,
+    pub provider: String,
+    pub provider_intent_id: String,
+    pub amount: Money,
+    pub client_secret: Option<String>,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// Crea (o reutiliza) el intento de cobro de una reserva del titular. La
+// reserva se bloquea para que dos solicitudes simultáneas no abran dos
+// intentos; si ya hay uno abierto por el mismo monto se devuelve ese.
+pub async fn create_booking_intent(
+    conn: &mut PgConnection,
+    provider: &dyn PaymentProvider,
+    booking_id: i32,
+    holder: BookingHolder,
+) -> Result<(PaymentIntent, bool), PaymentError> {
+    let booking = sqlx::query!(
+        r#"
+        SELECT booking_reference, total_price, currency, status, payment_status, trip_id
+        FROM bookings WHERE id = $1 AND (user_id = $2 OR guest_id = $3)
+        FOR UPDATE
+        "#,
+        booking_id,
+        holder.user_id(),
+        holder.guest_id()
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(PaymentError::BookingNotFound)?;
+
+    // Las reservas de un viaje se cobran juntas con create_trip_intent
+    if booking.trip_id.is_some() {
+        return Err(PaymentError::BookingInTrip);
+    }
+    if matches!(
+        BookingStatus::from_db(&booking.status),
+        Some(BookingStatus::Cancelled | BookingStatus::Rejected | BookingStatus::NoShow)
+    ) {
+        return Err(PaymentError::BookingCancelled);
+    }
+    if booking.payment_status == "paid" {
+        return Err(PaymentError::AlreadyPaid);
+    }
+
+    let amount = Money::from_db(booking.total_price, &booking.currency);
+
+    let open = sqlx::query!(
+        r#"
+        SELECT id, provider_intent_id, client_secret, status, created_at
+        FROM payment_intents
+        WHERE booking_id = $1 AND provider = $2 AND status = 'requires_payment'
+          AND amount = $3 AND currency = $4
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+        booking_id,
+        provider.name(),
+        amount.amount(),
+        amount.currency().as_str()
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(open) = open {
+        return Ok((
+            PaymentIntent {
+                id: open.id,
+                booking_id: Some(booking_id),
+                trip_id: None,
+                provider: provider.name().to_string(),
+                provider_intent_id: open.provider_intent_id,
+                amount,
+                client_secret: open.client_secret,
+                status: open.status,
+                created_at: open.created_at,
+            },
+            true,
+        ));
+    }
+
+    let created = provider
+        .create_intent(&IntentRequest {
+            reference: booking.booking_reference,
+            amount: amount.clone(),
+        })
+        .await?;
+
+    let intent = sqlx::query!(
+        r#"
+        INSERT INTO payment_intents (booking_id, provider, provider_intent_id, amount, currency, client_secret)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, status, created_at
+        "#,
+        booking_id,
+        provider.name(),
+        created.id,
+        amount.amount(),
+        amount.currency().as_str(),
+        created.client_secret
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((
+        PaymentIntent {
+            id: intent.id,
+            booking_id: Some(booking_id),
+            trip_id: None,
+            provider: provider.name().to_string(),
+            provider_intent_id: created.id,
+            amount,
+            client_secret: created.client_secret,
+            status: intent.status,
+            created_at: intent.created_at,
+        },
+        false,
+    ))
+}
+
+// Crea (o reutiliza) el intento que cobra el viaje completo en un solo pago,
+// con las mismas reglas que create_booking_intent
+pub async fn create_trip_intent(
+    conn: &mut PgConnection,
+    provider: &dyn PaymentProvider,
+    trip_id: i32,
+    user_id: i32,
+) -> Result<(PaymentIntent, bool), PaymentError> {
+    let trip = sqlx::query!(
+        r#"
+        SELECT trip_reference, total_price, currency, status, payment_status
+        FROM trips WHERE id = $1 AND user_id = $2
+        FOR UPDATE
+        "#,
+        trip_id,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(PaymentError::TripNotFound)?;
+
+    if trip.status == "cancelled" {
+        return Err(PaymentError::TripCancelled);
+    }
+    if trip.payment_status == "paid" {
+        return Err(PaymentError::AlreadyPaid);
+    }
+
+    let amount = Money::from_db(trip.total_price, &trip.currency);
+
+    let open = sqlx::query!(
+        r#"
+        SELECT id, provider_intent_id, client_secret, status, created_at
+        FROM payment_intents
+        WHERE trip_id = $1 AND provider = $2 AND status = 'requires_payment'
+          AND amount = $3 AND currency = $4
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+        trip_id,
+        provider.name(),
+        amount.amount(),
+        amount.currency().as_str()
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(open) = open {
+        return Ok((
+            PaymentIntent {
+                id: open.id,
+                booking_id: None,
+                trip_id: Some(trip_id),
+                provider: provider.name().to_string(),
+                provider_intent_id: open.provider_intent_id,
+                amount,
+                client_secret: open.client_secret,
+                status: open.status,
+                created_at: open.created_at,
+            },
+            true,
+        ));
+    }
+
+    let created = provider
+        .create_intent(&IntentRequest {
+            reference: trip.trip_reference,
+            amount: amount.clone(),
+        })
+        .await?;
+
+    let intent = sqlx::query!(
+        r#"
+        INSERT INTO payment_intents (trip_id, provider, provider_intent_id, amount, currency, client_secret)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, status, created_at
+        "#,
+        trip_id,
+        provider.name(),
+        created.id,
+        amount.amount(),
+        amount.currency().as_str(),
+        created.client_secret
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((
+        PaymentIntent {
+            id: intent.id,
+            booking_id: None,
+            trip_id: Some(trip_id),
+            provider: provider.name().to_string(),
+            provider_intent_id: created.id,
+            amount,
+            client_secret: created.client_secret,
+            status: intent.status,
+            created_at: intent.created_at,
+        },
+        false,
+    ))
+}
+
+// === WEBHOOKS ===
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WebhookOutcome {
+    // El evento ya se había recibido antes
+    Duplicate,
+    // Evento registrado sin efecto (tipo no manejado, intento desconocido o ya resuelto)
+    Ignored,
+    Applied { booking_id: i32, payment_status: &'static str },
+    TripApplied { trip_id: i32, payment_status: &'static str },
+}
+
+// Aplica un evento verificado. Debe llamarse dentro de una transacción: la
+// inserción del evento con llave única hace que una entrega repetida (incluso
+// simultánea) termine como Duplicate sin volver a tocar la reserva.
+pub async fn apply_webhook_event(
+    conn: &mut PgConnection,
+    provider: &str,
+    event: &WebhookEvent,
+) -> Result<WebhookOutcome, PaymentError> {
+    let recorded = sqlx::query_scalar!(
+        r#"
+        INSERT INTO payment_webhook_events (provider, event_id, event_type, payload)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (provider, event_id) DO NOTHING
+        RETURNING id
+        "#,
+        provider,
+        event.id,
+        event.event_type,
+        event.payload
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let event_row_id = match recorded {
+        Some(id) => id,
+        None => return Ok(WebhookOutcome::Duplicate),
+    };
+
+    let outcome = match event.outcome {
+        Some(outcome) => outcome,
+        None => return Ok(WebhookOutcome::Ignored),
+    };
+
+    let intent = sqlx::query!(
+        r#"
+        SELECT id, booking_id, trip_id, status FROM payment_intents
+        WHERE provider = $1 AND provider_intent_id = $2
+        FOR UPDATE
+        "#,
+        provider,
+        event.intent_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let intent = match intent {
+        Some(intent) => intent,
+        None => {
+            println!("⚠️ [PAYMENTS] Evento {} para intento desconocido {}", event.id, event.intent_id);
+            return Ok(WebhookOutcome::Ignored);
+        }
+    };
+
+    sqlx::query!(
+        "UPDATE payment_webhook_events SET payment_intent_id = $2 WHERE id = $1",
+        event_row_id,
+        intent.id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // Un cobro exitoso es definitivo; uno fallido todavía puede reintentarse
+    if intent.status == "succeeded" {
+        return Ok(WebhookOutcome::Ignored);
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE payment_intents SET status = $2, failure_reason = $3, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        intent.id,
+        outcome.as_str(),
+        event.failure_reason
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let payment_status = match outcome {
+        IntentOutcome::Succeeded => "paid",
+        IntentOutcome::Failed => "failed",
+    };
+
+    let booking_id = match (intent.booking_id, intent.trip_id) {
+        (_, Some(trip_id)) => return settle_trip(&mut *conn, trip_id, outcome).await,
+        (Some(booking_id), None) => booking_id,
+        (None, None) => return Ok(WebhookOutcome::Ignored),
+    };
+
+    // Si otro intento ya pagó (o se reembolsó) la reserva, un evento tardío no la regresa
+    let booking = sqlx::query!(
+        r#"
+        UPDATE bookings SET payment_status = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND payment_status IN ('pending', 'failed')
+        RETURNING user_id, guest_id, booking_reference
+        "#,
+        booking_id,
+        payment_status
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let booking = match booking {
+        Some(booking) => booking,
+        None => return Ok(WebhookOutcome::Ignored),
+    };
+
+    let (kind, title, message) = match outcome {
+        IntentOutcome::Succeeded => (
+            "payment_received",
+            "Pago recibido",
+            format!("Recibimos el pago de tu reserva {}.", booking.booking_reference),
+        ),
+        IntentOutcome::Failed => (
+            "payment_failed",
+            "No se pudo completar el pago",
+            format!(
+                "El pago de tu reserva {} fue rechazado. Puedes intentarlo de nuevo.",
+                booking.booking_reference
+            ),
+        ),
+    };
+    notifications::notify_booking_holder(
+        &mut *conn,
+        booking.user_id,
+        booking.guest_id,
+        kind,
+        title,
+        &message,
+        Some(json!({ "booking_id": booking_id, "payment_status": payment_status })),
+    )
+    .await?;
+
+    Ok(WebhookOutcome::Applied { booking_id, payment_status })
+}
+
+// Un viaje se cobra completo: el pago exitoso confirma el viaje y marca como
+// pagadas todas sus reservas de hotel; si falla, todo sigue pendiente y el
+// viajero puede reintentar. Las experiencias, mesas y artesanías no llevan
+// estado de pago propio: dependen del estado del viaje.
+async fn settle_trip(
+    conn: &mut PgConnection,
+    trip_id: i32,
+    outcome: IntentOutcome,
+) -> Result<WebhookOutcome, PaymentError> {
+    let payment_status = match outcome {
+        IntentOutcome::Succeeded => "paid",
+        IntentOutcome::Failed => "failed",
+    };
+
+    let trip = sqlx::query!(
+        r#"
+        UPDATE trips SET payment_status = $2::varchar,
+            status = CASE WHEN $2::varchar = 'paid' THEN 'confirmed' ELSE status END,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status = 'pending' AND payment_status IN ('pending', 'failed')
+        RETURNING user_id, trip_reference
+        "#,
+        trip_id,
+        payment_status
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let trip = match trip {
+        Some(trip) => trip,
+        None => return Ok(WebhookOutcome::Ignored),
+    };
+
+    sqlx::query!(
+        r#"
+        UPDATE bookings SET payment_status = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE trip_id = $1 AND payment_status IN ('pending', 'failed')
+          AND status NOT IN ('cancelled', 'rejected')
+        "#,
+        trip_id,
+        payment_status
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let (kind, title, message) = match outcome {
+        IntentOutcome::Succeeded => (
+            "payment_received",
+            "Viaje confirmado",
+            format!("Recibimos el pago de tu viaje {}. Todas sus reservas quedaron pagadas.", trip.trip_reference),
+        ),
+        IntentOutcome::Failed => (
+            "payment_failed",
+            "No se pudo completar el pago",
+            format!(
+                "El pago de tu viaje {} fue rechazado. Puedes intentarlo de nuevo.",
+                trip.trip_reference
+            ),
+        ),
+    };
+    notifications::notify(
+        &mut *conn,
+        trip.user_id,
+        kind,
+        title,
+        &message,
+        Some(json!({ "trip_id": trip_id, "payment_status": payment_status })),
+    )
+    .await?;
+
+    Ok(WebhookOutcome::TripApplied { trip_id, payment_status })
+}
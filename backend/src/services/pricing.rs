@@ -0,0 +1,420 @@
+// backend/src/services/pricing.rs
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool};
+
+use crate::models::AddonSelection;
+use crate::money::Money;
+use crate::services::addons::{self, AddonError, PricedAddon};
+
+// Comisión usada si platform_settings no tiene un valor válido
+const DEFAULT_COMMISSION_RATE: &str = "0.20";
+
+// Datos de la estancia a cotizar
+pub struct StayRequest<'a> {
+    pub hotel_id: i32,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+    pub guests: i32,
+    pub rooms: i32,
+    pub addons: &'a [AddonSelection],
+}
+
+// Ajuste aplicado por una regla de precios
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteLine {
+    pub rule: String,
+    pub description: String,
+    pub amount: Money,
+}
+
+// Tarifa de una noche por habitación
+#[derive(Debug, Clone, Serialize)]
+pub struct NightRate {
+    pub date: NaiveDate,
+    pub base_rate: Money,
+    pub adjustments: Vec<QuoteLine>,
+    pub rate: Money,
+}
+
+// Cotización desglosada de una estancia
+#[derive(Debug, Clone, Serialize)]
+pub struct StayQuote {
+    pub hotel_id: i32,
+    pub check_in: NaiveDate,
+    pub check_out: NaiveDate,
+    pub nights: i64,
+    pub rooms: i32,
+    pub guests: i32,
+    pub nightly: Vec<NightRate>,
+    pub room_subtotal: Money,
+    pub discounts: Vec<QuoteLine>,
+    pub addons: Vec<PricedAddon>,
+    pub addons_total: Money,
+    pub total: Money,
+    // La comisión sale del total; el huésped paga `total` y el hotel recibe `owner_payout`
+    pub commission_rate: String,
+    pub commission: Money,
+    pub owner_payout: Money,
+}
+
+#[derive(Debug)]
+pub enum PricingError {
+    Addon(AddonError),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for PricingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PricingError::Addon(e) => write!(f, "{}", e),
+            PricingError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for PricingError {
+    fn from(e: sqlx::Error) -> Self {
+        PricingError::Database(e)
+    }
+}
+
+impl From<AddonError> for PricingError {
+    fn from(e: AddonError) -> Self {
+        match e {
+            AddonError::Database(e) => PricingError::Database(e),
+            other => PricingError::Addon(other),
+        }
+    }
+}
+
+struct PricingRule {
+    rule_name: String,
+    rule_type: String,
+    value: BigDecimal,
+    description: Option<String>,
+    hotel_id: Option<i32>,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    min_nights: Option<i32>,
+}
+
+impl PricingRule {
+    fn line(&self, amount: Money) -> QuoteLine {
+        QuoteLine {
+            rule: self.rule_name.clone(),
+            description: self.description.clone().unwrap_or_else(|| self.rule_name.clone()),
+            amount,
+        }
+    }
+}
+
+// Entre varias reglas aplicables gana la específica del hotel y, después, la de mayor valor
+fn pick_rule<'r>(rules: impl Iterator<Item = &'r PricingRule>) -> Option<&'r PricingRule> {
+    rules.max_by(|a, b| {
+        (a.hotel_id.is_some(), &a.value).cmp(&(b.hotel_id.is_some(), &b.value))
+    })
+}
+
+// Calcula el precio de una estancia aplicando, en orden:
+// 1. temporada (recargo % sobre la tarifa base de cada noche dentro del rango)
+// 2. fin de semana (recargo % sobre la tarifa base de viernes y sábado)
+// 3. duración de estancia (descuento % sobre el subtotal de habitaciones)
+// 4. servicios adicionales del catálogo del hotel
+// 5. comisión de la plataforma (informativa: se descuenta del pago al hotel)
+pub async fn quote_stay(
+    conn: &mut PgConnection,
+    nightly_rate: &Money,
+    stay: &StayRequest<'_>,
+) -> Result<StayQuote, PricingError> {
+    let nights = (stay.check_out - stay.check_in).num_days();
+
+    let rules = load_rules(&mut *conn, &[stay.hotel_id]).await?;
+    let priced_addons = addons::price_addons(
+        &mut *conn,
+        stay.hotel_id,
+        stay.addons,
+        nights,
+        stay.guests,
+        nightly_rate.currency(),
+    )
+    .await?;
+    let commission_rate = commission_rate(conn).await?;
+
+    Ok(build_quote(&rules, nightly_rate, stay, priced_addons, &commission_rate))
+}
+
+// Cotiza varias estancias sin servicios adicionales (búsqueda de disponibilidad).
+// Las reglas y la comisión se leen una sola vez para todos los hoteles.
+pub async fn quote_rooms_batch(
+    pool: &PgPool,
+    stays: &[(Money, StayRequest<'_>)],
+) -> Result<Vec<StayQuote>, PricingError> {
+    if stays.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = pool.acquire().await?;
+    let hotel_ids: Vec<i32> = stays.iter().map(|(_, stay)| stay.hotel_id).collect();
+    let rules = load_rules(&mut conn, &hotel_ids).await?;
+    let commission_rate = commission_rate(&mut conn).await?;
+
+    Ok(stays
+        .iter()
+        .map(|(nightly_rate, stay)| build_quote(&rules, nightly_rate, stay, Vec::new(), &commission_rate))
+        .collect())
+}
+
+// Reglas activas globales y las propias de los hoteles indicados
+async fn load_rules(conn: &mut PgConnection, hotel_ids: &[i32]) -> Result<Vec<PricingRule>, sqlx::Error> {
+    sqlx::query_as!(
+        PricingRule,
+        r#"
+        SELECT rule_name, rule_type, value, description, hotel_id, start_date, end_date, min_nights
+        FROM pricing_rules
+        WHERE is_active
+          AND rule_type IN ('seasonal', 'weekend', 'length_of_stay')
+          AND (hotel_id IS NULL OR hotel_id = ANY($1))
+        "#,
+        hotel_ids
+    )
+    .fetch_all(conn)
+    .await
+}
+
+// Arma la cotización a partir de datos ya cargados, sin tocar la base de datos
+fn build_quote(
+    rules: &[PricingRule],
+    nightly_rate: &Money,
+    stay: &StayRequest<'_>,
+    priced_addons: Vec<PricedAddon>,
+    commission_rate: &BigDecimal,
+) -> StayQuote {
+    let currency = nightly_rate.currency();
+    let nights = (stay.check_out - stay.check_in).num_days();
+
+    let (nightly, room_subtotal, discounts) = price_rooms(rules, nightly_rate, stay);
+    let addons_total = addons::addons_total(&priced_addons, currency);
+
+    let total = discounts
+        .iter()
+        .fold(room_subtotal.clone(), |total, line| total + line.amount.clone())
+        + addons_total.clone();
+
+    let commission = total.rate(commission_rate);
+    let owner_payout = total.clone() - commission.clone();
+
+    StayQuote {
+        hotel_id: stay.hotel_id,
+        check_in: stay.check_in,
+        check_out: stay.check_out,
+        nights,
+        rooms: stay.rooms,
+        guests: stay.guests,
+        nightly,
+        room_subtotal,
+        discounts,
+        addons: priced_addons,
+        addons_total,
+        total,
+        commission_rate: commission_rate.to_string(),
+        commission,
+        owner_payout,
+    }
+}
+
+// Pasos 1 a 3 de quote_stay: tarifa de cada noche, subtotal de habitaciones
+// y descuento por duración
+fn price_rooms(
+    rules: &[PricingRule],
+    nightly_rate: &Money,
+    stay: &StayRequest<'_>,
+) -> (Vec<NightRate>, Money, Vec<QuoteLine>) {
+    let currency = nightly_rate.currency();
+    let nights = (stay.check_out - stay.check_in).num_days();
+
+    // Solo cuentan las reglas globales y las del propio hotel
+    let rules: Vec<&PricingRule> = rules
+        .iter()
+        .filter(|r| r.hotel_id.is_none_or(|id| id == stay.hotel_id))
+        .collect();
+
+    // Tarifa por noche con recargos de temporada y fin de semana
+    let mut nightly = Vec::with_capacity(nights as usize);
+    for date in stay.check_in.iter_days().take(nights as usize) {
+        let mut adjustments = Vec::new();
+
+        let seasonal = pick_rule(rules.iter().copied().filter(|r| {
+            r.rule_type == "seasonal"
+                && r.start_date.is_some_and(|start| date >= start)
+                && r.end_date.is_some_and(|end| date <= end)
+        }));
+        if let Some(rule) = seasonal {
+            adjustments.push(rule.line(nightly_rate.percent(&rule.value)));
+        }
+
+        if matches!(date.weekday(), Weekday::Fri | Weekday::Sat) {
+            if let Some(rule) = pick_rule(rules.iter().copied().filter(|r| r.rule_type == "weekend")) {
+                adjustments.push(rule.line(nightly_rate.percent(&rule.value)));
+            }
+        }
+
+        let rate = adjustments
+            .iter()
+            .fold(nightly_rate.clone(), |rate, line| rate + line.amount.clone());
+
+        nightly.push(NightRate {
+            date,
+            base_rate: nightly_rate.clone(),
+            adjustments,
+            rate,
+        });
+    }
+
+    let room_subtotal = nightly
+        .iter()
+        .fold(Money::zero(currency), |total, night| total + night.rate.clone())
+        .times(stay.rooms as i64);
+
+    // Descuento por duración: se aplica el mejor al que califica la estancia
+    let mut discounts = Vec::new();
+    let length_of_stay = pick_rule(rules.iter().copied().filter(|r| {
+        r.rule_type == "length_of_stay" && r.min_nights.is_some_and(|min| nights >= min as i64)
+    }));
+    if let Some(rule) = length_of_stay {
+        let discount = room_subtotal.percent(&rule.value);
+        discounts.push(rule.line(Money::zero(currency) - discount));
+    }
+
+    (nightly, room_subtotal, discounts)
+}
+
+async fn commission_rate(conn: &mut PgConnection) -> Result<BigDecimal, sqlx::Error> {
+    let setting = sqlx::query!(
+        "SELECT setting_value FROM platform_settings WHERE setting_name = 'PLATFORM_COMMISSION_RATE'"
+    )
+    .fetch_optional(conn)
+    .await?;
+
+    let rate = setting
+        .and_then(|s| BigDecimal::from_str(s.setting_value.trim()).ok())
+        .unwrap_or_else(|| {
+            println!("⚠️ [PRICING] PLATFORM_COMMISSION_RATE inválido o ausente, usando {}", DEFAULT_COMMISSION_RATE);
+            BigDecimal::from_str(DEFAULT_COMMISSION_RATE).expect("valid default rate")
+        });
+
+    Ok(rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    fn rule(rule_type: &str, value: i32) -> PricingRule {
+        PricingRule {
+            rule_name: format!("{} {}", rule_type, value),
+            rule_type: rule_type.to_string(),
+            value: BigDecimal::from(value),
+            description: None,
+            hotel_id: None,
+            start_date: None,
+            end_date: None,
+            min_nights: None,
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn stay(check_in: NaiveDate, check_out: NaiveDate, rooms: i32) -> StayRequest<'static> {
+        StayRequest {
+            hotel_id: 1,
+            check_in,
+            check_out,
+            guests: 2,
+            rooms,
+            addons: &[],
+        }
+    }
+
+    fn mxn(amount: i32) -> Money {
+        Money::new(BigDecimal::from(amount), Currency::MXN)
+    }
+
+    #[test]
+    fn sin_reglas_cobra_la_tarifa_base_por_noche_y_habitacion() {
+        // Lunes a miércoles
+        let (nightly, subtotal, discounts) = price_rooms(&[], &mxn(1000), &stay(date(2025, 8, 4), date(2025, 8, 6), 2));
+
+        assert_eq!(nightly.len(), 2);
+        assert!(nightly.iter().all(|n| n.adjustments.is_empty() && n.rate == mxn(1000)));
+        assert_eq!(subtotal, mxn(4000));
+        assert!(discounts.is_empty());
+    }
+
+    #[test]
+    fn recargo_de_fin_de_semana_solo_viernes_y_sabado() {
+        let rules = [rule("weekend", 10)];
+        // Jueves 31 de julio a domingo 3 de agosto: jueves, viernes y sábado
+        let (nightly, subtotal, _) = price_rooms(&rules, &mxn(1000), &stay(date(2025, 7, 31), date(2025, 8, 3), 1));
+
+        let rates: Vec<Money> = nightly.into_iter().map(|n| n.rate).collect();
+        assert_eq!(rates, vec![mxn(1000), mxn(1100), mxn(1100)]);
+        assert_eq!(subtotal, mxn(3200));
+    }
+
+    #[test]
+    fn temporada_aplica_dentro_del_rango_y_gana_la_regla_del_hotel() {
+        let mut global = rule("seasonal", 50);
+        global.start_date = Some(date(2025, 8, 5));
+        global.end_date = Some(date(2025, 8, 31));
+        let mut del_hotel = rule("seasonal", 20);
+        del_hotel.hotel_id = Some(1);
+        del_hotel.start_date = Some(date(2025, 8, 5));
+        del_hotel.end_date = Some(date(2025, 8, 31));
+
+        // Lunes 4 (fuera de temporada) y martes 5 (dentro)
+        let (nightly, subtotal, _) =
+            price_rooms(&[global, del_hotel], &mxn(1000), &stay(date(2025, 8, 4), date(2025, 8, 6), 1));
+
+        assert_eq!(nightly[0].rate, mxn(1000));
+        assert_eq!(nightly[1].rate, mxn(1200));
+        assert_eq!(nightly[1].adjustments[0].rule, "seasonal 20");
+        assert_eq!(subtotal, mxn(2200));
+    }
+
+    #[test]
+    fn descuento_por_duracion_usa_el_mejor_al_que_califica() {
+        let mut tres_noches = rule("length_of_stay", 10);
+        tres_noches.min_nights = Some(3);
+        let mut siete_noches = rule("length_of_stay", 25);
+        siete_noches.min_nights = Some(7);
+        let rules = [tres_noches, siete_noches];
+
+        // Lunes a jueves: 3 noches, no llega a 7
+        let (_, subtotal, discounts) = price_rooms(&rules, &mxn(1000), &stay(date(2025, 8, 4), date(2025, 8, 7), 1));
+        assert_eq!(subtotal, mxn(3000));
+        assert_eq!(discounts.len(), 1);
+        assert_eq!(discounts[0].amount, mxn(-300));
+
+        // Dos noches no califican para ningún descuento
+        let (_, _, discounts) = price_rooms(&rules, &mxn(1000), &stay(date(2025, 8, 4), date(2025, 8, 6), 1));
+        assert!(discounts.is_empty());
+    }
+
+    #[test]
+    fn ignora_las_reglas_de_otros_hoteles() {
+        let mut otro_hotel = rule("weekend", 30);
+        otro_hotel.hotel_id = Some(2);
+        let rules = [rule("weekend", 10), otro_hotel];
+
+        // Viernes 1 de agosto
+        let (nightly, _, _) = price_rooms(&rules, &mxn(1000), &stay(date(2025, 8, 1), date(2025, 8, 2), 1));
+        assert_eq!(nightly[0].rate, mxn(1100));
+    }
+}
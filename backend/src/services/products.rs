@@ -0,0 +1,209 @@
+// backend/src/services/products.rs
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::CreateProductOrderRequest;
+use crate::money::{Currency, Money};
+use crate::services::references;
+
+pub const PRODUCT_ORDER_PREFIX: &str = "MA";
+
+// Máximo de piezas iguales por compra
+pub const MAX_PRODUCT_QUANTITY: i32 = 20;
+
+// Pieza comprada con el precio vigente al momento de la compra
+#[derive(Debug, Clone, Serialize)]
+pub struct PurchasedProduct {
+    pub product_id: i32,
+    pub name: String,
+    pub unit_price: Money,
+    pub quantity: i32,
+    pub subtotal: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProductOrder {
+    pub id: i32,
+    pub reference: String,
+    pub status: String,
+    pub items: Vec<PurchasedProduct>,
+    pub total_price: Money,
+    pub shipping_address: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum ProductError {
+    NotFound(i32),
+    InsufficientStock { product_id: i32, available: i32 },
+    InvalidQuantity(i32),
+    Duplicated(i32),
+    MixedCurrency,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ProductError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProductError::NotFound(id) => write!(f, "El producto {} no existe o ya no está a la venta", id),
+            ProductError::InsufficientStock { product_id, available } => write!(
+                f,
+                "Solo quedan {} piezas del producto {}",
+                available, product_id
+            ),
+            ProductError::InvalidQuantity(id) => write!(
+                f,
+                "La cantidad del producto {} debe estar entre 1 y {}",
+                id, MAX_PRODUCT_QUANTITY
+            ),
+            ProductError::Duplicated(id) => write!(f, "El producto {} aparece más de una vez", id),
+            ProductError::MixedCurrency => write!(f, "Todos los productos de una compra deben tener la misma moneda"),
+            ProductError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ProductError {
+    fn from(e: sqlx::Error) -> Self {
+        ProductError::Database(e)
+    }
+}
+
+// Descuenta el inventario y registra la compra. El UPDATE condicionado a
+// `stock >= cantidad` evita vender de más aunque dos compras lleguen a la vez;
+// si alguna pieza falla, el llamador descarta la transacción completa.
+pub async fn purchase_products(
+    conn: &mut PgConnection,
+    user_id: i32,
+    order: &CreateProductOrderRequest,
+) -> Result<ProductOrder, ProductError> {
+    let mut selections = order.items.clone();
+    for (i, selection) in selections.iter().enumerate() {
+        if selections[..i].iter().any(|s| s.product_id == selection.product_id) {
+            return Err(ProductError::Duplicated(selection.product_id));
+        }
+        if selection.quantity < 1 || selection.quantity > MAX_PRODUCT_QUANTITY {
+            return Err(ProductError::InvalidQuantity(selection.product_id));
+        }
+    }
+
+    // Bloquear las filas siempre en el mismo orden para no provocar deadlocks
+    selections.sort_by_key(|s| s.product_id);
+
+    let mut items: Vec<PurchasedProduct> = Vec::with_capacity(selections.len());
+    let mut currency: Option<Currency> = None;
+
+    for selection in &selections {
+        let product = sqlx::query!(
+            r#"
+            UPDATE products SET stock = stock - $2, updated_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND is_active AND stock >= $2
+            RETURNING id, name, price, currency
+            "#,
+            selection.product_id,
+            selection.quantity
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let product = match product {
+            Some(product) => product,
+            None => {
+                let available = sqlx::query_scalar!(
+                    "SELECT stock FROM products WHERE id = $1 AND is_active",
+                    selection.product_id
+                )
+                .fetch_optional(&mut *conn)
+                .await?;
+
+                return Err(match available {
+                    Some(available) => ProductError::InsufficientStock {
+                        product_id: selection.product_id,
+                        available,
+                    },
+                    None => ProductError::NotFound(selection.product_id),
+                });
+            }
+        };
+
+        let unit_price = Money::from_db(product.price, &product.currency);
+        match currency {
+            Some(c) if c != unit_price.currency() => return Err(ProductError::MixedCurrency),
+            _ => currency = Some(unit_price.currency()),
+        }
+
+        items.push(PurchasedProduct {
+            product_id: product.id,
+            name: product.name,
+            subtotal: unit_price.times(selection.quantity as i64),
+            unit_price,
+            quantity: selection.quantity,
+        });
+    }
+
+    let total_price = items.iter().fold(
+        Money::zero(currency.unwrap_or(Currency::MXN)),
+        |total, item| total + item.subtotal.clone(),
+    );
+
+    let reference = references::issue_reference(&mut *conn, PRODUCT_ORDER_PREFIX, "product_order").await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO product_orders (user_id, order_reference, total_price, currency, shipping_address)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, status, created_at
+        "#,
+        user_id,
+        reference,
+        total_price.amount(),
+        total_price.currency().as_str(),
+        order.shipping_address
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    for item in &items {
+        sqlx::query!(
+            r#"
+            INSERT INTO product_order_items (order_id, product_id, name, unit_price, quantity, subtotal)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            created.id,
+            item.product_id,
+            item.name,
+            item.unit_price.amount(),
+            item.quantity,
+            item.subtotal.amount()
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(ProductOrder {
+        id: created.id,
+        reference,
+        status: created.status,
+        items,
+        total_price,
+        shipping_address: order.shipping_address.clone(),
+        created_at: created.created_at,
+    })
+}
+
+// Devuelve al inventario las piezas de una compra cancelada
+pub async fn restock_order(conn: &mut PgConnection, order_id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE products p SET stock = p.stock + i.quantity, updated_at = CURRENT_TIMESTAMP
+        FROM product_order_items i
+        WHERE i.order_id = $1 AND p.id = i.product_id
+        "#,
+        order_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+// backend/src/services/references.rs
+use sqlx::PgConnection;
+
+// Prefijos visibles para el cliente según el producto
+pub const HOTEL_BOOKING_PREFIX: &str = "MY";
+pub const TABLE_RESERVATION_PREFIX: &str = "MR";
+
+// Emite una referencia única (prefijo + 6 caracteres) y la registra en
+// issued_references. El INSERT ... ON CONFLICT reemplaza el antiguo
+// "consultar y luego insertar", que podía repetir referencias bajo concurrencia.
+pub async fn issue_reference(
+    conn: &mut PgConnection,
+    prefix: &str,
+    kind: &str,
+) -> Result<String, sqlx::Error> {
+    loop {
+        let candidate = format!(
+            "{}{}",
+            prefix,
+            uuid::Uuid::new_v4().simple().to_string()[..6].to_uppercase()
+        );
+
+        let issued = sqlx::query_scalar!(
+            r#"
+            INSERT INTO issued_references (reference, kind) VALUES ($1, $2)
+            ON CONFLICT (reference) DO NOTHING
+            RETURNING reference
+            "#,
+            candidate,
+            kind
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if let Some(reference) = issued {
+            return Ok(reference);
+        }
+    }
+}
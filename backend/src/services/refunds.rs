@@ -0,0 +1,338 @@
+// backend/src/services/refunds.rs
+use bigdecimal::{BigDecimal, Zero};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::{BookingStatus, CancellationPolicyKind};
+use crate::money::Money;
+use crate::services::bookings::BookingHolder;
+use crate::services::payments::{PaymentError, PaymentProviders, RefundRequest};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CancellationPolicy {
+    pub kind: CancellationPolicyKind,
+    pub free_cancellation_days: i32,
+    pub penalty_percent: i32,
+}
+
+impl CancellationPolicy {
+    pub fn from_db(kind: &str, free_cancellation_days: i32, penalty_percent: i32) -> CancellationPolicy {
+        CancellationPolicy {
+            // Una política desconocida se trata como la más estricta
+            kind: CancellationPolicyKind::from_db(kind).unwrap_or(CancellationPolicyKind::NonRefundable),
+            free_cancellation_days,
+            penalty_percent,
+        }
+    }
+
+    // Porcentaje del pago que se retiene al cancelar `days_before` días antes del check-in
+    pub fn penalty_percent_for(&self, days_before: i32) -> i32 {
+        match self.kind {
+            CancellationPolicyKind::NonRefundable => 100,
+            _ if days_before >= self.free_cancellation_days => 0,
+            CancellationPolicyKind::Flexible => 100,
+            CancellationPolicyKind::Partial => self.penalty_percent,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self.kind {
+            CancellationPolicyKind::NonRefundable => "Tarifa no reembolsable".to_string(),
+            CancellationPolicyKind::Flexible => format!(
+                "Cancelación gratuita hasta {} días antes del check-in; después no hay reembolso",
+                self.free_cancellation_days
+            ),
+            CancellationPolicyKind::Partial => format!(
+                "Cancelación gratuita hasta {} días antes del check-in; después se retiene el {}%",
+                self.free_cancellation_days, self.penalty_percent
+            ),
+        }
+    }
+}
+
+// Lo que se devolvería al cancelar hoy
+#[derive(Debug, Clone, Serialize)]
+pub struct RefundQuote {
+    pub booking_id: i32,
+    // Estado de la reserva antes de cancelarla
+    pub status: BookingStatus,
+    pub policy: CancellationPolicy,
+    pub policy_summary: String,
+    pub days_before_check_in: i32,
+    pub payment_status: String,
+    pub paid: Money,
+    pub penalty: Money,
+    pub refund: Money,
+    // Cobro exitoso sobre el que se emite el reembolso
+    #[serde(skip)]
+    pub payment_intent_id: Option<i32>,
+}
+
+// Reembolso registrado al cancelar
+#[derive(Debug, Clone, Serialize)]
+pub struct Refund {
+    pub id: i32,
+    pub provider_refund_id: String,
+    pub amount: Money,
+    pub payment_status: &'static str,
+}
+
+#[derive(Debug)]
+pub enum RefundError {
+    BookingNotFound,
+    NotCancellable(BookingStatus),
+    StayStarted,
+    ProviderUnavailable(String),
+    Payment(PaymentError),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for RefundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefundError::BookingNotFound => write!(f, "Reserva no encontrada"),
+            RefundError::NotCancellable(status) => {
+                write!(f, "No se puede cancelar una reserva en estado '{}'", status.as_str())
+            }
+            RefundError::StayStarted => write!(f, "La estancia ya comenzó; contacta al hotel para cancelarla"),
+            RefundError::ProviderUnavailable(name) => {
+                write!(f, "El proveedor de pagos '{}' ya no está configurado para reembolsar", name)
+            }
+            RefundError::Payment(e) => write!(f, "{}", e),
+            RefundError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for RefundError {
+    fn from(e: sqlx::Error) -> Self {
+        RefundError::Database(e)
+    }
+}
+
+impl From<PaymentError> for RefundError {
+    fn from(e: PaymentError) -> Self {
+        match e {
+            PaymentError::Database(e) => RefundError::Database(e),
+            other => RefundError::Payment(other),
+        }
+    }
+}
+
+// Evalúa la política del hotel para que el huésped cancele hoy. Bloquea la
+// reserva, así que dentro de una transacción el resultado sigue vigente hasta el commit.
+pub async fn quote_cancellation(
+    conn: &mut PgConnection,
+    booking_id: i32,
+    holder: BookingHolder,
+) -> Result<RefundQuote, RefundError> {
+    load_quote(conn, booking_id, Some(holder), false).await
+}
+
+// Cuando el hotel rechaza una reserva no aplica la política: se devuelve todo
+pub async fn quote_hotel_rejection(conn: &mut PgConnection, booking_id: i32) -> Result<RefundQuote, RefundError> {
+    load_quote(conn, booking_id, None, true).await
+}
+
+async fn load_quote(
+    conn: &mut PgConnection,
+    booking_id: i32,
+    holder: Option<BookingHolder>,
+    rejected_by_hotel: bool,
+) -> Result<RefundQuote, RefundError> {
+    let booking = sqlx::query!(
+        r#"
+        SELECT b.status, b.payment_status, b.total_price, b.currency, b.trip_id,
+               b.check_in - CURRENT_DATE as "days_before!",
+               h.cancellation_policy, h.free_cancellation_days, h.cancellation_penalty_percent
+        FROM bookings b
+        JOIN hotels h ON b.hotel_id = h.id
+        WHERE b.id = $1
+          AND (($2::INT IS NULL AND $3::INT IS NULL) OR b.user_id = $2 OR b.guest_id = $3)
+        FOR UPDATE OF b
+        "#,
+        booking_id,
+        holder.and_then(|h| h.user_id()),
+        holder.and_then(|h| h.guest_id())
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(RefundError::BookingNotFound)?;
+
+    let current = BookingStatus::from_db(&booking.status).unwrap_or(BookingStatus::Pending);
+    let target = if rejected_by_hotel { BookingStatus::Rejected } else { BookingStatus::Cancelled };
+    if !current.can_transition_to(target) {
+        return Err(RefundError::NotCancellable(current));
+    }
+    if !rejected_by_hotel && booking.days_before < 0 {
+        return Err(RefundError::StayStarted);
+    }
+
+    let policy = CancellationPolicy::from_db(
+        &booking.cancellation_policy,
+        booking.free_cancellation_days,
+        booking.cancellation_penalty_percent,
+    );
+
+    // Solo hay algo que devolver si la reserva se pagó, sola o con su viaje
+    let payment = if booking.payment_status == "paid" {
+        sqlx::query!(
+            r#"
+            SELECT id, amount, currency, trip_id FROM payment_intents
+            WHERE (booking_id = $1 OR trip_id = $2) AND status = 'succeeded'
+            ORDER BY updated_at DESC
+            LIMIT 1
+            "#,
+            booking_id,
+            booking.trip_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+    } else {
+        None
+    };
+
+    // Del cobro de un viaje solo corresponde a esta reserva su propio total
+    let (payment_intent_id, paid) = match payment {
+        Some(p) if p.trip_id.is_some() => (Some(p.id), Money::from_db(booking.total_price, &booking.currency)),
+        Some(p) => (Some(p.id), Money::from_db(p.amount, &p.currency)),
+        None => (None, Money::from_db(BigDecimal::zero(), &booking.currency)),
+    };
+
+    let penalty_percent = if rejected_by_hotel { 0 } else { policy.penalty_percent_for(booking.days_before) };
+    let penalty = paid.percent(&BigDecimal::from(penalty_percent));
+    let refund = paid.clone() - penalty.clone();
+    let policy_summary = if rejected_by_hotel {
+        "El hotel rechazó la reserva: se reembolsa el total".to_string()
+    } else {
+        policy.summary()
+    };
+
+    Ok(RefundQuote {
+        booking_id,
+        status: current,
+        policy_summary,
+        policy,
+        days_before_check_in: booking.days_before,
+        payment_status: booking.payment_status,
+        paid,
+        penalty,
+        refund,
+        payment_intent_id,
+    })
+}
+
+// Emite con el proveedor el reembolso calculado por quote_cancellation y
+// actualiza payment_status. Devuelve None si no hay nada que reembolsar.
+pub async fn issue_refund(
+    conn: &mut PgConnection,
+    providers: &PaymentProviders,
+    quote: &RefundQuote,
+    reason: Option<&str>,
+) -> Result<Option<Refund>, RefundError> {
+    let payment_intent_id = match quote.payment_intent_id {
+        Some(id) if !quote.refund.amount().is_zero() => id,
+        _ => return Ok(None),
+    };
+
+    let intent = sqlx::query!(
+        r#"
+        SELECT p.provider, p.provider_intent_id, b.booking_reference
+        FROM payment_intents p
+        JOIN bookings b ON b.id = $2
+        WHERE p.id = $1
+        "#,
+        payment_intent_id,
+        quote.booking_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let provider = providers
+        .get(&intent.provider)
+        .ok_or_else(|| RefundError::ProviderUnavailable(intent.provider.clone()))?;
+
+    let provider_refund = provider
+        .refund(&RefundRequest {
+            provider_intent_id: intent.provider_intent_id,
+            reference: intent.booking_reference,
+            amount: quote.refund.clone(),
+        })
+        .await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO refunds
+        (booking_id, payment_intent_id, provider, provider_refund_id, amount, penalty_amount, currency, policy, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id
+        "#,
+        quote.booking_id,
+        payment_intent_id,
+        provider.name(),
+        provider_refund.id,
+        quote.refund.amount(),
+        quote.penalty.amount(),
+        quote.refund.currency().as_str(),
+        serde_json::to_value(&quote.policy).unwrap_or(serde_json::Value::Null),
+        reason
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let payment_status = if quote.penalty.amount().is_zero() { "refunded" } else { "partially_refunded" };
+    sqlx::query!(
+        "UPDATE bookings SET payment_status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        quote.booking_id,
+        payment_status
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(Some(Refund {
+        id: created.id,
+        provider_refund_id: provider_refund.id,
+        amount: quote.refund.clone(),
+        payment_status,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flexible_es_gratis_hasta_el_limite_y_despues_retiene_todo() {
+        let policy = CancellationPolicy::from_db("flexible", 3, 0);
+
+        assert_eq!(policy.penalty_percent_for(10), 0);
+        assert_eq!(policy.penalty_percent_for(3), 0);
+        assert_eq!(policy.penalty_percent_for(2), 100);
+        assert_eq!(policy.penalty_percent_for(0), 100);
+    }
+
+    #[test]
+    fn parcial_retiene_el_porcentaje_dentro_del_limite() {
+        let policy = CancellationPolicy::from_db("partial", 7, 50);
+
+        assert_eq!(policy.penalty_percent_for(7), 0);
+        assert_eq!(policy.penalty_percent_for(6), 50);
+        assert_eq!(policy.penalty_percent_for(-1), 50);
+    }
+
+    #[test]
+    fn no_reembolsable_retiene_todo_siempre() {
+        let policy = CancellationPolicy::from_db("non_refundable", 30, 0);
+
+        assert_eq!(policy.penalty_percent_for(365), 100);
+        assert_eq!(policy.penalty_percent_for(0), 100);
+    }
+
+    #[test]
+    fn politica_desconocida_se_trata_como_no_reembolsable() {
+        let policy = CancellationPolicy::from_db("otra", 3, 0);
+
+        assert_eq!(policy.kind, CancellationPolicyKind::NonRefundable);
+        assert_eq!(policy.penalty_percent_for(10), 100);
+    }
+}
@@ -0,0 +1,337 @@
+// backend/src/services/rides.rs
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::{CreateRideRequest, RidePlace, RideStatus};
+use crate::money::Money;
+use crate::services::references;
+
+pub const RIDE_PREFIX: &str = "MV";
+
+// La distancia en línea recta se multiplica por este factor para aproximar la ruta por carretera
+const ROAD_FACTOR: f64 = 1.3;
+// Velocidad promedio usada para estimar la duración del trayecto
+const AVERAGE_SPEED_KMH: f64 = 40.0;
+const MIN_RIDE_KM: f64 = 0.3;
+const MAX_RIDE_KM: f64 = 400.0;
+// Solo se ofrece el viaje a conductores a esta distancia del punto de partida
+pub const MAX_PICKUP_KM: f64 = 30.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// Tarifa desglosada con las reglas 'transport' de pricing_rules
+#[derive(Debug, Clone, Serialize)]
+pub struct FareEstimate {
+    pub distance_km: f64,
+    pub duration_minutes: i32,
+    pub base_fare: Money,
+    pub distance_fare: Money,
+    pub time_fare: Money,
+    pub total: Money,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignedDriver {
+    pub id: i32,
+    pub name: String,
+    pub phone: Option<String>,
+    pub vehicle: String,
+    pub plate: String,
+    // Distancia en línea recta del conductor al punto de partida
+    pub pickup_distance_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Ride {
+    pub id: i32,
+    pub reference: String,
+    pub status: RideStatus,
+    pub origin: RidePlace,
+    pub destination: RidePlace,
+    pub fare: FareEstimate,
+    pub notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// Viaje que un conductor acaba de aceptar
+#[derive(Debug, Clone, Serialize)]
+pub struct AcceptedRide {
+    pub id: i32,
+    pub reference: String,
+    #[serde(skip)]
+    pub rider_id: i32,
+    pub status: RideStatus,
+    pub origin: RidePlace,
+    pub destination: RidePlace,
+    pub fare: Money,
+    pub driver: AssignedDriver,
+}
+
+#[derive(Debug)]
+pub enum RideError {
+    SameLocation,
+    TooFar,
+    FareUnavailable(&'static str),
+    DriverUnavailable,
+    AlreadyTaken,
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for RideError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RideError::SameLocation => write!(f, "El origen y el destino deben ser lugares distintos"),
+            RideError::TooFar => write!(f, "El destino está fuera del área de servicio (máximo {} km)", MAX_RIDE_KM),
+            RideError::FareUnavailable(rule) => write!(f, "La tarifa de transporte no está configurada ({})", rule),
+            RideError::DriverUnavailable => write!(f, "Debes estar en línea, certificado y sin otro viaje para aceptar"),
+            RideError::AlreadyTaken => write!(f, "Otro conductor ya aceptó este viaje o fue cancelado"),
+            RideError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for RideError {
+    fn from(e: sqlx::Error) -> Self {
+        RideError::Database(e)
+    }
+}
+
+// Distancia en línea recta (fórmula del haversine) entre dos puntos (lat, lng)
+pub fn haversine_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lng = (to.1 - from.1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+// Cotiza un trayecto: tarifa base + costo por km + costo por minuto.
+// Las reglas son globales y están en MXN (BASE_FARE_MXN, PER_KM_RATE_MXN, PER_MINUTE_RATE_MXN).
+pub async fn estimate_fare(
+    conn: &mut PgConnection,
+    origin: &RidePlace,
+    destination: &RidePlace,
+) -> Result<FareEstimate, RideError> {
+    let straight_km = haversine_km((origin.lat, origin.lng), (destination.lat, destination.lng));
+    if straight_km < MIN_RIDE_KM {
+        return Err(RideError::SameLocation);
+    }
+
+    let road_km = straight_km * ROAD_FACTOR;
+    if road_km > MAX_RIDE_KM {
+        return Err(RideError::TooFar);
+    }
+    let duration_minutes = ((road_km / AVERAGE_SPEED_KMH) * 60.0).ceil().max(1.0) as i32;
+
+    let rules = sqlx::query!(
+        r#"
+        SELECT rule_name, value
+        FROM pricing_rules
+        WHERE is_active
+          AND rule_type = 'transport'
+          AND hotel_id IS NULL
+          AND rule_name IN ('BASE_FARE_MXN', 'PER_KM_RATE_MXN', 'PER_MINUTE_RATE_MXN')
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let rule = |name: &'static str| {
+        rules
+            .iter()
+            .find(|r| r.rule_name == name)
+            .map(|r| Money::mxn(r.value.clone()))
+            .ok_or(RideError::FareUnavailable(name))
+    };
+    let base_fare = rule("BASE_FARE_MXN")?;
+    let per_km = rule("PER_KM_RATE_MXN")?;
+    let per_minute = rule("PER_MINUTE_RATE_MXN")?;
+
+    let distance_km = (road_km * 100.0).round() / 100.0;
+    let km = BigDecimal::from_str(&format!("{:.2}", distance_km)).expect("valid distance");
+    let distance_fare = per_km.rate(&km);
+    let time_fare = per_minute.times(duration_minutes as i64);
+    let total = base_fare.clone() + distance_fare.clone() + time_fare.clone();
+
+    Ok(FareEstimate {
+        distance_km,
+        duration_minutes,
+        base_fare,
+        distance_fare,
+        time_fare,
+        total,
+    })
+}
+
+// Registra la solicitud con la tarifa congelada. El viaje queda en 'requested'
+// hasta que un conductor acepte la oferta (ver services::dispatch).
+pub async fn request_ride(
+    conn: &mut PgConnection,
+    user_id: i32,
+    ride: &CreateRideRequest,
+) -> Result<Ride, RideError> {
+    let fare = estimate_fare(&mut *conn, &ride.origin, &ride.destination).await?;
+    let reference = references::issue_reference(&mut *conn, RIDE_PREFIX, "ride").await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO rides
+        (user_id, ride_reference, origin_address, origin_lat, origin_lng,
+         destination_address, destination_lat, destination_lng,
+         distance_km, duration_minutes, fare, currency, fare_breakdown, notes)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING id, created_at
+        "#,
+        user_id,
+        reference,
+        ride.origin.address.trim(),
+        ride.origin.lat,
+        ride.origin.lng,
+        ride.destination.address.trim(),
+        ride.destination.lat,
+        ride.destination.lng,
+        BigDecimal::from_str(&format!("{:.2}", fare.distance_km)).expect("valid distance"),
+        fare.duration_minutes,
+        fare.total.amount(),
+        fare.total.currency().as_str(),
+        serde_json::to_value(&fare).ok(),
+        ride.notes
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_status_change(&mut *conn, created.id, None, RideStatus::Requested, Some(user_id)).await?;
+
+    Ok(Ride {
+        id: created.id,
+        reference,
+        status: RideStatus::Requested,
+        origin: ride.origin.clone(),
+        destination: ride.destination.clone(),
+        fare,
+        notes: ride.notes.clone(),
+        created_at: created.created_at,
+    })
+}
+
+// Asigna el viaje al conductor que aceptó la oferta. El primero en aceptar se
+// queda con el viaje: el UPDATE condicionado a status = 'requested' espera a que
+// termine una aceptación simultánea y, si esa ganó, ya no afecta ninguna fila.
+// Si falla, el llamador descarta la transacción y el conductor sigue libre.
+pub async fn accept_ride(
+    conn: &mut PgConnection,
+    ride_id: i32,
+    driver_id: i32,
+    driver_user_id: i32,
+) -> Result<AcceptedRide, RideError> {
+    let driver = sqlx::query!(
+        r#"
+        UPDATE drivers SET is_available = FALSE, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND is_available AND is_online AND certification_status = 'approved'
+        RETURNING name, phone, vehicle, plate, latitude, longitude
+        "#,
+        driver_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(RideError::DriverUnavailable)?;
+
+    let ride = sqlx::query!(
+        r#"
+        UPDATE rides SET driver_id = $2, status = 'assigned', assigned_at = CURRENT_TIMESTAMP,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status = 'requested'
+        RETURNING user_id, ride_reference, origin_address, origin_lat, origin_lng,
+                  destination_address, destination_lat, destination_lng, fare, currency
+        "#,
+        ride_id,
+        driver_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(RideError::AlreadyTaken)?;
+
+    record_status_change(&mut *conn, ride_id, Some(RideStatus::Requested), RideStatus::Assigned, Some(driver_user_id)).await?;
+
+    let pickup_distance_km = match (driver.latitude, driver.longitude) {
+        (Some(lat), Some(lng)) => haversine_km((lat, lng), (ride.origin_lat, ride.origin_lng)),
+        _ => 0.0,
+    };
+
+    Ok(AcceptedRide {
+        id: ride_id,
+        reference: ride.ride_reference,
+        rider_id: ride.user_id,
+        status: RideStatus::Assigned,
+        origin: RidePlace {
+            address: ride.origin_address,
+            lat: ride.origin_lat,
+            lng: ride.origin_lng,
+        },
+        destination: RidePlace {
+            address: ride.destination_address,
+            lat: ride.destination_lat,
+            lng: ride.destination_lng,
+        },
+        fare: Money::from_db(ride.fare, &ride.currency),
+        driver: AssignedDriver {
+            id: driver_id,
+            name: driver.name,
+            phone: driver.phone,
+            vehicle: driver.vehicle,
+            plate: driver.plate,
+            pickup_distance_km: (pickup_distance_km * 100.0).round() / 100.0,
+        },
+    })
+}
+
+// Deja libre al conductor cuando su viaje termina o se cancela. Al completar
+// el viaje su nueva ubicación es el destino.
+pub async fn release_driver(
+    conn: &mut PgConnection,
+    driver_id: i32,
+    location: Option<(f64, f64)>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE drivers SET is_available = TRUE,
+            latitude = COALESCE($2, latitude),
+            longitude = COALESCE($3, longitude),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        driver_id,
+        location.map(|(lat, _)| lat),
+        location.map(|(_, lng)| lng)
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn record_status_change(
+    conn: &mut PgConnection,
+    ride_id: i32,
+    from: Option<RideStatus>,
+    to: RideStatus,
+    changed_by: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO ride_status_history (ride_id, from_status, to_status, changed_by)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        ride_id,
+        from.map(|s| s.as_str()),
+        to.as_str(),
+        changed_by
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+// backend/src/services/session.rs
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{JwtClaims, UserInfo};
+
+// Vida del access token: corta para limitar el daño de un token robado
+pub const ACCESS_TOKEN_MINUTES: i64 = 15;
+// Vida del refresh token (se renueva en cada rotación)
+pub const REFRESH_TOKEN_DAYS: i64 = 30;
+
+// Tokens emitidos al iniciar o renovar una sesión
+#[derive(Debug)]
+pub struct IssuedSession {
+    pub session_id: Uuid,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    InvalidToken,
+    Expired,
+    Revoked,
+    // Se presentó un refresh token ya rotado: posible robo, la sesión se revoca
+    Reused,
+    Database(sqlx::Error),
+    Jwt(jsonwebtoken::errors::Error),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::InvalidToken => write!(f, "Refresh token inválido"),
+            SessionError::Expired => write!(f, "La sesión expiró. Por favor, inicia sesión nuevamente."),
+            SessionError::Revoked => write!(f, "La sesión fue cerrada. Por favor, inicia sesión nuevamente."),
+            SessionError::Reused => write!(f, "Refresh token reutilizado. La sesión fue revocada por seguridad."),
+            SessionError::Database(e) => write!(f, "Error de base de datos: {}", e),
+            SessionError::Jwt(e) => write!(f, "Error generando token: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for SessionError {
+    fn from(e: sqlx::Error) -> Self {
+        SessionError::Database(e)
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for SessionError {
+    fn from(e: jsonwebtoken::errors::Error) -> Self {
+        SessionError::Jwt(e)
+    }
+}
+
+pub fn jwt_secret() -> String {
+    std::env::var("JWT_SECRET_KEY").unwrap_or_else(|_| {
+        println!("⚠️ [SESSION] JWT_SECRET_KEY no encontrado en .env, usando valor por defecto");
+        "esta_es_una_clave_diferente_para_la_copia".to_string()
+    })
+}
+
+fn create_access_token(user: &UserInfo, session_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::minutes(ACCESS_TOKEN_MINUTES))
+        .expect("valid timestamp")
+        .timestamp() as usize;
+
+    let claims = JwtClaims {
+        user_id: user.id,
+        email: user.email.clone(),
+        user_type: user.user_type.clone(),
+        session_id,
+        exp: expiration,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret().as_ref()),
+    )
+}
+
+// Token opaco de 256 bits; en la BD solo se guarda su SHA-256
+fn generate_refresh_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+// Abre una sesión nueva (un dispositivo) para el usuario
+pub async fn start_session(
+    pool: &PgPool,
+    user: &UserInfo,
+    user_agent: Option<&str>,
+) -> Result<IssuedSession, SessionError> {
+    let session_id = Uuid::new_v4();
+    let refresh_token = generate_refresh_token();
+    let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_DAYS);
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_sessions (id, user_id, refresh_token_hash, user_agent, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        session_id,
+        user.id,
+        hash_token(&refresh_token),
+        user_agent,
+        expires_at
+    )
+    .execute(pool)
+    .await?;
+
+    println!("🔑 [SESSION] Sesión {} iniciada para usuario {}", session_id, user.id);
+
+    Ok(IssuedSession {
+        session_id,
+        access_token: create_access_token(user, session_id)?,
+        refresh_token,
+        expires_in: ACCESS_TOKEN_MINUTES * 60,
+    })
+}
+
+// Canjea un refresh token por un par nuevo. El token anterior queda invalidado
+// y los datos del usuario (incluido su rol) se leen de nuevo de la BD.
+pub async fn rotate_session(
+    pool: &PgPool,
+    refresh_token: &str,
+) -> Result<(UserInfo, IssuedSession), SessionError> {
+    let token_hash = hash_token(refresh_token);
+    let mut tx = pool.begin().await?;
+
+    let session = sqlx::query!(
+        r#"
+        SELECT s.id, s.user_id, s.refresh_token_hash, s.expires_at, s.revoked_at,
+               u.first_name, u.last_name, u.email, u.user_type
+        FROM user_sessions s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.refresh_token_hash = $1 OR s.previous_token_hash = $1
+        FOR UPDATE OF s
+        "#,
+        token_hash
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(SessionError::InvalidToken)?;
+
+    if session.revoked_at.is_some() {
+        return Err(SessionError::Revoked);
+    }
+
+    if session.refresh_token_hash != token_hash {
+        sqlx::query!(
+            "UPDATE user_sessions SET revoked_at = CURRENT_TIMESTAMP WHERE id = $1",
+            session.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        println!("🚨 [SESSION] Refresh token reutilizado, sesión {} revocada", session.id);
+        return Err(SessionError::Reused);
+    }
+
+    if session.expires_at <= Utc::now() {
+        return Err(SessionError::Expired);
+    }
+
+    let new_refresh_token = generate_refresh_token();
+    let expires_at = Utc::now() + Duration::days(REFRESH_TOKEN_DAYS);
+
+    sqlx::query!(
+        r#"
+        UPDATE user_sessions
+        SET previous_token_hash = refresh_token_hash, refresh_token_hash = $2,
+            expires_at = $3, last_used_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        session.id,
+        hash_token(&new_refresh_token),
+        expires_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let user = UserInfo {
+        id: session.user_id,
+        first_name: session.first_name,
+        last_name: session.last_name,
+        email: session.email,
+        user_type: session.user_type,
+    };
+
+    let issued = IssuedSession {
+        session_id: session.id,
+        access_token: create_access_token(&user, session.id)?,
+        refresh_token: new_refresh_token,
+        expires_in: ACCESS_TOKEN_MINUTES * 60,
+    };
+
+    Ok((user, issued))
+}
+
+// Datos actuales del usuario si la sesión sigue activa; None si fue revocada o expiró
+pub async fn active_session_user(
+    pool: &PgPool,
+    session_id: Uuid,
+    user_id: i32,
+) -> Result<Option<UserInfo>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT u.id, u.first_name, u.last_name, u.email, u.user_type
+        FROM user_sessions s
+        JOIN users u ON u.id = s.user_id
+        WHERE s.id = $1 AND s.user_id = $2
+          AND s.revoked_at IS NULL AND s.expires_at > CURRENT_TIMESTAMP
+        "#,
+        session_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|u| UserInfo {
+        id: u.id,
+        first_name: u.first_name,
+        last_name: u.last_name,
+        email: u.email,
+        user_type: u.user_type,
+    }))
+}
+
+pub async fn revoke_session(pool: &PgPool, session_id: Uuid, user_id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE user_sessions SET revoked_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+        "#,
+        session_id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// Cierra la sesión en todos los dispositivos del usuario
+pub async fn revoke_all_sessions(pool: &PgPool, user_id: i32) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        "UPDATE user_sessions SET revoked_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND revoked_at IS NULL",
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,357 @@
+// backend/src/services/tables.rs
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::CreateReservationRequest;
+use crate::services::references;
+
+// Resultado de intentar apartar lugares en un horario
+#[derive(Debug)]
+pub enum SlotAllocation {
+    Reserved,
+    // El restaurante no abre en ese horario o no coincide con el inicio de un turno
+    Closed,
+    Full { covers_left: i32 },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableReservation {
+    pub id: i32,
+    pub reference: String,
+    pub restaurant_id: i32,
+    pub restaurant_name: String,
+    pub restaurant_location: String,
+    pub reservation_date: NaiveDate,
+    pub slot_time: NaiveTime,
+    pub party_size: i32,
+    pub special_requests: Option<String>,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum ReservationError {
+    RestaurantUnavailable,
+    InvalidPartySize { min: i32, max: i32 },
+    Closed,
+    SlotPassed,
+    Full { covers_left: i32 },
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReservationError::RestaurantUnavailable => write!(f, "Restaurante no encontrado o no disponible"),
+            ReservationError::InvalidPartySize { min, max } => write!(
+                f,
+                "Este restaurante acepta reservaciones de {} a {} comensales",
+                min, max
+            ),
+            ReservationError::Closed => write!(f, "El restaurante no recibe reservaciones en ese horario"),
+            ReservationError::SlotPassed => write!(f, "Ese horario ya pasó; elige uno más tarde"),
+            ReservationError::Full { .. } => write!(f, "No hay lugares disponibles en ese horario"),
+            ReservationError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ReservationError {
+    fn from(e: sqlx::Error) -> Self {
+        ReservationError::Database(e)
+    }
+}
+
+// Rango de atención de un día
+#[derive(Debug, Clone)]
+pub struct OpeningRange {
+    pub opens_at: NaiveTime,
+    pub closes_at: NaiveTime,
+}
+
+// Horarios en los que se puede iniciar una reservación: cada `slot_minutes`
+// desde la apertura, hasta que el turno completo ya no cabe antes del cierre
+pub fn slots_for_day(ranges: &[OpeningRange], slot_minutes: i32) -> Vec<NaiveTime> {
+    let step = Duration::minutes(slot_minutes as i64);
+    let mut slots = Vec::new();
+
+    for range in ranges {
+        let mut slot = range.opens_at;
+        while slot < range.closes_at && range.closes_at.signed_duration_since(slot) >= step {
+            slots.push(slot);
+            let (next, wrapped) = slot.overflowing_add_signed(step);
+            if wrapped != 0 {
+                break;
+            }
+            slot = next;
+        }
+    }
+
+    slots.sort();
+    slots.dedup();
+    slots
+}
+
+// Un horario deja de poder reservarse en cuanto llega su hora de inicio
+pub fn slot_has_passed(date: NaiveDate, slot: NaiveTime, now: NaiveDateTime) -> bool {
+    date.and_time(slot) <= now
+}
+
+pub async fn opening_ranges(
+    conn: &mut PgConnection,
+    restaurant_id: i32,
+    date: NaiveDate,
+) -> Result<Vec<OpeningRange>, sqlx::Error> {
+    let weekday = date.weekday().number_from_monday() as i16;
+
+    sqlx::query_as!(
+        OpeningRange,
+        r#"
+        SELECT opens_at, closes_at FROM restaurant_opening_hours
+        WHERE restaurant_id = $1 AND weekday = $2
+        ORDER BY opens_at
+        "#,
+        restaurant_id,
+        weekday
+    )
+    .fetch_all(&mut *conn)
+    .await
+}
+
+// Aparta `party_size` lugares en el horario indicado.
+// Debe llamarse dentro de una transacción: el bloqueo de la fila del restaurante
+// serializa las reservaciones concurrentes hasta el COMMIT.
+pub async fn reserve_covers(
+    conn: &mut PgConnection,
+    restaurant_id: i32,
+    date: NaiveDate,
+    slot_time: NaiveTime,
+    party_size: i32,
+) -> Result<SlotAllocation, sqlx::Error> {
+    let restaurant = sqlx::query!(
+        "SELECT slot_minutes, covers_per_slot FROM restaurants WHERE id = $1 FOR UPDATE",
+        restaurant_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let ranges = opening_ranges(&mut *conn, restaurant_id, date).await?;
+    if !slots_for_day(&ranges, restaurant.slot_minutes).contains(&slot_time) {
+        return Ok(SlotAllocation::Closed);
+    }
+
+    let slot = sqlx::query!(
+        r#"
+        INSERT INTO restaurant_slot_inventory (restaurant_id, slot_date, slot_time, covers_booked)
+        VALUES ($1, $2, $3, 0)
+        ON CONFLICT (restaurant_id, slot_date, slot_time)
+            DO UPDATE SET updated_at = restaurant_slot_inventory.updated_at
+        RETURNING covers_booked
+        "#,
+        restaurant_id,
+        date,
+        slot_time
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let covers_left = restaurant.covers_per_slot - slot.covers_booked;
+    if covers_left < party_size {
+        println!(
+            "⛔ [TABLES] Restaurante {} sin cupo el {} a las {} (quedan {})",
+            restaurant_id, date, slot_time, covers_left
+        );
+        return Ok(SlotAllocation::Full { covers_left: covers_left.max(0) });
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE restaurant_slot_inventory
+        SET covers_booked = covers_booked + $4, updated_at = CURRENT_TIMESTAMP
+        WHERE restaurant_id = $1 AND slot_date = $2 AND slot_time = $3
+        "#,
+        restaurant_id,
+        date,
+        slot_time,
+        party_size
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(SlotAllocation::Reserved)
+}
+
+// Devuelve al horario los lugares de una reservación cancelada
+pub async fn release_covers(
+    conn: &mut PgConnection,
+    restaurant_id: i32,
+    date: NaiveDate,
+    slot_time: NaiveTime,
+    party_size: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE restaurant_slot_inventory
+        SET covers_booked = GREATEST(covers_booked - $4, 0), updated_at = CURRENT_TIMESTAMP
+        WHERE restaurant_id = $1 AND slot_date = $2 AND slot_time = $3
+        "#,
+        restaurant_id,
+        date,
+        slot_time,
+        party_size
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Aparta los lugares y registra la reservación. Debe llamarse dentro de una
+// transacción, igual que `reserve_covers`.
+pub async fn book_table(
+    conn: &mut PgConnection,
+    user_id: i32,
+    restaurant_id: i32,
+    reservation: &CreateReservationRequest,
+) -> Result<TableReservation, ReservationError> {
+    let restaurant = sqlx::query!(
+        r#"
+        SELECT name, location, min_party_size, max_party_size
+        FROM restaurants WHERE id = $1 AND status = 'approved'
+        "#,
+        restaurant_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(ReservationError::RestaurantUnavailable)?;
+
+    if reservation.party_size < restaurant.min_party_size
+        || reservation.party_size > restaurant.max_party_size
+    {
+        return Err(ReservationError::InvalidPartySize {
+            min: restaurant.min_party_size,
+            max: restaurant.max_party_size,
+        });
+    }
+
+    // Se puede reservar para hoy, pero no en un horario que ya pasó. Los horarios
+    // están en la hora local del servidor, igual que la validación de fecha
+    if slot_has_passed(reservation.reservation_date, reservation.slot_time, Local::now().naive_local()) {
+        return Err(ReservationError::SlotPassed);
+    }
+
+    match reserve_covers(
+        &mut *conn,
+        restaurant_id,
+        reservation.reservation_date,
+        reservation.slot_time,
+        reservation.party_size,
+    )
+    .await?
+    {
+        SlotAllocation::Reserved => {}
+        SlotAllocation::Closed => return Err(ReservationError::Closed),
+        SlotAllocation::Full { covers_left } => return Err(ReservationError::Full { covers_left }),
+    }
+
+    let reference = references::issue_reference(
+        &mut *conn,
+        references::TABLE_RESERVATION_PREFIX,
+        "table_reservation",
+    )
+    .await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO restaurant_reservations
+        (user_id, restaurant_id, reservation_date, slot_time, party_size, special_requests, reservation_reference)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, status, created_at
+        "#,
+        user_id,
+        restaurant_id,
+        reservation.reservation_date,
+        reservation.slot_time,
+        reservation.party_size,
+        reservation.special_requests,
+        reference
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(TableReservation {
+        id: created.id,
+        reference,
+        restaurant_id,
+        restaurant_name: restaurant.name,
+        restaurant_location: restaurant.location,
+        reservation_date: reservation.reservation_date,
+        slot_time: reservation.slot_time,
+        party_size: reservation.party_size,
+        special_requests: reservation.special_requests.clone(),
+        status: created.status,
+        created_at: created.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn range(opens: (u32, u32), closes: (u32, u32)) -> OpeningRange {
+        OpeningRange {
+            opens_at: time(opens.0, opens.1),
+            closes_at: time(closes.0, closes.1),
+        }
+    }
+
+    #[test]
+    fn el_ultimo_turno_puede_terminar_justo_al_cierre() {
+        let slots = slots_for_day(&[range((13, 0), (15, 0))], 60);
+        assert_eq!(slots, vec![time(13, 0), time(14, 0)]);
+    }
+
+    #[test]
+    fn no_ofrece_turnos_que_no_caben_antes_del_cierre() {
+        let slots = slots_for_day(&[range((13, 0), (14, 30))], 60);
+        assert_eq!(slots, vec![time(13, 0)]);
+
+        // Un rango más corto que el turno no tiene horarios
+        assert!(slots_for_day(&[range((13, 0), (13, 45))], 60).is_empty());
+    }
+
+    #[test]
+    fn los_horarios_avanzan_segun_el_intervalo() {
+        let slots = slots_for_day(&[range((18, 0), (23, 0))], 90);
+        assert_eq!(slots, vec![time(18, 0), time(19, 30), time(21, 0)]);
+    }
+
+    #[test]
+    fn varios_rangos_se_ordenan_sin_duplicados() {
+        let ranges = [range((19, 0), (21, 0)), range((13, 0), (15, 0)), range((19, 0), (20, 0))];
+        let slots = slots_for_day(&ranges, 60);
+        assert_eq!(slots, vec![time(13, 0), time(14, 0), time(19, 0), time(20, 0)]);
+    }
+
+    #[test]
+    fn rango_que_cierra_cerca_de_medianoche_no_da_la_vuelta() {
+        let slots = slots_for_day(&[range((22, 0), (23, 59))], 60);
+        assert_eq!(slots, vec![time(22, 0)]);
+    }
+
+    #[test]
+    fn se_puede_reservar_hoy_hasta_la_hora_del_turno() {
+        let today = NaiveDate::from_ymd_opt(2025, 8, 4).unwrap();
+        let now = today.and_time(time(19, 0));
+
+        assert!(!slot_has_passed(today, time(19, 30), now));
+        assert!(slot_has_passed(today, time(19, 0), now));
+        assert!(slot_has_passed(today, time(18, 30), now));
+        assert!(slot_has_passed(today.pred_opt().unwrap(), time(21, 0), now));
+    }
+}
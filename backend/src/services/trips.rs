@@ -0,0 +1,339 @@
+// backend/src/services/trips.rs
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+use crate::models::{BookingStatus, CreateTripRequest};
+use crate::money::{Currency, Money};
+use crate::services::bookings::{self, BookingError, BookingHolder, StayBooking};
+use crate::services::experiences::{self, ExperienceBooking, ExperienceError};
+use crate::services::inventory;
+use crate::services::products::{self, ProductError, ProductOrder};
+use crate::services::references;
+use crate::services::tables::{self, ReservationError, TableReservation};
+
+pub const TRIP_PREFIX: &str = "MT";
+
+// Máximo de reservas (sin contar artesanías) en un mismo viaje
+const MAX_TRIP_ITEMS: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trip {
+    pub id: i32,
+    pub reference: String,
+    pub name: Option<String>,
+    pub status: String,
+    pub payment_status: String,
+    pub hotels: Vec<StayBooking>,
+    pub experiences: Vec<ExperienceBooking>,
+    pub restaurant_reservations: Vec<TableReservation>,
+    pub product_order: Option<ProductOrder>,
+    pub total_price: Money,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+// Los errores de cada parte indican su posición en la petición (desde 1)
+#[derive(Debug)]
+pub enum TripError {
+    Empty,
+    TooManyItems,
+    MixedCurrency,
+    Hotel(usize, BookingError),
+    Experience(usize, ExperienceError),
+    Restaurant(usize, ReservationError),
+    Products(ProductError),
+    NotFound,
+    NotCancellable,
+    Database(sqlx::Error),
+}
+
+impl TripError {
+    // Sin lugar disponible: el cliente puede elegir otra fecha u horario
+    pub fn is_conflict(&self) -> bool {
+        matches!(
+            self,
+            TripError::Hotel(_, BookingError::SoldOut { .. })
+                | TripError::Experience(_, ExperienceError::SoldOut { .. })
+                | TripError::Restaurant(_, ReservationError::Full { .. })
+                | TripError::Products(ProductError::InsufficientStock { .. })
+        )
+    }
+}
+
+impl std::fmt::Display for TripError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TripError::Empty => write!(f, "El viaje debe incluir al menos una reserva o compra"),
+            TripError::TooManyItems => write!(f, "Un viaje admite máximo {} reservas", MAX_TRIP_ITEMS),
+            TripError::MixedCurrency => write!(f, "Todas las partes del viaje deben cobrarse en la misma moneda"),
+            TripError::Hotel(n, e) => write!(f, "Hotel #{}: {}", n, e),
+            TripError::Experience(n, e) => write!(f, "Experiencia #{}: {}", n, e),
+            TripError::Restaurant(n, e) => write!(f, "Reservación de restaurante #{}: {}", n, e),
+            TripError::Products(e) => write!(f, "Artesanías: {}", e),
+            TripError::NotFound => write!(f, "Viaje no encontrado"),
+            TripError::NotCancellable => write!(
+                f,
+                "Solo se puede cancelar completo un viaje pendiente de pago; cancela cada reserva por separado"
+            ),
+            TripError::Database(e) => write!(f, "Error de base de datos: {}", e),
+        }
+    }
+}
+
+impl From<sqlx::Error> for TripError {
+    fn from(e: sqlx::Error) -> Self {
+        TripError::Database(e)
+    }
+}
+
+// Reserva todas las partes del viaje con los mismos servicios que usan los
+// endpoints individuales. Debe llamarse dentro de una transacción: si una sola
+// parte falla, el llamador descarta la transacción y no queda nada apartado.
+// El viaje queda pendiente hasta que se cobra completo (payments::create_trip_intent).
+pub async fn book_trip(
+    conn: &mut PgConnection,
+    user_id: i32,
+    trip: &CreateTripRequest,
+) -> Result<Trip, TripError> {
+    let items = trip.hotels.len() + trip.experiences.len() + trip.restaurant_reservations.len();
+    if items == 0 && trip.products.is_none() {
+        return Err(TripError::Empty);
+    }
+    if items > MAX_TRIP_ITEMS {
+        return Err(TripError::TooManyItems);
+    }
+
+    let mut totals: Vec<Money> = Vec::new();
+
+    let mut hotels = Vec::with_capacity(trip.hotels.len());
+    for (i, hotel) in trip.hotels.iter().enumerate() {
+        let booking = bookings::book_stay(&mut *conn, BookingHolder::User(user_id), hotel)
+            .await
+            .map_err(|e| match e {
+                BookingError::Database(e) => TripError::Database(e),
+                e => TripError::Hotel(i + 1, e),
+            })?;
+        totals.push(booking.total_price.clone());
+        hotels.push(booking);
+    }
+
+    let mut experience_bookings = Vec::with_capacity(trip.experiences.len());
+    for (i, item) in trip.experiences.iter().enumerate() {
+        let booking = experiences::book_departure(&mut *conn, user_id, item.experience_id, &item.booking)
+            .await
+            .map_err(|e| match e {
+                ExperienceError::Database(e) => TripError::Database(e),
+                e => TripError::Experience(i + 1, e),
+            })?;
+        totals.push(booking.total_price.clone());
+        experience_bookings.push(booking);
+    }
+
+    // Las reservaciones de mesa no tienen costo; solo se apartan lugares
+    let mut reservations = Vec::with_capacity(trip.restaurant_reservations.len());
+    for (i, item) in trip.restaurant_reservations.iter().enumerate() {
+        let reservation = tables::book_table(&mut *conn, user_id, item.restaurant_id, &item.reservation)
+            .await
+            .map_err(|e| match e {
+                ReservationError::Database(e) => TripError::Database(e),
+                e => TripError::Restaurant(i + 1, e),
+            })?;
+        reservations.push(reservation);
+    }
+
+    let product_order = match &trip.products {
+        Some(order) => {
+            let order = products::purchase_products(&mut *conn, user_id, order)
+                .await
+                .map_err(|e| match e {
+                    ProductError::Database(e) => TripError::Database(e),
+                    e => TripError::Products(e),
+                })?;
+            totals.push(order.total_price.clone());
+            Some(order)
+        }
+        None => None,
+    };
+
+    let currency = totals.first().map(|t| t.currency()).unwrap_or(Currency::MXN);
+    if totals.iter().any(|t| t.currency() != currency) {
+        return Err(TripError::MixedCurrency);
+    }
+    let total_price = totals
+        .into_iter()
+        .fold(Money::zero(currency), |total, part| total + part);
+
+    let reference = references::issue_reference(&mut *conn, TRIP_PREFIX, "trip").await?;
+
+    let created = sqlx::query!(
+        r#"
+        INSERT INTO trips (user_id, trip_reference, name, total_price, currency)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, status, payment_status, created_at
+        "#,
+        user_id,
+        reference,
+        trip.name,
+        total_price.amount(),
+        total_price.currency().as_str()
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let hotel_ids: Vec<i32> = hotels.iter().map(|b| b.id).collect();
+    sqlx::query!("UPDATE bookings SET trip_id = $1 WHERE id = ANY($2)", created.id, &hotel_ids[..])
+        .execute(&mut *conn)
+        .await?;
+
+    let experience_ids: Vec<i32> = experience_bookings.iter().map(|b| b.id).collect();
+    sqlx::query!("UPDATE experience_bookings SET trip_id = $1 WHERE id = ANY($2)", created.id, &experience_ids[..])
+        .execute(&mut *conn)
+        .await?;
+
+    let reservation_ids: Vec<i32> = reservations.iter().map(|r| r.id).collect();
+    sqlx::query!("UPDATE restaurant_reservations SET trip_id = $1 WHERE id = ANY($2)", created.id, &reservation_ids[..])
+        .execute(&mut *conn)
+        .await?;
+
+    if let Some(order) = &product_order {
+        sqlx::query!("UPDATE product_orders SET trip_id = $1 WHERE id = $2", created.id, order.id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(Trip {
+        id: created.id,
+        reference,
+        name: trip.name.clone(),
+        status: created.status,
+        payment_status: created.payment_status,
+        hotels,
+        experiences: experience_bookings,
+        restaurant_reservations: reservations,
+        product_order,
+        total_price,
+        created_at: created.created_at,
+    })
+}
+
+// Cancela un viaje que todavía no se paga y devuelve al inventario cada una de
+// sus partes: habitaciones, lugares de experiencias, mesas y piezas de artesanía.
+// Debe llamarse dentro de una transacción. Devuelve la referencia del viaje.
+pub async fn cancel_trip(
+    conn: &mut PgConnection,
+    user_id: i32,
+    trip_id: i32,
+    reason: Option<&str>,
+) -> Result<String, TripError> {
+    // El bloqueo de la fila evita cancelar un viaje mientras se está cobrando
+    let trip = sqlx::query!(
+        "SELECT trip_reference, status, payment_status FROM trips WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        trip_id,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(TripError::NotFound)?;
+
+    if trip.status != "pending" || trip.payment_status == "paid" {
+        return Err(TripError::NotCancellable);
+    }
+
+    // Las reservas rechazadas ya devolvieron sus habitaciones
+    let stays = sqlx::query!(
+        r#"
+        UPDATE bookings b SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $2, updated_at = CURRENT_TIMESTAMP
+        FROM (
+            SELECT id, status FROM bookings
+            WHERE trip_id = $1 AND status NOT IN ('cancelled', 'rejected')
+            FOR UPDATE
+        ) previous
+        WHERE b.id = previous.id
+        RETURNING b.id, previous.status as "from_status!", b.hotel_id, b.check_in, b.check_out, b.rooms
+        "#,
+        trip_id,
+        reason
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for stay in &stays {
+        inventory::release_rooms(&mut *conn, stay.hotel_id, stay.check_in, stay.check_out, stay.rooms).await?;
+        bookings::record_status_change(
+            &mut *conn,
+            stay.id,
+            BookingStatus::from_db(&stay.from_status),
+            BookingStatus::Cancelled,
+            Some(user_id),
+            reason,
+        )
+        .await?;
+    }
+
+    let departures = sqlx::query!(
+        r#"
+        UPDATE experience_bookings SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE trip_id = $1 AND status = 'confirmed'
+        RETURNING departure_id, participants
+        "#,
+        trip_id,
+        reason
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for departure in &departures {
+        experiences::release_seats(&mut *conn, departure.departure_id, departure.participants).await?;
+    }
+
+    let tables_booked = sqlx::query!(
+        r#"
+        UPDATE restaurant_reservations SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE trip_id = $1 AND status = 'confirmed'
+        RETURNING restaurant_id, reservation_date, slot_time, party_size
+        "#,
+        trip_id,
+        reason
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for table in &tables_booked {
+        tables::release_covers(
+            &mut *conn,
+            table.restaurant_id,
+            table.reservation_date,
+            table.slot_time,
+            table.party_size,
+        )
+        .await?;
+    }
+
+    let orders = sqlx::query!(
+        r#"
+        UPDATE product_orders SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP
+        WHERE trip_id = $1 AND status = 'confirmed'
+        RETURNING id
+        "#,
+        trip_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for order in &orders {
+        products::restock_order(&mut *conn, order.id).await?;
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE trips SET status = 'cancelled', cancelled_at = CURRENT_TIMESTAMP,
+            cancellation_reason = $2, updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+        trip_id,
+        reason
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(trip.trip_reference)
+}
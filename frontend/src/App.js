@@ -23,11 +23,15 @@ import ExperienciasPage from './pages/ExperienciasPage';
 import ArtesanosPage from './pages/ArtesanosPage';
 import TransportePage from './pages/TransportePage';
 import PortalPage from './pages/PortalPage';
+import apiService from './services/api';
+
+const emptyCart = { items: [], total_items: 0, total: null };
 
 function App() {
     const [introComplete, setIntroComplete] = useState(false);
     const [modalState, setModalState] = useState({ type: null, data: null });
-    const [cart, setCart] = useState([]);
+    // Carrito guardado en el servidor: { items, total_items, total }
+    const [cart, setCart] = useState(emptyCart);
     const [isLoggedIn, setIsLoggedIn] = useState(false);
     const [userData, setUserData] = useState(null);
     const [pendingAction, setPendingAction] = useState(null);
@@ -44,15 +48,51 @@ function App() {
     const openModal = (type, data = null) => setModalState({ type, data });
     const closeModal = () => setModalState({ type: null, data: null });
 
+    const refreshCart = async () => {
+        try {
+            setCart(await apiService.getCart());
+        } catch (error) {
+            console.error('Error cargando el carrito:', error);
+        }
+    };
+
+    const addToServerCart = async (product) => {
+        try {
+            const data = await apiService.addCartItem(product.id);
+            setCart(data.cart);
+            alert(data.warning ? `${product.name} agregado al carrito. ${data.warning}` : `${product.name} agregado al carrito!`);
+        } catch (error) {
+            alert(`❌ ${error.message}`);
+        }
+    };
+
     // ✅ FUNCIÓN: Agregar productos al carrito
     const handleAddToCart = (product) => {
-        setCart(prevCart => [...prevCart, product]);
-        alert(`${product.name} agregado al carrito!`);
+        if (!isLoggedIn) {
+            setPendingAction({ type: 'cart', data: product });
+            openModal('register');
+            return;
+        }
+        addToServerCart(product);
     };
 
     // ✅ FUNCIÓN: Remover productos del carrito
-    const handleRemoveFromCart = (productId) => {
-        setCart(prevCart => prevCart.filter(item => item.id !== productId));
+    const handleRemoveFromCart = async (productId) => {
+        try {
+            const data = await apiService.removeCartItem(productId);
+            setCart(data.cart);
+        } catch (error) {
+            alert(`❌ ${error.message}`);
+        }
+    };
+
+    const handleUpdateCartQuantity = async (productId, quantity) => {
+        try {
+            const data = await apiService.updateCartItem(productId, quantity);
+            setCart(data.cart);
+        } catch (error) {
+            alert(`❌ ${error.message}`);
+        }
     };
 
     // ✅ FUNCIÓN MODIFICADA: Manejar confirmaciones de reservas - AHORA GUARDA REAL
@@ -106,35 +146,36 @@ function App() {
         closeModal();
     };
 
-    // ✅ NUEVA FUNCIÓN: Procesar compras del carrito
-    const handleCartCheckout = () => {
-        if (cart.length === 0) return;
-        
-        if (!isLoggedIn) {
-            setPendingAction({ type: 'cart', data: { items: cart, total: cart.reduce((sum, item) => sum + item.price, 0) } });
-            openModal('register');
-            return;
-        }
+    // ✅ NUEVA FUNCIÓN: Procesar compras del carrito (el servidor recalcula precios y aparta inventario)
+    const handleCartCheckout = async (shippingAddress) => {
+        if (cart.items.length === 0) return;
 
-        const total = cart.reduce((sum, item) => sum + item.price, 0);
-        const timestamp = new Date().toISOString();
-        
-        const purchaseRecord = {
-            id: Date.now(),
-            items: [...cart],
-            total: total,
-            date: timestamp,
-            status: 'confirmed'
-        };
+        try {
+            const { order } = await apiService.checkoutCart({ shipping_address: shippingAddress });
+            const total = parseFloat(order.total_price.amount);
+
+            const purchaseRecord = {
+                id: order.id,
+                reference: order.reference,
+                items: order.items,
+                total: total,
+                date: order.created_at,
+                status: order.status
+            };
 
-        setUserHistory(prevHistory => ({
-            ...prevHistory,
-            purchases: [...prevHistory.purchases, purchaseRecord],
-            totalSpent: prevHistory.totalSpent + total
-        }));
+            setUserHistory(prevHistory => ({
+                ...prevHistory,
+                purchases: [...prevHistory.purchases, purchaseRecord],
+                totalSpent: prevHistory.totalSpent + total
+            }));
 
-        setCart([]); // Vaciar carrito
-        alert(`✅ Compra realizada por $${total.toFixed(2)} MXN`);
+            setCart(emptyCart);
+            closeModal();
+            alert(`✅ Compra ${order.reference} realizada por $${order.total_price.amount} ${order.total_price.currency}`);
+        } catch (error) {
+            alert(`❌ ${error.message}`);
+            refreshCart();
+        }
     };
 
     // ✅ FUNCIÓN: Manejar registro de usuarios
@@ -143,10 +184,12 @@ function App() {
         setIsLoggedIn(true);
         closeModal();
         
+        refreshCart();
+
         // Si había una acción pendiente, ejecutarla ahora
         if (pendingAction) {
             if (pendingAction.type === 'cart') {
-                handleCartCheckout();
+                addToServerCart(pendingAction.data);
             } else {
                 handleConfirmAction(pendingAction.type, pendingAction.data);
             }
@@ -162,10 +205,12 @@ function App() {
         setIsLoggedIn(true);
         closeModal();
         
+        refreshCart();
+
         // Si había una acción pendiente, ejecutarla ahora
         if (pendingAction) {
             if (pendingAction.type === 'cart') {
-                handleCartCheckout();
+                addToServerCart(pendingAction.data);
             } else {
                 handleConfirmAction(pendingAction.type, pendingAction.data);
             }
@@ -179,7 +224,7 @@ function App() {
     const handleLogout = () => {
         setUserData(null);
         setIsLoggedIn(false);
-        setCart([]);
+        setCart(emptyCart);
         setPendingAction(null);
         // ✅ MANTENER EL HISTORIAL (en una app real esto se guardaría en base de datos)
         // setUserHistory({ hotels: [], restaurants: [], experiences: [], purchases: [], totalSpent: 0 });
@@ -193,7 +238,7 @@ function App() {
     return (
         <div className="antialiased">
             <Header 
-                cartItemCount={cart.length}
+                cartItemCount={cart.items.reduce((sum, item) => sum + item.quantity, 0)}
                 onCartClick={() => openModal('cart')}
                 onLoginClick={() => openModal('register')}
                 onLogout={handleLogout}
@@ -256,8 +301,8 @@ function App() {
                 {modalState.type === 'hotel' && <HotelBookingForm hotel={modalState.data} onConfirm={handleConfirmAction} />}
                 {modalState.type === 'restaurant' && <RestaurantMenu restaurant={modalState.data} onConfirm={handleConfirmAction} />}
                 {/* ✅ PASAR LA FUNCIÓN DE CHECKOUT REAL */}
-                {modalState.type === 'cart' && <CartModal cartItems={cart} onRemoveItem={handleRemoveFromCart} onCheckout={handleCartCheckout} />}
-                {modalState.type === 'transporte' && <TransporteModal />}
+                {modalState.type === 'cart' && <CartModal cart={cart} onRemoveItem={handleRemoveFromCart} onUpdateQuantity={handleUpdateCartQuantity} onCheckout={handleCartCheckout} />}
+                {modalState.type === 'transporte' && <TransporteModal isLoggedIn={isLoggedIn} />}
                 {modalState.type === 'register' && (
                     <RegistrationModal 
                         onRegister={handleRegistration} 
@@ -1,24 +1,41 @@
 // src/components/CaballosBookingForm.js
 
-import { useState, useEffect } from 'react';
+import useExperienceBooking, { toHistoryRecord } from '../hooks/useExperienceBooking';
+import DepartureSelect from './DepartureSelect';
 
-export default function CaballosBookingForm({ experience }) {
-    const [jinetes, setJinetes] = useState(1);
-    const [total, setTotal] = useState(experience.price);
+export default function CaballosBookingForm({ experience, onConfirm }) {
+    const booking = useExperienceBooking(experience);
+    const riderFare = booking.fares[0];
+    const jinetes = riderFare ? booking.quantities[riderFare.fare_type] || 0 : 0;
 
-    useEffect(() => {
-        setTotal(jinetes * experience.price);
-    }, [jinetes, experience.price]);
-
-    const handleSubmit = (e) => {
+    const handleSubmit = async (e) => {
         e.preventDefault();
-        alert(`Reserva para ${experience.name} para ${jinetes} persona(s) por $${total.toFixed(2)} MXN confirmada (simulación).`);
+        try {
+            const created = await booking.submitBooking();
+            onConfirm('experience', toHistoryRecord(experience, created));
+        } catch (err) {
+            alert(err.message);
+        }
     };
 
+    if (booking.isLoading) {
+        return <p className="text-gray-500">Cargando salidas...</p>;
+    }
+
+    if (booking.error || !riderFare) {
+        return <p className="text-red-500">{booking.error || 'Esta experiencia no tiene tarifas disponibles.'}</p>;
+    }
+
     return (
         <div>
             <h2 className="text-3xl font-bold mb-4">Reserva tu {experience.name}</h2>
             <form onSubmit={handleSubmit}>
+                <DepartureSelect
+                    label="Horario del Paseo"
+                    departures={booking.departures}
+                    value={booking.departureId}
+                    onChange={booking.setDepartureId}
+                />
                 <div className="mb-4">
                     <label className="block text-gray-700 font-semibold">Elige tu Caballo</label>
                     <select className="w-full p-2 border rounded-lg mt-1">
@@ -27,21 +44,27 @@ export default function CaballosBookingForm({ experience }) {
                     </select>
                 </div>
                 <div className="mb-4">
-                    <label className="block text-gray-700 font-semibold">Jinetes</label>
-                    <input type="number" value={jinetes} onChange={(e) => setJinetes(e.target.value)} min="1" className="w-full p-2 border rounded-lg mt-1" />
+                    <label className="block text-gray-700 font-semibold">Jinetes (${riderFare.price.amount} c/u)</label>
+                    <input type="number" value={jinetes} onChange={(e) => booking.setQuantity(riderFare.fare_type, e.target.value)} min="1" className="w-full p-2 border rounded-lg mt-1" />
                 </div>
                 <div className="p-4 bg-yellow-100 border-l-4 border-yellow-500 text-yellow-700 mb-4">
                     <p className="font-bold">Requisitos:</p>
                     <ul className="list-disc list-inside text-sm">
-                        <li>Edad mínima: 12 años</li>
-                        <li>Peso máximo: 100 kg</li>
+                        {booking.details?.min_age && <li>Edad mínima: {booking.details.min_age} años</li>}
+                        {booking.details?.requirements && <li>{booking.details.requirements}</li>}
                     </ul>
                 </div>
                 <div className="mt-6 bg-gray-100 p-4 rounded-lg text-right">
-                    <div className="font-bold text-2xl">Total: ${total.toFixed(2)} MXN</div>
+                    <div className="font-bold text-2xl">Total: ${booking.total.toFixed(2)} MXN</div>
                 </div>
-                <button type="submit" className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4">Reservar Paseo</button>
+                <button 
+                    type="submit" 
+                    disabled={booking.isSubmitting || jinetes === 0 || !booking.departureId} 
+                    className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4"
+                >
+                    {booking.isSubmitting ? 'Reservando...' : 'Reservar Paseo'}
+                </button>
             </form>
         </div>
     );
-}
\ No newline at end of file
+}
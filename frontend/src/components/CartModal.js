@@ -1,11 +1,12 @@
 // src/components/CartModal.js
 
-export default function CartModal({ cartItems, onRemoveItem }) {
-    const total = cartItems.reduce((sum, item) => sum + item.price, 0);
+import { useState } from 'react';
 
-    const handleCheckout = () => {
-        alert('Procediendo al pago (simulación)...');
-    };
+export default function CartModal({ cart, onRemoveItem, onUpdateQuantity, onCheckout }) {
+    const [shippingAddress, setShippingAddress] = useState('');
+    const cartItems = cart.items;
+    // El total lo calcula el servidor con los precios vigentes del catálogo
+    const canCheckout = cartItems.length > 0 && cartItems.every(item => item.available) && shippingAddress.trim().length >= 10;
 
     return (
         <div>
@@ -14,32 +15,57 @@ export default function CartModal({ cartItems, onRemoveItem }) {
                 {cartItems.length === 0 ? (
                     <p className="text-gray-500">Tu carrito está vacío.</p>
                 ) : (
-                    cartItems.map((item, index) => (
-                        <div key={index} className="flex justify-between items-center border-b pb-2">
+                    cartItems.map(item => (
+                        <div key={item.product_id} className="flex justify-between items-center border-b pb-2">
                             <div>
                                 <h4 className="font-bold">{item.name}</h4>
-                                <p className="text-sm text-gray-500">${item.price}</p>
+                                <p className="text-sm text-gray-500">${item.unit_price.amount} {item.unit_price.currency} c/u</p>
+                                {!item.available && (
+                                    <p className="text-xs text-red-500">Solo quedan {item.stock} piezas disponibles</p>
+                                )}
+                            </div>
+                            <div className="flex items-center gap-3">
+                                <input
+                                    type="number"
+                                    min="1"
+                                    max="20"
+                                    value={item.quantity}
+                                    onChange={(e) => onUpdateQuantity(item.product_id, parseInt(e.target.value, 10) || 1)}
+                                    className="w-16 p-1 border rounded-md text-center"
+                                />
+                                <button onClick={() => onRemoveItem(item.product_id)} className="text-red-500 hover:text-red-700 font-bold">
+                                    Quitar
+                                </button>
                             </div>
-                            <button onClick={() => onRemoveItem(item.id)} className="text-red-500 hover:text-red-700 font-bold">
-                                Quitar
-                            </button>
                         </div>
                     ))
                 )}
             </div>
+            {cartItems.length > 0 && (
+                <div className="mt-6">
+                    <label className="block text-gray-700 font-semibold mb-1">Dirección de envío</label>
+                    <textarea
+                        value={shippingAddress}
+                        onChange={(e) => setShippingAddress(e.target.value)}
+                        rows="2"
+                        placeholder="Calle, número, colonia, ciudad y código postal"
+                        className="w-full p-2 border rounded-md"
+                    />
+                </div>
+            )}
             <div className="mt-6 pt-4 border-t-2 border-dashed text-right">
                 <span className="text-2xl font-bold">Total: </span>
                 <span className="text-2xl font-bold" style={{ color: 'var(--theme-primary)' }}>
-                    ${total.toFixed(2)} MXN
+                    ${cart.total ? cart.total.amount : '0.00'} {cart.total ? cart.total.currency : 'MXN'}
                 </span>
             </div>
-            <button 
-                onClick={handleCheckout} 
-                disabled={cartItems.length === 0}
+            <button
+                onClick={() => onCheckout(shippingAddress.trim())}
+                disabled={!canCheckout}
                 className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-6 disabled:bg-gray-400 disabled:shadow-none disabled:cursor-not-allowed"
             >
                 Proceder al Pago
             </button>
         </div>
     );
-}
\ No newline at end of file
+}
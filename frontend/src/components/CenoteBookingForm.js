@@ -1,52 +1,65 @@
 // src/components/CenoteBookingForm.js
 
-import { useState, useEffect } from 'react';
+import useExperienceBooking, { toHistoryRecord } from '../hooks/useExperienceBooking';
+import DepartureSelect from './DepartureSelect';
 
-const ticketTypes = [
-    { name: 'Acceso General', price: 450 },
-    { name: 'Acceso con Snorkel', price: 650 }
-];
+export default function CenoteBookingForm({ experience, onConfirm }) {
+    const booking = useExperienceBooking(experience);
 
-export default function CenoteBookingForm({ experience }) {
-    const [personas, setPersonas] = useState(1);
-    const [ticketType, setTicketType] = useState(ticketTypes[0]);
-    const [total, setTotal] = useState(ticketTypes[0].price);
-
-    useEffect(() => {
-        setTotal(personas * ticketType.price);
-    }, [personas, ticketType]);
-
-    const handleTypeChange = (e) => {
-        const selectedType = ticketTypes.find(t => t.price == e.target.value);
-        setTicketType(selectedType);
-    };
-
-    const handleSubmit = (e) => {
+    const handleSubmit = async (e) => {
         e.preventDefault();
-        alert(`Entradas para ${experience.name} (${ticketType.name}) para ${personas} persona(s) por $${total.toFixed(2)} MXN confirmadas (simulación).`);
+        try {
+            const created = await booking.submitBooking();
+            onConfirm('experience', toHistoryRecord(experience, created));
+        } catch (err) {
+            alert(err.message);
+        }
     };
 
+    if (booking.isLoading) {
+        return <p className="text-gray-500">Cargando horarios...</p>;
+    }
+
+    if (booking.error) {
+        return <p className="text-red-500">{booking.error}</p>;
+    }
+
     return (
         <div>
             <h2 className="text-3xl font-bold mb-4">Entradas para {experience.name}</h2>
             <form onSubmit={handleSubmit}>
-                <div className="mb-4">
-                    <label className="block text-gray-700 font-semibold">Personas</label>
-                    <input type="number" value={personas} onChange={(e) => setPersonas(e.target.value)} min="1" className="w-full p-2 border rounded-lg mt-1" />
-                </div>
+                <DepartureSelect
+                    label="Horario de Acceso"
+                    departures={booking.departures}
+                    value={booking.departureId}
+                    onChange={booking.setDepartureId}
+                />
                 <div className="mb-4">
                     <label className="block text-gray-700 font-semibold">Tipo de Entrada</label>
-                    <select onChange={handleTypeChange} className="w-full p-2 border rounded-lg mt-1">
-                        {ticketTypes.map(type => (
-                            <option key={type.name} value={type.price}>{type.name} (${type.price})</option>
-                        ))}
-                    </select>
+                    {booking.fares.map(fare => (
+                        <div key={fare.fare_type} className="flex justify-between items-center mt-2">
+                            <span>{fare.label} (${fare.price.amount})</span>
+                            <input 
+                                type="number" 
+                                value={booking.quantities[fare.fare_type] || 0} 
+                                onChange={(e) => booking.setQuantity(fare.fare_type, e.target.value)} 
+                                min="0" 
+                                className="w-20 p-2 border rounded-lg" 
+                            />
+                        </div>
+                    ))}
                 </div>
                 <div className="mt-6 bg-gray-100 p-4 rounded-lg text-right">
-                    <div className="font-bold text-2xl">Total: ${total.toFixed(2)} MXN</div>
+                    <div className="font-bold text-2xl">Total: ${booking.total.toFixed(2)} MXN</div>
                 </div>
-                <button type="submit" className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4">Comprar Entradas</button>
+                <button 
+                    type="submit" 
+                    disabled={booking.isSubmitting || booking.participants === 0 || !booking.departureId} 
+                    className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4"
+                >
+                    {booking.isSubmitting ? 'Comprando...' : 'Comprar Entradas'}
+                </button>
             </form>
         </div>
     );
-}
\ No newline at end of file
+}
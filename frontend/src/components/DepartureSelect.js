@@ -0,0 +1,26 @@
+// src/components/DepartureSelect.js
+
+// Selector de salidas programadas con lugares disponibles
+export default function DepartureSelect({ label, departures, value, onChange }) {
+    return (
+        <div className="mb-4">
+            <label className="block text-gray-700 font-semibold">{label}</label>
+            {departures.length === 0 ? (
+                <p className="text-sm text-gray-500 mt-1">No hay salidas disponibles por ahora.</p>
+            ) : (
+                <select
+                    value={value}
+                    onChange={(e) => onChange(e.target.value)}
+                    className="w-full p-2 border rounded-lg mt-1"
+                    required
+                >
+                    {departures.map(d => (
+                        <option key={d.id} value={d.id} disabled={d.seats_left <= 0}>
+                            {new Date(`${d.date}T00:00:00`).toLocaleDateString('es-MX')} - {d.time.slice(0, 5)} ({d.seats_left > 0 ? `${d.seats_left} lugares` : 'agotado'})
+                        </option>
+                    ))}
+                </select>
+            )}
+        </div>
+    );
+}
@@ -1,12 +1,7 @@
 // src/components/HotelBookingForm.js - VERSIÓN CORREGIDA CON MEJOR MANEJO DE ERRORES
 
 import { useState, useEffect } from 'react';
-
-const hotelAddonsData = [
-    { name: 'Tour Romántico', price: 1500, icon: '💖' },
-    { name: 'Paquete Luna de Miel', price: 3500, icon: '🥂' },
-    { name: 'Acceso a Spa', price: 800, icon: '💆‍♀️' }
-];
+import apiService from '../services/api';
 
 export default function HotelBookingForm({ hotel, onConfirm }) {
     const [checkinDate, setCheckinDate] = useState('');
@@ -17,6 +12,8 @@ export default function HotelBookingForm({ hotel, onConfirm }) {
     const [specialRequests, setSpecialRequests] = useState('');
     const [isSubmitting, setIsSubmitting] = useState(false);
     const [error, setError] = useState('');
+    const [soldOut, setSoldOut] = useState(false);
+    const [hotelAddons, setHotelAddons] = useState([]);
     
     // --- ESTADO PARA EL DESGLOSE ---
     const [itinerary, setItinerary] = useState([]);
@@ -45,20 +42,51 @@ export default function HotelBookingForm({ hotel, onConfirm }) {
             }
         }
 
-        // Agregar extras
+        // Agregar extras (estimado; el servidor calcula el precio final)
         selectedAddons.forEach(addon => {
-            newItinerary.push({ description: addon.name, price: addon.price });
-            newTotal += addon.price;
+            const price = Number(addon.price.amount);
+            newItinerary.push({ description: addon.name, price });
+            newTotal += price;
         });
         
         setItinerary(newItinerary);
         setTotal(newTotal);
     }, [checkinDate, checkoutDate, rooms, selectedAddons, hotel.price]);
 
+    // Catálogo de servicios adicionales del hotel (precios definidos por el servidor)
+    useEffect(() => {
+        apiService.getHotelAddons(hotel.id)
+            .then(result => setHotelAddons(result.addons || []))
+            .catch(err => console.error('❌ [FRONTEND] Error cargando servicios adicionales:', err));
+    }, [hotel.id]);
+
+    // Consultar disponibilidad real del hotel para las fechas seleccionadas
+    useEffect(() => {
+        if (!checkinDate || !checkoutDate || new Date(checkoutDate) <= new Date(checkinDate)) {
+            setSoldOut(false);
+            return;
+        }
+
+        let cancelled = false;
+        apiService.getHotelAvailability({
+            checkIn: checkinDate,
+            checkOut: checkoutDate,
+            guests: parseInt(guests),
+            rooms: parseInt(rooms),
+        })
+            .then(result => {
+                if (cancelled || !result.hotels) return;
+                setSoldOut(!result.hotels.some(h => h.id === hotel.id));
+            })
+            .catch(err => console.error('❌ [FRONTEND] Error consultando disponibilidad:', err));
+
+        return () => { cancelled = true; };
+    }, [checkinDate, checkoutDate, guests, rooms, hotel.id]);
+
     const handleAddonToggle = (addon) => {
         setSelectedAddons(prevAddons => 
-            prevAddons.find(a => a.name === addon.name)
-                ? prevAddons.filter(a => a.name !== addon.name)
+            prevAddons.find(a => a.id === addon.id)
+                ? prevAddons.filter(a => a.id !== addon.id)
                 : [...prevAddons, addon]
         );
     };
@@ -83,6 +111,12 @@ export default function HotelBookingForm({ hotel, onConfirm }) {
             return;
         }
 
+        if (soldOut) {
+            setError('No hay habitaciones disponibles para las fechas seleccionadas');
+            setIsSubmitting(false);
+            return;
+        }
+
         // Verificar autenticación
         const token = localStorage.getItem('auth_token');
         const userData = localStorage.getItem('user_data');
@@ -105,7 +139,9 @@ export default function HotelBookingForm({ hotel, onConfirm }) {
             guests: parseInt(guests),
             rooms: parseInt(rooms),
             special_requests: specialRequests.trim() || null,
-            addon_services: selectedAddons.length > 0 ? selectedAddons : null
+            addon_services: selectedAddons.length > 0
+                ? selectedAddons.map(addon => ({ addon_id: addon.id, quantity: 1 }))
+                : null
         };
 
         console.log('📦 [FRONTEND] Datos de reserva preparados:', bookingData);
@@ -151,7 +187,7 @@ Hotel: ${result.booking?.hotel_name || hotel.name}
 Referencia: ${result.booking?.reference || 'N/A'}
 Check-in: ${result.booking?.check_in || checkinDate}
 Check-out: ${result.booking?.check_out || checkoutDate}
-Total: ${result.booking?.total_price?.toFixed(2) || total.toFixed(2)} MXN
+Total: ${result.booking?.total_price?.amount || total.toFixed(2)} ${result.booking?.total_price?.currency || 'MXN'}
 
 Estado: ${result.booking?.status || 'pendiente'}
 
@@ -302,21 +338,21 @@ Estado: ${result.booking?.status || 'pendiente'}
                         <div>
                             <h4 className="font-bold text-lg mb-2">Mejora tu Estancia</h4>
                             <div className="space-y-3">
-                                {hotelAddonsData.map(addon => (
-                                    <div key={addon.name} className="flex justify-between items-center p-2 rounded-lg hover:bg-gray-100">
+                                {hotelAddons.map(addon => (
+                                    <div key={addon.id} className="flex justify-between items-center p-2 rounded-lg hover:bg-gray-100">
                                         <div>
                                             <span className="font-bold">{addon.icon} {addon.name}</span>
-                                            <span className="text-sm text-gray-500"> +${addon.price}</span>
+                                            <span className="text-sm text-gray-500"> +${addon.price.amount}</span>
                                         </div>
                                         <button 
                                             type="button" 
                                             onClick={() => handleAddonToggle(addon)} 
-                                            className={`${selectedAddons.find(a => a.name === addon.name) 
+                                            className={`${selectedAddons.find(a => a.id === addon.id) 
                                                 ? 'bg-red-500 hover:bg-red-600' 
                                                 : 'btn-primary'
                                             } text-white text-xs font-bold py-1 px-3 rounded-full transition-colors`}
                                         >
-                                            {selectedAddons.find(a => a.name === addon.name) ? 'Quitar' : 'Añadir'}
+                                            {selectedAddons.find(a => a.id === addon.id) ? 'Quitar' : 'Añadir'}
                                         </button>
                                     </div>
                                 ))}
@@ -345,6 +381,12 @@ Estado: ${result.booking?.status || 'pendiente'}
                              Total: ${total.toFixed(2)} MXN
                          </div>
 
+                         {soldOut && (
+                             <p className="mt-2 text-sm text-red-600 text-right">
+                                 Sin disponibilidad para estas fechas. Prueba con otras.
+                             </p>
+                         )}
+
                          {/* Estado de debug */}
                          {process.env.NODE_ENV === 'development' && (
                              <div className="mt-4 p-2 bg-blue-50 rounded text-xs">
@@ -360,9 +402,9 @@ Estado: ${result.booking?.status || 'pendiente'}
                 
                 <button 
                     type="submit" 
-                    disabled={isSubmitting || total === 0}
+                    disabled={isSubmitting || total === 0 || soldOut}
                     className={`w-full font-bold py-3 px-4 rounded-full mt-6 transition-colors ${
-                        isSubmitting || total === 0
+                        isSubmitting || total === 0 || soldOut
                             ? 'bg-gray-400 cursor-not-allowed'
                             : 'btn-primary hover:bg-blue-600'
                     }`}
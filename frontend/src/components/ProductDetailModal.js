@@ -48,8 +48,8 @@ export default function ProductDetailModal({ product, onAddToCart, onClose }) {
             <div>
                 <h3 className="text-3xl font-bold">{product.name}</h3>
                 <p className="text-gray-500 mb-4">Por {product.artisan}</p>
-                <p className="text-gray-700 mb-4">{product.desc}</p>
-                <p className="text-3xl font-bold mb-6" style={{ color: 'var(--theme-primary)' }}>${product.price} MXN</p>
+                <p className="text-gray-700 mb-4">{product.description}</p>
+                <p className="text-3xl font-bold mb-6" style={{ color: 'var(--theme-primary)' }}>${product.price.amount} {product.price.currency}</p>
                 <button onClick={handleAddToCartClick} className="btn-secondary w-full font-bold py-3 rounded-full">
                     Añadir al Carrito
                 </button>
@@ -1,21 +1,30 @@
 // src/components/RestaurantMenu.js
 
 import { useState, useEffect } from 'react';
-
-// Datos de ejemplo para el menú
-const menuItems = [
-    { name: 'Cochinita Pibil', price: 250 },
-    { name: 'Sopa de Lima', price: 150 },
-    { name: 'Poc Chuc', price: 220 },
-    { name: 'Relleno Negro', price: 230 },
-];
+import apiService from '../services/api';
 
 export default function RestaurantMenu({ restaurant }) {
+    const [menu, setMenu] = useState([]);
+    const [isLoading, setIsLoading] = useState(true);
+    const [error, setError] = useState(null);
     const [order, setOrder] = useState([]);
     const [total, setTotal] = useState(0);
+    const [isSubmitting, setIsSubmitting] = useState(false);
+
+    useEffect(() => {
+        setIsLoading(true);
+        setError(null);
+        apiService.getRestaurantMenu(restaurant.id)
+            .then(data => setMenu(data.menu || []))
+            .catch(err => {
+                console.error("Error al obtener el menú:", err);
+                setError('No se pudo cargar el menú. Intenta de nuevo más tarde.');
+            })
+            .finally(() => setIsLoading(false));
+    }, [restaurant.id]);
 
     useEffect(() => {
-        const newTotal = order.reduce((sum, item) => sum + item.price, 0);
+        const newTotal = order.reduce((sum, item) => sum + parseFloat(item.price.amount), 0);
         setTotal(newTotal);
     }, [order]);
 
@@ -27,9 +36,36 @@ export default function RestaurantMenu({ restaurant }) {
         setOrder(prevOrder => prevOrder.filter((_, index) => index !== indexToRemove));
     };
 
-    const handleSubmit = (e) => {
+    const handleSubmit = async (e) => {
         e.preventDefault();
-        alert(`Pedido para ${restaurant.name} por $${total.toFixed(2)} MXN listo para proceder al pago (simulación).`);
+
+        if (!localStorage.getItem('auth_token')) {
+            alert('Debes iniciar sesión para hacer un pedido.');
+            return;
+        }
+
+        // Agrupar platillos repetidos: el servidor recibe IDs y cantidades
+        const quantities = order.reduce((acc, item) => {
+            acc[item.id] = (acc[item.id] || 0) + 1;
+            return acc;
+        }, {});
+        const items = Object.entries(quantities).map(([id, quantity]) => ({
+            menu_item_id: parseInt(id, 10),
+            quantity,
+        }));
+
+        setIsSubmitting(true);
+        try {
+            const result = await apiService.createRestaurantOrder(restaurant.id, { items });
+            const placed = result.order;
+            alert(`¡Pedido ${placed.reference} enviado a ${restaurant.name}! Total: $${placed.total_price.amount} ${placed.total_price.currency}`);
+            setOrder([]);
+        } catch (err) {
+            console.error("Error al crear el pedido:", err);
+            alert(err.message);
+        } finally {
+            setIsSubmitting(false);
+        }
     };
 
     return (
@@ -37,14 +73,31 @@ export default function RestaurantMenu({ restaurant }) {
             <h2 className="text-3xl font-bold mb-4">Menú - {restaurant.name}</h2>
             
             {/* Lista del Menú */}
-            <div className="space-y-3 mb-6">
-                {menuItems.map((item, index) => (
-                    <div key={index} className="flex justify-between items-center border-b pb-2">
-                        <div>
-                            <h4 className="font-bold">{item.name}</h4>
-                            <p className="text-sm text-gray-600">${item.price.toFixed(2)} MXN</p>
+            <div className="space-y-6 mb-6">
+                {isLoading && <p className="text-gray-500">Cargando menú...</p>}
+                {error && <p className="text-red-500">{error}</p>}
+                {!isLoading && !error && menu.length === 0 && (
+                    <p className="text-gray-500">Este restaurante aún no tiene platillos en su menú.</p>
+                )}
+                {menu.map(section => (
+                    <div key={section.category}>
+                        <h3 className="font-bold text-lg mb-2">{section.category}</h3>
+                        <div className="space-y-3">
+                            {section.items.map(item => (
+                                <div key={item.id} className="flex justify-between items-center border-b pb-2">
+                                    <div>
+                                        <h4 className="font-bold">{item.name}</h4>
+                                        {item.description && <p className="text-xs text-gray-500">{item.description}</p>}
+                                        <p className="text-sm text-gray-600">${item.price.amount} {item.price.currency}</p>
+                                    </div>
+                                    {item.is_available ? (
+                                        <button onClick={() => handleAddItem(item)} className="btn-primary text-xs font-bold py-1 px-3 rounded-full">Añadir</button>
+                                    ) : (
+                                        <span className="text-xs text-gray-400 font-bold">Agotado</span>
+                                    )}
+                                </div>
+                            ))}
                         </div>
-                        <button onClick={() => handleAddItem(item)} className="btn-primary text-xs font-bold py-1 px-3 rounded-full">Añadir</button>
                     </div>
                 ))}
             </div>
@@ -60,7 +113,7 @@ export default function RestaurantMenu({ restaurant }) {
                             <div key={index} className="flex justify-between items-center text-sm">
                                 <span>{item.name}</span>
                                 <div className="flex items-center gap-4">
-                                  <span>${item.price.toFixed(2)}</span>
+                                  <span>${item.price.amount}</span>
                                   <button onClick={() => handleRemoveItem(index)} className="text-red-500 hover:text-red-700 font-bold">Quitar</button>
                                 </div>
                             </div>
@@ -72,7 +125,9 @@ export default function RestaurantMenu({ restaurant }) {
                 </div>
             </div>
 
-             <button onClick={handleSubmit} className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-6">Proceder al Pago</button>
+             <button onClick={handleSubmit} disabled={order.length === 0 || isSubmitting} className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-6">
+                {isSubmitting ? 'Enviando pedido...' : 'Enviar Pedido'}
+             </button>
         </div>
     );
-}
\ No newline at end of file
+}
@@ -1,77 +1,71 @@
 // src/components/TourBookingForm.js
 
-import { useState, useEffect } from 'react';
+import useExperienceBooking, { toHistoryRecord } from '../hooks/useExperienceBooking';
+import DepartureSelect from './DepartureSelect';
 
 export default function TourBookingForm({ experience, onConfirm }) {
-    const [tourDate, setTourDate] = useState('');
-    const [adults, setAdults] = useState(1);
-    const [children, setChildren] = useState(0);
-    const [total, setTotal] = useState(0);
+    const booking = useExperienceBooking(experience);
 
-    const pricePerAdult = experience.price || 1200;
-    const pricePerChild = pricePerAdult * 0.6; // Los niños pagan el 60%
+    // Los adultos (o adultos mayores) son obligatorios; las tarifas las define el servidor
+    const hasAdult = booking.fares.some(f => !f.requires_adult && (booking.quantities[f.fare_type] || 0) > 0);
 
-    // Efecto para recalcular el total
-    useEffect(() => {
-        const newTotal = (adults * pricePerAdult) + (children * pricePerChild);
-        setTotal(newTotal);
-    }, [adults, children, pricePerAdult, pricePerChild]);
-
-    const handleSubmit = (e) => {
+    const handleSubmit = async (e) => {
         e.preventDefault();
-        if (adults < 1) {
+        if (!hasAdult) {
             alert('Debe haber al menos un adulto en la reserva.');
             return;
         }
-        const bookingData = {
-            name: experience.name,
-            total: total,
-            personas: parseInt(adults, 10) + parseInt(children, 10),
-            details: `${adults} adulto(s), ${children} niño(s) para el ${tourDate}`
-        };
-        onConfirm('experience', bookingData);
+        try {
+            const created = await booking.submitBooking();
+            onConfirm('experience', toHistoryRecord(experience, created));
+        } catch (err) {
+            alert(err.message);
+        }
     };
 
+    if (booking.isLoading) {
+        return <p className="text-gray-500">Cargando salidas...</p>;
+    }
+
+    if (booking.error) {
+        return <p className="text-red-500">{booking.error}</p>;
+    }
+
     return (
         <div>
             <h2 className="text-3xl font-bold mb-4">Reservar {experience.name}</h2>
             <form onSubmit={handleSubmit}>
-                <div className="mb-4">
-                    <label className="block text-gray-700 font-semibold">Fecha del Tour</label>
-                    <input 
-                        type="date" 
-                        value={tourDate} 
-                        onChange={(e) => setTourDate(e.target.value)} 
-                        className="w-full p-2 border rounded-lg mt-1" 
-                        required 
-                    />
-                </div>
+                <DepartureSelect
+                    label="Fecha del Tour"
+                    departures={booking.departures}
+                    value={booking.departureId}
+                    onChange={booking.setDepartureId}
+                />
                 <div className="grid grid-cols-2 gap-4 mb-4">
-                    <div>
-                        <label className="block text-gray-700 font-semibold">Adultos</label>
-                        <input 
-                            type="number" 
-                            value={adults} 
-                            onChange={(e) => setAdults(e.target.value)} 
-                            min="1" 
-                            className="w-full p-2 border rounded-lg mt-1" 
-                        />
-                    </div>
-                    <div>
-                        <label className="block text-gray-700 font-semibold">Niños</label>
-                        <input 
-                            type="number" 
-                            value={children} 
-                            onChange={(e) => setChildren(e.target.value)} 
-                            min="0" 
-                            className="w-full p-2 border rounded-lg mt-1" 
-                        />
-                    </div>
+                    {booking.fares.map(fare => (
+                        <div key={fare.fare_type}>
+                            <label className="block text-gray-700 font-semibold">{fare.label}</label>
+                            <p className="text-xs text-gray-500">${fare.price.amount} {fare.price.currency}</p>
+                            <input 
+                                type="number" 
+                                value={booking.quantities[fare.fare_type] || 0} 
+                                onChange={(e) => booking.setQuantity(fare.fare_type, e.target.value)} 
+                                min="0" 
+                                className="w-full p-2 border rounded-lg mt-1" 
+                            />
+                        </div>
+                    ))}
                 </div>
                 <div className="mt-6 bg-gray-100 p-4 rounded-lg text-right">
-                    <div className="font-bold text-2xl">Total: ${total.toFixed(2)} MXN</div>
+                    <div className="font-bold text-2xl">Total: ${booking.total.toFixed(2)} MXN</div>
                 </div>
-                <button type="submit" className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4">Confirmar Reserva</button>
+                <button 
+                    type="submit" 
+                    disabled={booking.isSubmitting || booking.participants === 0 || !booking.departureId} 
+                    className="btn-primary w-full font-bold py-3 px-4 rounded-full mt-4"
+                >
+                    {booking.isSubmitting ? 'Reservando...' : 'Confirmar Reserva'}
+                </button>
             </form>
         </div>
     );
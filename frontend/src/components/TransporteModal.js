@@ -1,25 +1,132 @@
 // src/components/TransporteModal.js
 
-export default function TransporteModal() {
-    const handleSubmit = (e) => {
+import { useEffect, useState } from 'react';
+import apiService from '../services/api';
+
+// Puntos frecuentes con sus coordenadas; la tarifa la calcula el servidor
+const PLACES = [
+    { address: 'Aeropuerto Internacional de Cancún', lat: 21.0365, lng: -86.8771 },
+    { address: 'Zona Hotelera, Cancún', lat: 21.1333, lng: -86.7460 },
+    { address: 'Centro de Cancún', lat: 21.1619, lng: -86.8515 },
+    { address: 'Puerto Morelos', lat: 20.8467, lng: -86.8755 },
+    { address: 'Playa del Carmen', lat: 20.6296, lng: -87.0739 },
+    { address: 'Tulum', lat: 20.2114, lng: -87.4654 },
+];
+
+export default function TransporteModal({ isLoggedIn }) {
+    const [originIndex, setOriginIndex] = useState(0);
+    const [destinationIndex, setDestinationIndex] = useState(1);
+    const [estimate, setEstimate] = useState(null);
+    const [ride, setRide] = useState(null);
+    const [message, setMessage] = useState('');
+    const [isSubmitting, setIsSubmitting] = useState(false);
+
+    const origin = PLACES[originIndex];
+    const destination = PLACES[destinationIndex];
+
+    useEffect(() => {
+        setEstimate(null);
+        setMessage('');
+        if (originIndex === destinationIndex) {
+            return;
+        }
+        apiService.estimateRide({ origin, destination })
+            .then(data => setEstimate(data.fare))
+            .catch(error => setMessage(error.message));
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [originIndex, destinationIndex]);
+
+    const handleSubmit = async (e) => {
         e.preventDefault();
-        alert('Buscando conductor... (simulación)');
+        setIsSubmitting(true);
+        try {
+            const data = await apiService.createRide({ origin, destination });
+            setRide(data.ride);
+            setMessage(data.message);
+        } catch (error) {
+            setMessage(`❌ ${error.message}`);
+        } finally {
+            setIsSubmitting(false);
+        }
     };
 
+    const handleRetry = async () => {
+        try {
+            const data = await apiService.retryRideAssignment(ride.id);
+            setRide({ ...ride, driver: data.driver, status: data.driver ? 'assigned' : ride.status });
+            setMessage(data.message);
+        } catch (error) {
+            setMessage(`❌ ${error.message}`);
+        }
+    };
+
+    const handleCancel = async () => {
+        try {
+            await apiService.cancelRide(ride.id);
+            setRide({ ...ride, status: 'cancelled' });
+            setMessage('Viaje cancelado');
+        } catch (error) {
+            setMessage(`❌ ${error.message}`);
+        }
+    };
+
+    if (ride) {
+        return (
+            <div>
+                <h3 className="text-2xl font-bold mb-4">Viaje {ride.reference}</h3>
+                <p className="mb-2">{ride.origin.address} → {ride.destination.address}</p>
+                <p className="mb-2 font-semibold">Tarifa: ${ride.fare.total.amount} {ride.fare.total.currency}</p>
+                {ride.driver && (
+                    <p className="mb-2">Conductor: {ride.driver.name} · {ride.driver.vehicle} ({ride.driver.plate})</p>
+                )}
+                {message && <p className="text-sm text-gray-600 mb-4">{message}</p>}
+                {ride.status === 'requested' && (
+                    <button onClick={handleRetry} className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-2">
+                        Buscar conductor de nuevo
+                    </button>
+                )}
+                {(ride.status === 'requested' || ride.status === 'assigned') && (
+                    <button onClick={handleCancel} className="w-full text-red-500 hover:text-red-700 font-bold py-2 mt-2">
+                        Cancelar viaje
+                    </button>
+                )}
+            </div>
+        );
+    }
+
     return (
         <div>
             <h3 className="text-2xl font-bold mb-4">Solicitar Viaje Certificado</h3>
             <form onSubmit={handleSubmit}>
                 <div className="mb-4">
                     <label className="block text-gray-700 font-semibold">Mi ubicación</label>
-                    <input type="text" className="w-full p-2 border rounded-lg mt-1 bg-gray-100" value="Aeropuerto Internacional de Cancún" readOnly />
+                    <select value={originIndex} onChange={(e) => setOriginIndex(Number(e.target.value))} className="w-full p-2 border rounded-lg mt-1">
+                        {PLACES.map((place, index) => <option key={place.address} value={index}>{place.address}</option>)}
+                    </select>
                 </div>
                 <div className="mb-4">
                     <label className="block text-gray-700 font-semibold">Destino</label>
-                    <input type="text" className="w-full p-2 border rounded-lg mt-1" placeholder="Escribe la dirección o nombre del hotel" required />
+                    <select value={destinationIndex} onChange={(e) => setDestinationIndex(Number(e.target.value))} className="w-full p-2 border rounded-lg mt-1">
+                        {PLACES.map((place, index) => <option key={place.address} value={index}>{place.address}</option>)}
+                    </select>
                 </div>
-                <button type="submit" className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-2">Buscar Conductor</button>
+                {estimate && (
+                    <div className="mb-4 p-3 bg-gray-100 rounded-lg text-sm">
+                        <p>{estimate.distance_km} km · {estimate.duration_minutes} min aprox.</p>
+                        <p>Banderazo ${estimate.base_fare.amount} + distancia ${estimate.distance_fare.amount} + tiempo ${estimate.time_fare.amount}</p>
+                        <p className="font-bold text-lg mt-1">Total estimado: ${estimate.total.amount} {estimate.total.currency}</p>
+                    </div>
+                )}
+                {message && <p className="text-sm text-red-500 mb-2">{message}</p>}
+                {!isLoggedIn && <p className="text-sm text-gray-500 mb-2">Inicia sesión para solicitar el viaje.</p>}
+                <button
+                    type="submit"
+                    disabled={!estimate || !isLoggedIn || isSubmitting}
+                    className="btn-secondary w-full font-bold py-3 px-4 rounded-full mt-2 disabled:bg-gray-400 disabled:shadow-none disabled:cursor-not-allowed"
+                >
+                    {isSubmitting ? 'Buscando conductor...' : 'Buscar Conductor'}
+                </button>
             </form>
         </div>
     );
-}
\ No newline at end of file
+}
@@ -0,0 +1,101 @@
+// src/hooks/useExperienceBooking.js
+
+import { useState, useEffect, useMemo } from 'react';
+import apiService from '../services/api';
+
+// Carga tarifas y salidas de una experiencia y envía la reserva al servidor.
+// El total que se muestra es solo una vista previa: el servidor calcula el precio final.
+export default function useExperienceBooking(experience) {
+    const [fares, setFares] = useState([]);
+    const [departures, setDepartures] = useState([]);
+    const [details, setDetails] = useState(null);
+    const [departureId, setDepartureId] = useState('');
+    const [quantities, setQuantities] = useState({});
+    const [isLoading, setIsLoading] = useState(true);
+    const [isSubmitting, setIsSubmitting] = useState(false);
+    const [error, setError] = useState(null);
+
+    useEffect(() => {
+        setIsLoading(true);
+        setError(null);
+        apiService.getExperience(experience.id)
+            .then(data => {
+                setDetails(data.experience);
+                setFares(data.fares || []);
+                setDepartures(data.departures || []);
+                if (data.departures && data.departures.length > 0) {
+                    setDepartureId(String(data.departures[0].id));
+                }
+            })
+            .catch(err => {
+                console.error("Error al obtener la experiencia:", err);
+                setError('No se pudo cargar la información de la experiencia.');
+            })
+            .finally(() => setIsLoading(false));
+    }, [experience.id]);
+
+    const setQuantity = (fareType, value) => {
+        const quantity = Math.max(0, parseInt(value, 10) || 0);
+        setQuantities(prev => ({ ...prev, [fareType]: quantity }));
+    };
+
+    const participants = Object.values(quantities).reduce((sum, q) => sum + q, 0);
+
+    const total = useMemo(() => fares.reduce(
+        (sum, fare) => sum + (quantities[fare.fare_type] || 0) * parseFloat(fare.price.amount),
+        0
+    ), [fares, quantities]);
+
+    const selectedDeparture = departures.find(d => String(d.id) === departureId);
+
+    const submitBooking = async (specialRequests) => {
+        if (!localStorage.getItem('auth_token')) {
+            throw new Error('Debes iniciar sesión para reservar.');
+        }
+
+        const selectedFares = Object.entries(quantities)
+            .filter(([, quantity]) => quantity > 0)
+            .map(([fare_type, quantity]) => ({ fare_type, quantity }));
+
+        setIsSubmitting(true);
+        try {
+            const result = await apiService.createExperienceBooking(experience.id, {
+                departure_id: parseInt(departureId, 10),
+                fares: selectedFares,
+                special_requests: specialRequests || null,
+            });
+            return result.booking;
+        } finally {
+            setIsSubmitting(false);
+        }
+    };
+
+    return {
+        details,
+        fares,
+        departures,
+        departureId,
+        setDepartureId,
+        selectedDeparture,
+        quantities,
+        setQuantity,
+        participants,
+        total,
+        isLoading,
+        isSubmitting,
+        error,
+        submitBooking,
+    };
+}
+
+// Convierte la reserva del servidor al formato que usa el historial de App
+export function toHistoryRecord(experience, booking) {
+    const summary = booking.fares.map(f => `${f.quantity} ${f.label}`).join(', ');
+    return {
+        name: experience.name,
+        reference: booking.reference,
+        total: parseFloat(booking.total_price.amount),
+        personas: booking.participants,
+        details: `${summary} para el ${booking.departure_date} a las ${booking.departure_time.slice(0, 5)}`,
+    };
+}
@@ -2,54 +2,102 @@
 
 import { useState, useEffect } from 'react';
 import { Link } from 'react-router-dom';
+import apiService from '../services/api';
 
-// Importamos las imágenes locales
-import huipil1 from '../assets/huipil-1.jpg';
-import huipil2 from '../assets/huipil-2.jpg';
-import huipil3 from '../assets/huipil-3.jpg';
-import vasija1 from '../assets/vasija-1.jpg';
-import vasija2 from '../assets/vasija-2.jpg';
-import aretes1 from '../assets/aretes-1.jpg';
-
-// Usamos los datos con las imágenes locales
-const allProducts = [
-    { id: 1, name: 'Huipil Ceremonial', artisan: 'Elena Poot', price: 1800, category: 'textil', images: [huipil1, huipil2, huipil3], desc: 'Tejido a mano con técnicas ancestrales...' },
-    { id: 2, name: 'Vasija de Sac-bé', artisan: 'Mateo Cruz', price: 950, category: 'ceramica', images: [vasija1, vasija2], desc: 'Cerámica de alta temperatura...' },
-    { id: 3, name: 'Aretes de Filigrana', artisan: 'Isabel Chi', price: 1200, category: 'joyeria', images: [aretes1], desc: 'Elegantes aretes de plata...' },
+const categories = [
+    { value: 'all', label: 'Todas' },
+    { value: 'textil', label: 'Textiles' },
+    { value: 'ceramica', label: 'Cerámica' },
+    { value: 'joyeria', label: 'Joyería' },
 ];
 
 export default function ArtesanosPage() {
-    // El resto del componente no necesita los datos del backend por ahora, usamos los locales
-    const [filteredProducts, setFilteredProducts] = useState(allProducts);
+    // El catálogo, los filtros y la paginación vienen del backend
+    const [products, setProducts] = useState([]);
     const [searchTerm, setSearchTerm] = useState('');
     const [selectedCategory, setSelectedCategory] = useState('all');
+    const [page, setPage] = useState(1);
+    const [totalPages, setTotalPages] = useState(1);
+    const [loading, setLoading] = useState(true);
+    const [error, setError] = useState(null);
 
+    // Al cambiar los filtros se vuelve a la primera página
     useEffect(() => {
-        let products = allProducts.filter(p => 
-            (selectedCategory === 'all' || p.category === selectedCategory) &&
-            (p.name.toLowerCase().includes(searchTerm.toLowerCase()) || p.artisan.toLowerCase().includes(searchTerm.toLowerCase()))
-        );
-        setFilteredProducts(products);
+        setPage(1);
     }, [searchTerm, selectedCategory]);
 
+    useEffect(() => {
+        let cancelled = false;
+        setLoading(true);
+
+        apiService.getProductos({ category: selectedCategory, q: searchTerm.trim(), page })
+            .then(data => {
+                if (cancelled) return;
+                setProducts(data.products);
+                setTotalPages(Math.max(data.total_pages, 1));
+                setError(null);
+            })
+            .catch(err => {
+                if (!cancelled) setError(err.message);
+            })
+            .finally(() => {
+                if (!cancelled) setLoading(false);
+            });
+
+        return () => { cancelled = true; };
+    }, [searchTerm, selectedCategory, page]);
+
     return (
         <div className="container mx-auto px-6 py-16">
             <h2 className="text-4xl font-bold mb-2 text-center">Corazón Maya</h2>
-            {/* ... resto del JSX de la página de artesanos ... */}
+            <div className="flex flex-col md:flex-row gap-4 justify-center my-8">
+                <input
+                    type="text"
+                    value={searchTerm}
+                    onChange={(e) => setSearchTerm(e.target.value)}
+                    placeholder="Buscar por pieza o artesano..."
+                    className="w-full md:w-80 p-2 border rounded-md"
+                />
+                <select
+                    value={selectedCategory}
+                    onChange={(e) => setSelectedCategory(e.target.value)}
+                    className="p-2 border rounded-md"
+                >
+                    {categories.map(c => <option key={c.value} value={c.value}>{c.label}</option>)}
+                </select>
+            </div>
+
+            {error && <p className="text-center text-red-500 mb-6">{error}</p>}
+            {!loading && !error && products.length === 0 && (
+                <p className="text-center text-gray-500">No encontramos piezas con esos filtros.</p>
+            )}
+
             <div className="grid md:grid-cols-2 lg:grid-cols-4 gap-8">
-                {filteredProducts.map(product => (
-                    // --- AQUÍ ESTÁ EL CAMBIO ---
+                {products.map(product => (
                     // El prop 'state' envía el objeto 'product' completo a la página de detalle
                     <Link to={`/artesanos/${product.id}`} state={{ product: product }} key={product.id} className="card bg-white rounded-xl shadow-lg overflow-hidden block">
                         <img src={product.images[0]} alt={product.name} className="w-full h-56 object-cover" />
                         <div className="p-4">
                             <h3 className="font-bold">{product.name}</h3>
                             <p className="text-sm text-gray-500">Por {product.artisan}</p>
-                            <p className="font-bold mt-2" style={{ color: 'var(--theme-primary)' }}>${product.price} MXN</p>
+                            <p className="font-bold mt-2" style={{ color: 'var(--theme-primary)' }}>${product.price.amount} {product.price.currency}</p>
+                            {!product.in_stock && <p className="text-xs text-red-500 mt-1">Agotado</p>}
                         </div>
                     </Link>
                 ))}
             </div>
+
+            {totalPages > 1 && (
+                <div className="flex justify-center items-center gap-4 mt-10">
+                    <button onClick={() => setPage(p => p - 1)} disabled={page === 1} className="px-4 py-2 border rounded-md disabled:opacity-50">
+                        Anterior
+                    </button>
+                    <span className="text-gray-600">Página {page} de {totalPages}</span>
+                    <button onClick={() => setPage(p => p + 1)} disabled={page === totalPages} className="px-4 py-2 border rounded-md disabled:opacity-50">
+                        Siguiente
+                    </button>
+                </div>
+            )}
         </div>
     );
-}
\ No newline at end of file
+}
@@ -29,7 +29,9 @@ export default function ExperienciasPage({ onExperienceClick }) {
                         <div className="p-6">
                             <h3 className="text-2xl font-bold mb-2">{exp.name}</h3>
                             <div className="flex justify-between items-center mt-4">
-                                <span className="text-xl font-bold" style={{ color: 'var(--theme-primary)' }}>${exp.price} MXN</span>
+                                <span className="text-xl font-bold" style={{ color: 'var(--theme-primary)' }}>
+                                    {exp.price ? `Desde $${exp.price.amount} ${exp.price.currency}` : 'Próximamente'}
+                                </span>
                                 <button onClick={() => onExperienceClick(exp)} className="btn-primary font-bold py-2 px-4 rounded-full">Reservar</button>
                             </div>
                         </div>
@@ -1,20 +1,35 @@
 // src/pages/ProductDetailPage.js
 
 import { useState, useEffect } from 'react';
-import { useLocation, Link } from 'react-router-dom';
+import { useLocation, useParams, Link } from 'react-router-dom';
+import apiService from '../services/api';
 
-export default function ProductDetailPage() {
+export default function ProductDetailPage({ onAddToCart }) {
     const location = useLocation();
-    const { product } = location.state; // Obtenemos el producto enviado desde la página anterior
+    const { productId } = useParams();
+    // Se muestra de inmediato lo que envió el catálogo y se refresca con el inventario actual
+    const [product, setProduct] = useState(location.state?.product || null);
+    const [loading, setLoading] = useState(!location.state?.product);
 
     const [activeImage, setActiveImage] = useState('');
 
+    useEffect(() => {
+        apiService.getProducto(productId)
+            .then(setProduct)
+            .catch(() => setProduct(null))
+            .finally(() => setLoading(false));
+    }, [productId]);
+
     useEffect(() => {
         if (product && product.images && product.images.length > 0) {
             setActiveImage(product.images[0]);
         }
     }, [product]);
 
+    if (loading) {
+        return <div className="text-center py-20">Cargando producto...</div>;
+    }
+
     if (!product) {
         return <div className="text-center py-20">Producto no encontrado.</div>;
     }
@@ -45,13 +60,16 @@ export default function ProductDetailPage() {
                 <div>
                     <h1 className="text-4xl md:text-5xl font-bold">{product.name}</h1>
                     <p className="text-lg text-gray-500 mb-4">Por {product.artisan}</p>
-                    <p className="text-3xl font-bold my-6 text-theme-primary">${product.price} MXN</p>
-                    <p className="text-gray-700 mb-6 leading-relaxed">{product.desc}</p>
-                    <button onClick={() => alert('Añadido al carrito (simulación)')} className="btn-secondary w-full max-w-sm font-bold py-3 rounded-full">
+                    <p className="text-3xl font-bold my-6 text-theme-primary">${product.price.amount} {product.price.currency}</p>
+                    <p className="text-gray-700 mb-6 leading-relaxed">{product.description}</p>
+                    <p className="text-sm text-gray-500 mb-4">
+                        {product.in_stock ? `${product.stock} pieza(s) disponibles` : 'Agotado'}
+                    </p>
+                    <button onClick={() => onAddToCart(product)} disabled={!product.in_stock} className="btn-secondary w-full max-w-sm font-bold py-3 rounded-full disabled:bg-gray-400 disabled:cursor-not-allowed">
                         Añadir al Carrito
                     </button>
                 </div>
             </div>
         </div>
     );
-}
\ No newline at end of file
+}
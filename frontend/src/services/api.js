@@ -2,6 +2,42 @@
 
 const API_BASE_URL = 'http://127.0.0.1:8080/api';
 
+// Petición autenticada con JSON; lanza el mensaje de error del backend
+const authRequest = async (method, path, body) => {
+    const token = localStorage.getItem('auth_token');
+    const response = await fetch(`${API_BASE_URL}${path}`, {
+        method,
+        headers: {
+            'Content-Type': 'application/json',
+            'Authorization': `Bearer ${token}`,
+        },
+        body: body ? JSON.stringify(body) : undefined,
+    });
+
+    const data = await response.json();
+    if (!response.ok) {
+        throw new Error(data.error || 'Error en la solicitud');
+    }
+
+    return data;
+};
+
+// Petición pública con JSON (huéspedes sin cuenta); lanza el mensaje de error del backend
+const publicRequest = async (method, path, body) => {
+    const response = await fetch(`${API_BASE_URL}${path}`, {
+        method,
+        headers: { 'Content-Type': 'application/json' },
+        body: body ? JSON.stringify(body) : undefined,
+    });
+
+    const data = await response.json();
+    if (!response.ok) {
+        throw new Error(data.error || 'Error en la solicitud');
+    }
+
+    return data;
+};
+
 // Servicio para manejar todas las llamadas al API
 const apiService = {
     // === OBTENER DATOS ===
@@ -10,18 +46,69 @@ const apiService = {
         return response.json();
     },
 
+    getHotelAvailability: async ({ checkIn, checkOut, guests, rooms, location }) => {
+        const params = new URLSearchParams({
+            check_in: checkIn,
+            check_out: checkOut,
+            guests: guests,
+            rooms: rooms,
+        });
+        if (location) {
+            params.append('location', location);
+        }
+
+        const response = await fetch(`${API_BASE_URL}/hotels/availability?${params.toString()}`);
+        return response.json();
+    },
+
+    getHotelAddons: async (hotelId) => {
+        const response = await fetch(`${API_BASE_URL}/hotels/${hotelId}/addons`);
+        return response.json();
+    },
+
     getRestaurantes: async () => {
         const response = await fetch(`${API_BASE_URL}/restaurantes`);
         return response.json();
     },
 
+    getRestaurantMenu: async (restaurantId) => {
+        const response = await fetch(`${API_BASE_URL}/restaurantes/${restaurantId}`);
+        if (!response.ok) {
+            throw new Error('Error al obtener el menú del restaurante');
+        }
+        return response.json();
+    },
+
     getExperiencias: async () => {
         const response = await fetch(`${API_BASE_URL}/experiencias`);
         return response.json();
     },
 
-    getProductos: async () => {
-        const response = await fetch(`${API_BASE_URL}/productos`);
+    getExperience: async (experienceId) => {
+        const response = await fetch(`${API_BASE_URL}/experiencias/${experienceId}`);
+        if (!response.ok) {
+            throw new Error('Error al obtener la experiencia');
+        }
+        return response.json();
+    },
+
+    // Filtros opcionales: category, q, artisan_id, page, per_page
+    getProductos: async (filters = {}) => {
+        const params = new URLSearchParams(
+            Object.entries(filters).filter(([, value]) => value !== undefined && value !== '' && value !== 'all')
+        );
+        const response = await fetch(`${API_BASE_URL}/productos?${params}`);
+        if (!response.ok) {
+            throw new Error('Error al obtener los productos');
+        }
+        return response.json();
+    },
+
+    getProducto: async (productId) => {
+        const response = await fetch(`${API_BASE_URL}/productos/${productId}`);
+        if (!response.ok) {
+            throw new Error('Producto no encontrado');
+        }
         return response.json();
     },
 
@@ -42,36 +129,44 @@ const apiService = {
         return response.json();
     },
 
-    createExperienceReservation: async (reservationData) => {
-        const response = await fetch(`${API_BASE_URL}/reservations/experience`, {
+    // bookingData: { departure_id, fares: [{ fare_type, quantity }], special_requests }
+    createExperienceBooking: async (experienceId, bookingData) => {
+        const token = localStorage.getItem('auth_token');
+        const response = await fetch(`${API_BASE_URL}/experiences/${experienceId}/bookings`, {
             method: 'POST',
             headers: {
                 'Content-Type': 'application/json',
+                'Authorization': `Bearer ${token}`,
             },
-            body: JSON.stringify(reservationData),
+            body: JSON.stringify(bookingData),
         });
-        
+
+        const data = await response.json();
         if (!response.ok) {
-            throw new Error('Error al crear la reserva de experiencia');
+            throw new Error(data.error || 'Error al crear la reserva de experiencia');
         }
-        
-        return response.json();
+
+        return data;
     },
 
-    createRestaurantOrder: async (orderData) => {
-        const response = await fetch(`${API_BASE_URL}/reservations/restaurant`, {
+    // orderData: { items: [{ menu_item_id, quantity }], notes, reservation_id }
+    createRestaurantOrder: async (restaurantId, orderData) => {
+        const token = localStorage.getItem('auth_token');
+        const response = await fetch(`${API_BASE_URL}/restaurants/${restaurantId}/orders`, {
             method: 'POST',
             headers: {
                 'Content-Type': 'application/json',
+                'Authorization': `Bearer ${token}`,
             },
             body: JSON.stringify(orderData),
         });
-        
+
+        const data = await response.json();
         if (!response.ok) {
-            throw new Error('Error al crear la orden del restaurante');
+            throw new Error(data.error || 'Error al crear la orden del restaurante');
         }
-        
-        return response.json();
+
+        return data;
     },
 
     // === OBTENER HISTORIAL ===
@@ -85,16 +180,182 @@ const apiService = {
         return response.json();
     },
 
-    // === COMPRAS (Por implementar con MongoDB) ===
+    // === COMPRAS DE ARTESANÍAS ===
+    // items: [{ product_id, quantity }]; el precio y el inventario los valida el servidor
     createPurchase: async (purchaseData) => {
-        // Por ahora simulamos, pero esto debería guardar en MongoDB
-        console.log('Compra a guardar:', purchaseData);
-        return {
-            id: `P${Date.now()}`,
-            ...purchaseData,
-            date: new Date().toISOString(),
-            status: 'confirmed'
-        };
+        const token = localStorage.getItem('auth_token');
+        const response = await fetch(`${API_BASE_URL}/product-orders`, {
+            method: 'POST',
+            headers: {
+                'Content-Type': 'application/json',
+                'Authorization': `Bearer ${token}`,
+            },
+            body: JSON.stringify(purchaseData),
+        });
+
+        const data = await response.json();
+        if (!response.ok) {
+            throw new Error(data.error || 'Error al procesar la compra');
+        }
+
+        return data;
+    },
+
+    // === VIAJES (varias reservas en un solo checkout) ===
+    // { name, hotels: [...], experiences: [{ experience_id, departure_id, fares }],
+    //   restaurant_reservations: [{ restaurant_id, reservation_date, slot_time, party_size }],
+    //   products: { items, shipping_address } }
+    createTrip: async (tripData) => {
+        return authRequest('POST', '/trips', tripData);
+    },
+
+    getTrips: async () => {
+        return authRequest('GET', '/trips');
+    },
+
+    // Solo viajes pendientes de pago; libera todo lo apartado
+    cancelTrip: async (tripId, cancellationReason) => {
+        return authRequest('PATCH', `/trips/${tripId}/cancel`, { cancellation_reason: cancellationReason });
+    },
+
+    // El viaje se paga completo; sus reservas no se cobran por separado
+    createTripPaymentIntent: async (tripId) => {
+        return authRequest('POST', `/trips/${tripId}/payment-intent`);
+    },
+
+    // === TRANSPORTE CERTIFICADO ===
+    // origin/destination: { address, lat, lng }
+    estimateRide: async (route) => {
+        const response = await fetch(`${API_BASE_URL}/rides/estimate`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify(route),
+        });
+        const data = await response.json();
+        if (!response.ok) {
+            throw new Error(data.error || 'Error al cotizar el viaje');
+        }
+        return data;
+    },
+
+    createRide: async (rideData) => {
+        return authRequest('POST', '/rides', rideData);
+    },
+
+    getRides: async () => {
+        return authRequest('GET', '/rides');
+    },
+
+    retryRideAssignment: async (rideId) => {
+        return authRequest('POST', `/rides/${rideId}/assign`);
+    },
+
+    cancelRide: async (rideId, cancellationReason) => {
+        return authRequest('PATCH', `/rides/${rideId}/cancel`, { reason: cancellationReason });
+    },
+
+    // === PAGOS ===
+    createPaymentIntent: async (bookingId) => {
+        return authRequest('POST', `/bookings/${bookingId}/payment-intent`);
+    },
+
+    modifyBooking: async (bookingId, changes) => {
+        return authRequest('PUT', `/bookings/${bookingId}`, changes);
+    },
+
+    getBookingByReference: async (reference) => {
+        return authRequest('GET', `/bookings/ref/${encodeURIComponent(reference)}`);
+    },
+
+    lookupBooking: async (reference, email) => {
+        return publicRequest('POST', '/bookings/lookup', { reference, email });
+    },
+
+    // === RESERVAS SIN CUENTA ===
+    createGuestBooking: async (guestBooking) => {
+        return publicRequest('POST', '/guest/bookings', guestBooking);
+    },
+
+    getGuestBooking: async (token) => {
+        return publicRequest('GET', `/guest/bookings/${encodeURIComponent(token)}`);
+    },
+
+    createGuestPaymentIntent: async (token) => {
+        return publicRequest('POST', `/guest/bookings/${encodeURIComponent(token)}/payment-intent`);
+    },
+
+    getGuestRefundPreview: async (token) => {
+        return publicRequest('GET', `/guest/bookings/${encodeURIComponent(token)}/refund-preview`);
+    },
+
+    cancelGuestBooking: async (token, cancellationReason) => {
+        return publicRequest('PATCH', `/guest/bookings/${encodeURIComponent(token)}/cancel`, { cancellation_reason: cancellationReason });
+    },
+
+    resendGuestLink: async (reference, email) => {
+        return publicRequest('POST', '/guest/bookings/resend-link', { reference, email });
+    },
+
+    claimGuestBookings: async (token) => {
+        return authRequest('POST', '/guest/bookings/claim', { token });
+    },
+
+    getRefundPreview: async (bookingId) => {
+        return authRequest('GET', `/bookings/${bookingId}/refund-preview`);
+    },
+
+    cancelBooking: async (bookingId, cancellationReason) => {
+        return authRequest('PATCH', `/bookings/${bookingId}/cancel`, { cancellation_reason: cancellationReason });
+    },
+
+    // === CONDUCTORES ===
+    registerDriver: async (driverData) => {
+        return authRequest('POST', '/driver/profile', driverData);
+    },
+
+    getDriverProfile: async () => {
+        return authRequest('GET', '/driver/profile');
+    },
+
+    updateDriverStatus: async (online, location) => {
+        return authRequest('PATCH', '/driver/status', { online, ...location });
+    },
+
+    acceptRide: async (rideId) => {
+        return authRequest('POST', `/driver/rides/${rideId}/accept`);
+    },
+
+    // === CARRITO (guardado en el servidor) ===
+    getCart: async () => {
+        const token = localStorage.getItem('auth_token');
+        const response = await fetch(`${API_BASE_URL}/cart`, {
+            headers: { 'Authorization': `Bearer ${token}` },
+        });
+        if (!response.ok) {
+            throw new Error('Error al obtener el carrito');
+        }
+        return response.json();
+    },
+
+    addCartItem: async (productId, quantity = 1) => {
+        return authRequest('POST', '/cart/items', { product_id: productId, quantity });
+    },
+
+    updateCartItem: async (productId, quantity) => {
+        return authRequest('PUT', `/cart/items/${productId}`, { quantity });
+    },
+
+    removeCartItem: async (productId) => {
+        return authRequest('DELETE', `/cart/items/${productId}`);
+    },
+
+    clearCart: async () => {
+        return authRequest('DELETE', '/cart');
+    },
+
+    // Cobra el carrito completo; el servidor recalcula precios y aparta inventario
+    checkoutCart: async (checkoutData) => {
+        return authRequest('POST', '/cart/checkout', checkoutData);
     }
 };
 